@@ -0,0 +1,230 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+use log::{error, warn};
+use regex::Regex;
+
+lazy_static! {
+    // Locations embedded in error messages, e.g. "at line 3 column 7"
+    static ref LOCATION_REGEX: Regex = Regex::new(r"\bline (\d+),? column (\d+)\b").unwrap();
+}
+
+// The file being rendered on the current thread, for problems reported by
+// code that does not know which file it is working on (e.g. markdown).
+thread_local! {
+    static CURRENT_FILE: RefCell<PathBuf> = const { RefCell::new(PathBuf::new()) };
+
+    // The collector of the site being built on the current thread
+    static COLLECTOR: RefCell<Option<Diagnostics>> = const { RefCell::new(None) };
+}
+
+/// How serious a problem is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The output was generated but may not be what was intended
+    Warning,
+    /// The output could not be generated
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// The stage of the build a problem comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    /// Front matter parsing
+    FrontMatter,
+    /// Liquid parsing and rendering, including layouts
+    Liquid,
+    /// Markdown conversion
+    Markdown,
+    /// Writing output files
+    Output,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Category::FrontMatter => write!(f, "Front matter"),
+            Category::Liquid => write!(f, "Liquid"),
+            Category::Markdown => write!(f, "Markdown"),
+            Category::Output => write!(f, "Output"),
+        }
+    }
+}
+
+/// A problem found while building the site
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// How serious the problem is
+    pub severity: Severity,
+    /// The stage of the build the problem comes from
+    pub category: Category,
+    /// The source file the problem was found in
+    pub file: PathBuf,
+    /// Line in the source file, if known (1-indexed)
+    pub line: Option<usize>,
+    /// Column in the source file, if known (1-indexed)
+    pub column: Option<usize>,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Create a diagnostic, taking the location from the message if it has one
+    pub fn new(severity: Severity, category: Category, file: &Path, message: impl Into<String>) -> Self {
+        let message = message.into();
+        let (line, column) = match LOCATION_REGEX.captures(&message) {
+            Some(caps) => (caps[1].parse().ok(), caps[2].parse().ok()),
+            None => (None, None),
+        };
+
+        Self {
+            severity,
+            category,
+            file: file.to_path_buf(),
+            line,
+            column,
+            message,
+        }
+    }
+
+    /// The file and location, e.g. `_posts/hello.md:3:7`
+    pub fn location(&self) -> String {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{}:{}:{}", self.file.display(), line, column),
+            (Some(line), None) => format!("{}:{}", self.file.display(), line),
+            _ => self.file.display().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.location(), self.message)
+    }
+}
+
+/// The problems reported while building one site, from any of its threads
+///
+/// Code deep in the build reports through [`report`], which records into the
+/// collector entered on the current thread. Sites enter their collector on
+/// each thread they build on, so sites built at the same time stay apart.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    problems: Arc<Mutex<Vec<Diagnostic>>>,
+}
+
+/// A collector entered on the current thread, left again when dropped
+pub struct Scope {
+    previous: Option<Diagnostics>,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        COLLECTOR.with(|collector| *collector.borrow_mut() = self.previous.take());
+    }
+}
+
+impl Diagnostics {
+    /// The collector entered on the current thread, to enter on worker threads
+    pub fn current() -> Option<Self> {
+        COLLECTOR.with(|collector| collector.borrow().clone())
+    }
+
+    /// Record the problems reported on the current thread until the scope is dropped
+    pub fn enter(&self) -> Scope {
+        let previous = COLLECTOR.with(|collector| collector.borrow_mut().replace(self.clone()));
+        Scope { previous }
+    }
+
+    /// Take the problems recorded so far
+    pub fn take(&self) -> Vec<Diagnostic> {
+        std::mem::take(&mut *self.problems.lock().unwrap())
+    }
+}
+
+/// Record a problem in the current collector and log it
+pub fn report(diagnostic: Diagnostic) {
+    match diagnostic.severity {
+        Severity::Error => error!("{} error in {}: {}", diagnostic.category, diagnostic.location(), diagnostic.message),
+        Severity::Warning => warn!("{} warning in {}: {}", diagnostic.category, diagnostic.location(), diagnostic.message),
+    }
+
+    if let Some(collector) = Diagnostics::current() {
+        collector.problems.lock().unwrap().push(diagnostic);
+    }
+}
+
+/// Record an error in a source file
+pub fn error(category: Category, file: &Path, message: impl Into<String>) {
+    report(Diagnostic::new(Severity::Error, category, file, message));
+}
+
+/// Record a warning in a source file
+pub fn warning(category: Category, file: &Path, message: impl Into<String>) {
+    report(Diagnostic::new(Severity::Warning, category, file, message));
+}
+
+/// Set the file being rendered on the current thread
+pub fn set_current_file(path: &Path) {
+    CURRENT_FILE.with(|current| *current.borrow_mut() = path.to_path_buf());
+}
+
+/// The file being rendered on the current thread
+pub fn current_file() -> PathBuf {
+    CURRENT_FILE.with(|current| current.borrow().clone())
+}
+
+/// Count the errors among a set of diagnostics
+pub fn error_count(diagnostics: &[Diagnostic]) -> usize {
+    diagnostics.iter().filter(|d| d.severity == Severity::Error).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostic_location_from_message() {
+        let content = "---\ntitle: Hello\ntags: [a\n---\nBody";
+        let err = crate::front_matter::extract_front_matter(content).unwrap_err();
+        let diagnostic = Diagnostic::new(Severity::Error, Category::FrontMatter, Path::new("post.md"), err.to_string());
+        assert_eq!(diagnostic.line, Some(4));
+        assert!(diagnostic.location().starts_with("post.md:4:"));
+
+        let diagnostic = Diagnostic::new(Severity::Warning, Category::Markdown, Path::new("page.md"), "no location");
+        assert_eq!(diagnostic.location(), "page.md");
+    }
+
+    #[test]
+    fn test_collectors() {
+        let outer = Diagnostics::default();
+        let inner = Diagnostics::default();
+        {
+            let _outer = outer.enter();
+            warning(Category::Markdown, Path::new("a.md"), "first");
+            {
+                let _inner = inner.enter();
+                error(Category::Liquid, Path::new("b.md"), "second");
+            }
+            warning(Category::Markdown, Path::new("c.md"), "third");
+        }
+        warning(Category::Markdown, Path::new("d.md"), "not collected");
+
+        let messages = |collector: &Diagnostics| collector.take().into_iter().map(|d| d.message).collect::<Vec<_>>();
+        assert_eq!(messages(&outer), ["first", "third"]);
+        assert_eq!(messages(&inner), ["second"]);
+        assert!(Diagnostics::current().is_none());
+    }
+}
@@ -3,6 +3,9 @@ pub mod page;
 pub mod processor;
 pub mod watcher;
 pub mod types;
+pub mod diagnostics;
+pub mod profiler;
+pub mod render_state;
 
 
 pub use site::build_site;
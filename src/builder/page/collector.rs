@@ -7,6 +7,7 @@ use walkdir::WalkDir;
 use crate::directory::DirectoryStructure;
 use crate::front_matter::FrontMatter;
 use crate::builder::types::BoxResult;
+use crate::builder::diagnostics::{self, Category};
 use crate::builder::page::model::Page;
 use crate::builder::page::utils::determine_output_path;
 
@@ -44,7 +45,7 @@ pub fn collect_pages(dirs: &DirectoryStructure) -> BoxResult<Vec<Page>> {
                                 (true, content, front_matter)
                             },
                             Err(e) => {
-                                error!("Error extracting front matter from {}: {}", path.display(), e);
+                                diagnostics::error(Category::FrontMatter, path, e.to_string());
                                 // Just copy the file if front matter extraction fails
                                 (false, content, FrontMatter::default())
                             }
@@ -82,6 +83,8 @@ pub fn collect_pages(dirs: &DirectoryStructure) -> BoxResult<Vec<Page>> {
                 content,
                 front_matter,
                 process,
+                paginator: None,
+                data: liquid::Object::new(),
             };
             
             pages.push(page);
@@ -1,7 +1,10 @@
 mod model;
 mod collector;
 mod utils;
+mod paginator;
 
 pub use model::Page;
+pub use paginator::Paginator;
 pub use collector::collect_pages;
+pub use utils::output_path_for_url;
  
\ No newline at end of file
@@ -1,7 +1,10 @@
 use std::path::PathBuf;
 use chrono::{DateTime, Utc};
 
+use crate::config::Config;
 use crate::front_matter::FrontMatter;
+use crate::builder::page::Paginator;
+use crate::builder::page::utils::output_path_for_url;
 
 /// A page in the site
 #[derive(Debug, Clone)]
@@ -14,4 +17,27 @@ pub struct Page {
     pub content: String,
     pub front_matter: FrontMatter,
     pub process: bool,
+    pub paginator: Option<Paginator>,
+    pub data: liquid::Object,
+}
+
+impl Page {
+    /// Create a page generated by the site rather than read from a file
+    ///
+    /// The page renders its layout with empty content. `source` is the file
+    /// incremental builds track for it, usually the layout itself.
+    pub fn generated(source: PathBuf, url: String, front_matter: FrontMatter, config: &Config) -> Self {
+        Page {
+            relative_path: PathBuf::from(url.trim_start_matches('/')),
+            output_path: Some(output_path_for_url(&url, &config.destination)),
+            path: source,
+            url: Some(url),
+            date: None,
+            content: String::new(),
+            front_matter,
+            process: true,
+            paginator: None,
+            data: liquid::Object::new(),
+        }
+    }
 } 
\ No newline at end of file
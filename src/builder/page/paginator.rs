@@ -0,0 +1,67 @@
+use liquid::model::Value;
+
+/// The `paginator` object exposed to paginated pages
+///
+/// Field names follow jekyll-paginate, with the extra `first_page*` and
+/// `last_page*` fields from jekyll-paginate-v2.
+#[derive(Debug, Clone, Default)]
+pub struct Paginator {
+    /// Current page number (1-indexed)
+    pub page: usize,
+    /// Number of items per page
+    pub per_page: usize,
+    /// Items on the current page
+    pub posts: Vec<Value>,
+    /// Total number of items across all pages
+    pub total_posts: usize,
+    /// Total number of pages
+    pub total_pages: usize,
+    /// URL of the current page
+    pub page_path: String,
+    /// URL of the first page
+    pub first_page_path: String,
+    /// URL of the last page
+    pub last_page_path: String,
+    /// Previous page number, if any
+    pub previous_page: Option<usize>,
+    /// URL of the previous page, if any
+    pub previous_page_path: Option<String>,
+    /// Next page number, if any
+    pub next_page: Option<usize>,
+    /// URL of the next page, if any
+    pub next_page_path: Option<String>,
+}
+
+impl Paginator {
+    /// Convert the paginator to a Liquid object
+    pub fn to_liquid(&self) -> Value {
+        let mut obj = liquid::Object::new();
+
+        obj.insert("page".into(), Value::scalar(self.page as i64));
+        obj.insert("per_page".into(), Value::scalar(self.per_page as i64));
+        obj.insert("posts".into(), Value::Array(self.posts.clone()));
+        obj.insert("total_posts".into(), Value::scalar(self.total_posts as i64));
+        obj.insert("total_pages".into(), Value::scalar(self.total_pages as i64));
+        obj.insert("page_path".into(), Value::scalar(self.page_path.clone()));
+        obj.insert("first_page".into(), Value::scalar(1i64));
+        obj.insert("first_page_path".into(), Value::scalar(self.first_page_path.clone()));
+        obj.insert("last_page".into(), Value::scalar(self.total_pages as i64));
+        obj.insert("last_page_path".into(), Value::scalar(self.last_page_path.clone()));
+
+        // Missing neighbours are nil so `{% if paginator.next_page %}` works
+        obj.insert("previous_page".into(), self.previous_page
+            .map(|page| Value::scalar(page as i64))
+            .unwrap_or(Value::Nil));
+        obj.insert("previous_page_path".into(), self.previous_page_path.clone()
+            .map(Value::scalar)
+            .unwrap_or(Value::Nil));
+        obj.insert("next_page".into(), self.next_page
+            .map(|page| Value::scalar(page as i64))
+            .unwrap_or(Value::Nil));
+        obj.insert("next_page_path".into(), self.next_page_path.clone()
+            .map(Value::scalar)
+            .unwrap_or(Value::Nil));
+
+        Value::Object(obj)
+    }
+}
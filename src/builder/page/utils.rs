@@ -40,6 +40,20 @@ pub fn determine_output_path(
     Some(output_path)
 }
 
+/// Map a URL to the file it is written to in the destination
+///
+/// URLs ending in a slash or without an extension are directories, like
+/// Jekyll's `/page2/`, and get an `index.html`.
+pub fn output_path_for_url(url: &str, destination: &Path) -> PathBuf {
+    let relative = url.trim_start_matches('/');
+    
+    if url.ends_with('/') || Path::new(relative).extension().is_none() {
+        destination.join(relative).join("index.html")
+    } else {
+        destination.join(relative)
+    }
+}
+
 /// Check if a file is a markdown file based on extension
 fn is_markdown_file(path: &Path) -> bool {
     if let Some(ext) = path.extension() {
@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::builder::types::BoxResult;
+
+// The profiler of the site being built on the current thread, if profiling
+thread_local! {
+    static PROFILER: RefCell<Option<Profiler>> = const { RefCell::new(None) };
+}
+
+/// What kind of template a timing belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateKind {
+    /// A page or document, including its layouts
+    Page,
+    /// A single layout, excluding its parent layouts
+    Layout,
+    /// An include tag, including nested includes
+    Include,
+    /// Markdown conversion of a page or document
+    Markdown,
+}
+
+impl fmt::Display for TemplateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateKind::Page => write!(f, "page"),
+            TemplateKind::Layout => write!(f, "layout"),
+            TemplateKind::Include => write!(f, "include"),
+            TemplateKind::Markdown => write!(f, "markdown"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Timing {
+    count: usize,
+    total: Duration,
+}
+
+/// Aggregated timing of one template
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileEntry {
+    /// Kind of template
+    pub kind: TemplateKind,
+    /// Template name, or source path for pages and markdown
+    pub name: String,
+    /// Number of times the template was rendered
+    pub count: usize,
+    /// Total time spent rendering, in milliseconds
+    pub total_ms: f64,
+    /// Average time per render, in milliseconds
+    pub average_ms: f64,
+}
+
+/// Measures a render from its creation until it is dropped
+pub struct ProfileTimer {
+    kind: TemplateKind,
+    name: String,
+    start: Instant,
+}
+
+impl Drop for ProfileTimer {
+    fn drop(&mut self) {
+        record(self.kind, std::mem::take(&mut self.name), self.start.elapsed());
+    }
+}
+
+/// Render timings of one site, aggregated across its rendering threads
+///
+/// Like [`Diagnostics`](crate::builder::diagnostics::Diagnostics), a site
+/// enters its profiler on each thread it renders on.
+#[derive(Debug, Clone, Default)]
+pub struct Profiler {
+    timings: Arc<Mutex<HashMap<(TemplateKind, String), Timing>>>,
+}
+
+/// A profiler entered on the current thread, left again when dropped
+pub struct Scope {
+    previous: Option<Profiler>,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        PROFILER.with(|profiler| *profiler.borrow_mut() = self.previous.take());
+    }
+}
+
+impl Profiler {
+    /// The profiler entered on the current thread, to enter on worker threads
+    pub fn current() -> Option<Self> {
+        PROFILER.with(|profiler| profiler.borrow().clone())
+    }
+
+    /// Time the renders on the current thread until the scope is dropped
+    pub fn enter(&self) -> Scope {
+        let previous = PROFILER.with(|profiler| profiler.borrow_mut().replace(self.clone()));
+        Scope { previous }
+    }
+
+    /// Take the timings collected so far, slowest first
+    ///
+    /// Names below `source` are made relative to it.
+    pub fn take(&self, source: &Path) -> Vec<ProfileEntry> {
+        let timings = std::mem::take(&mut *self.timings.lock().unwrap());
+
+        let mut entries: Vec<ProfileEntry> = timings.into_iter()
+            .map(|((kind, name), timing)| {
+                let name = Path::new(&name).strip_prefix(source)
+                    .map(|relative| relative.to_string_lossy().to_string())
+                    .unwrap_or(name);
+                let total_ms = timing.total.as_secs_f64() * 1000.0;
+
+                ProfileEntry {
+                    kind,
+                    name,
+                    count: timing.count,
+                    total_ms,
+                    average_ms: total_ms / timing.count as f64,
+                }
+            })
+            .collect();
+
+        entries.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms)
+            .then_with(|| a.kind.cmp(&b.kind))
+            .then_with(|| a.name.cmp(&b.name)));
+        entries
+    }
+}
+
+/// Check if render timings are being collected on the current thread
+pub fn is_enabled() -> bool {
+    PROFILER.with(|profiler| profiler.borrow().is_some())
+}
+
+/// Start timing a render, if profiling is enabled
+///
+/// The time is recorded when the returned timer goes out of scope, so early
+/// returns are measured too.
+pub fn start(kind: TemplateKind, name: impl Into<String>) -> Option<ProfileTimer> {
+    if !is_enabled() {
+        return None;
+    }
+
+    Some(ProfileTimer {
+        kind,
+        name: name.into(),
+        start: Instant::now(),
+    })
+}
+
+/// Add a render time to the totals of a template in the current profiler
+pub fn record(kind: TemplateKind, name: String, elapsed: Duration) {
+    if let Some(profiler) = Profiler::current() {
+        let mut timings = profiler.timings.lock().unwrap();
+        let timing = timings.entry((kind, name)).or_default();
+        timing.count += 1;
+        timing.total += elapsed;
+    }
+}
+
+/// Write the timings as JSON
+pub fn write_json(entries: &[ProfileEntry], path: &Path) -> BoxResult<()> {
+    fs::write(path, serde_json::to_string_pretty(entries)?)?;
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use std::cell::RefCell;
+
+use chrono_tz::Tz;
+
+use crate::liquid::IncludeCache;
+use crate::liquid::strict::StrictState;
+
+// The render state of the site being built on the current thread
+thread_local! {
+    static STATE: RefCell<Option<RenderState>> = const { RefCell::new(None) };
+}
+
+/// State of one site that templates read and update while rendering
+///
+/// Like [`Diagnostics`](crate::builder::diagnostics::Diagnostics), a site
+/// enters its state on each thread it renders on, so sites built at the
+/// same time don't see each other's state.
+#[derive(Debug, Clone)]
+pub struct RenderState {
+    timezone: Tz,
+    includes: IncludeCache,
+    strict: StrictState,
+}
+
+impl Default for RenderState {
+    fn default() -> Self {
+        Self::new(Tz::UTC)
+    }
+}
+
+/// A render state entered on the current thread, left again when dropped
+pub struct Scope {
+    previous: Option<RenderState>,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        STATE.with(|state| *state.borrow_mut() = self.previous.take());
+    }
+}
+
+impl RenderState {
+    /// A fresh state for a site whose dates are in `timezone`
+    pub fn new(timezone: Tz) -> Self {
+        Self { timezone, includes: IncludeCache::default(), strict: StrictState::default() }
+    }
+
+    /// The state entered on the current thread, to enter on worker threads
+    pub fn current() -> Option<Self> {
+        STATE.with(|state| state.borrow().clone())
+    }
+
+    /// Use this state for renders on the current thread until the scope is dropped
+    pub fn enter(&self) -> Scope {
+        let previous = STATE.with(|state| state.borrow_mut().replace(self.clone()));
+        Scope { previous }
+    }
+
+    /// The timezone dates are interpreted and displayed in
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// Includes rendered by include_cached
+    pub(crate) fn includes(&self) -> &IncludeCache {
+        &self.includes
+    }
+
+    /// Filters known and warnings reported by strict checks
+    pub(crate) fn strict(&self) -> &StrictState {
+        &self.strict
+    }
+}
+
+/// Call `f` with the state entered on the current thread, if any
+pub(crate) fn with<R>(f: impl FnOnce(Option<&RenderState>) -> R) -> R {
+    STATE.with(|state| f(state.borrow().as_ref()))
+}
@@ -0,0 +1,212 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Datelike, Utc};
+use chrono_tz::Tz;
+use liquid::model::Value;
+use log::{info, warn};
+
+use crate::config::{ArchivesConfig, Config};
+use crate::config::permalink::slugify;
+use crate::collections::Collection;
+use crate::collections::document::model::Document;
+use crate::front_matter::FrontMatter;
+use crate::utils::date::local;
+use crate::builder::page::Page;
+use crate::builder::site::LayoutInfo;
+
+/// Generate archive pages for tags, categories and dates
+///
+/// Follows jekyll-archives: each archive page renders the configured layout
+/// with `page.type`, `page.title` and the archived `page.posts`, newest first.
+pub fn generate_archives(
+    collections: &HashMap<String, Collection>,
+    layouts: &HashMap<String, LayoutInfo>,
+    config: &Config
+) -> Vec<Page> {
+    let archives = match &config.archives {
+        Some(archives) if archives.enabled.is_some() => archives,
+        _ => return Vec::new(),
+    };
+
+    let mut posts: Vec<&Document> = match collections.get("posts") {
+        Some(posts) => posts.documents.iter().collect(),
+        None => return Vec::new(),
+    };
+    posts.sort_by_key(|doc| std::cmp::Reverse(doc.date));
+
+    let mut pages = Vec::new();
+
+    // Names that only differ in case or punctuation share an archive page
+    if archives.is_enabled("tag") {
+        for (slug, docs) in group_by(&posts, |doc| doc.tags.iter().map(|tag| slugify(tag)).collect()) {
+            let name = display_name(&docs[0].tags, &slug);
+            let url = permalink(archives, "tag", &[("name", slug)]);
+            push_archive(&mut pages, archives, layouts, config, "tag", name, None, url, &docs);
+        }
+    }
+
+    if archives.is_enabled("category") {
+        for (slug, docs) in group_by(&posts, |doc| doc.categories.iter().map(|category| slugify(category)).collect()) {
+            let name = display_name(&docs[0].categories, &slug);
+            let url = permalink(archives, "category", &[("name", slug)]);
+            push_archive(&mut pages, archives, layouts, config, "category", name, None, url, &docs);
+        }
+    }
+
+    // Date keys are zero-padded so they sort chronologically
+    let dated: Vec<&Document> = posts.iter().copied().filter(|doc| doc.date.is_some()).collect();
+
+    if archives.is_enabled("year") {
+        for (key, docs) in group_by(&dated, |doc| vec![date_of(doc).format("%Y").to_string()]) {
+            let date = date_of(docs[0]);
+            let url = permalink(archives, "year", &date_placeholders(&date));
+            push_archive(&mut pages, archives, layouts, config, "year", key, Some(date.to_utc()), url, &docs);
+        }
+    }
+
+    if archives.is_enabled("month") {
+        for (_, docs) in group_by(&dated, |doc| vec![date_of(doc).format("%Y-%m").to_string()]) {
+            let date = date_of(docs[0]);
+            let url = permalink(archives, "month", &date_placeholders(&date));
+            let title = date.format("%B %Y").to_string();
+            push_archive(&mut pages, archives, layouts, config, "month", title, Some(date.to_utc()), url, &docs);
+        }
+    }
+
+    if archives.is_enabled("day") {
+        for (_, docs) in group_by(&dated, |doc| vec![date_of(doc).format("%Y-%m-%d").to_string()]) {
+            let date = date_of(docs[0]);
+            let url = permalink(archives, "day", &date_placeholders(&date));
+            let title = date.format("%B %-d, %Y").to_string();
+            push_archive(&mut pages, archives, layouts, config, "day", title, Some(date.to_utc()), url, &docs);
+        }
+    }
+
+    if !pages.is_empty() {
+        info!("Generated {} archive pages", pages.len());
+    }
+
+    pages
+}
+
+/// Create an archive page and add it to the list
+#[allow(clippy::too_many_arguments)]
+fn push_archive(
+    pages: &mut Vec<Page>,
+    archives: &ArchivesConfig,
+    layouts: &HashMap<String, LayoutInfo>,
+    config: &Config,
+    archive_type: &str,
+    title: String,
+    date: Option<DateTime<Utc>>,
+    url: String,
+    docs: &[&Document]
+) {
+    let layout_name = archives.layout_for(archive_type);
+    let layout = match layouts.get(layout_name) {
+        Some(layout) => layout,
+        None => {
+            warn!("Archive layout '{}' not found, skipping {} archive '{}'", layout_name, archive_type, title);
+            return;
+        }
+    };
+
+    let front_matter = FrontMatter {
+        title: Some(title),
+        layout: Some(layout_name.to_string()),
+        ..FrontMatter::default()
+    };
+
+    let mut page = Page::generated(layout.path.clone(), url, front_matter, config);
+    page.date = date;
+    page.data.insert("type".into(), Value::scalar(archive_type.to_string()));
+    page.data.insert("posts".into(), Value::Array(
+        docs.iter().map(|doc| crate::collections::document_to_liquid(doc)).collect()
+    ));
+
+    pages.push(page);
+}
+
+/// Group documents by the keys a function returns for them, keeping document order
+fn group_by<'a, F>(docs: &[&'a Document], keys: F) -> BTreeMap<String, Vec<&'a Document>>
+where
+    F: Fn(&Document) -> Vec<String>,
+{
+    let mut groups: BTreeMap<String, Vec<&Document>> = BTreeMap::new();
+    for doc in docs {
+        for key in keys(doc) {
+            groups.entry(key).or_default().push(doc);
+        }
+    }
+    groups
+}
+
+/// The name a slug was made from, as written in a document
+fn display_name(names: &[String], slug: &str) -> String {
+    names.iter()
+        .find(|name| slugify(name) == slug)
+        .cloned()
+        .unwrap_or_else(|| slug.to_string())
+}
+
+/// The date of a document known to have one, in the site's timezone
+fn date_of(doc: &Document) -> DateTime<Tz> {
+    local(doc.date.unwrap_or_default())
+}
+
+/// Placeholders for date archive permalinks
+fn date_placeholders(date: &DateTime<Tz>) -> [(&'static str, String); 3] {
+    [
+        ("year", date.year().to_string()),
+        ("month", format!("{:02}", date.month())),
+        ("day", format!("{:02}", date.day())),
+    ]
+}
+
+/// Build the URL of an archive page from its permalink pattern
+fn permalink(archives: &ArchivesConfig, archive_type: &str, placeholders: &[(&str, String)]) -> String {
+    let mut url = archives.permalink_for(archive_type);
+    for (name, value) in placeholders {
+        url = url.replace(&format!(":{}", name), value);
+    }
+
+    if url.starts_with('/') {
+        url
+    } else {
+        format!("/{}", url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestSite;
+
+    #[test]
+    fn test_archives() {
+        let site = TestSite::new("archives");
+        site.write("_layouts/archive.html",
+                   "{{ page.type }}|{{ page.title }}|{% for post in page.posts %}{{ post.title }};{% endfor %}")
+            .write("_layouts/year.html", "Year {{ page.title }}: {{ page.posts | size }}")
+            .write("_posts/2024-01-01-a.md", "---\ntitle: A\ncategories: [News]\ntags: [Rust Lang]\n---\nA")
+            .write("_posts/2024-01-15-b.md", "---\ntitle: B\ncategories: [news]\ntags: [rust-lang, web]\n---\nB")
+            .write("_posts/2023-12-31-c.md", "---\ntitle: C\ntags: [web]\n---\nC");
+
+        let archives: ArchivesConfig = serde_yaml::from_str(
+            "enabled: all\nlayouts:\n  year: year\npermalinks:\n  tag: /topics/:name/\n").unwrap();
+        site.build(Config { archives: Some(archives), ..site.config() });
+        let read = |path: &str| site.read(path).trim().to_string();
+
+        // Names which slugify the same share a page, named as the newest post writes them
+        assert_eq!(read("topics/rust-lang/index.html"), "tag|rust-lang|B;A;");
+        assert_eq!(read("topics/web/index.html"), "tag|web|B;C;");
+        assert_eq!(read("category/news/index.html"), "category|news|B;A;");
+        assert!(!site.output("tag").exists());
+
+        assert_eq!(read("2024/index.html"), "Year 2024: 2");
+        assert_eq!(read("2023/index.html"), "Year 2023: 1");
+        assert_eq!(read("2024/01/index.html"), "month|January 2024|B;A;");
+        assert_eq!(read("2023/12/31/index.html"), "day|December 31, 2023|C;");
+        assert_eq!(read("2024/01/15/index.html"), "day|January 15, 2024|B;");
+    }
+}
@@ -1,693 +1,1018 @@
-use crate::config::Config;
+use crate::config::{Config, load_config};
 use crate::directory::{DirectoryStructure, clean_destination};
-use crate::collections::{load_collections, load_data_files, collections_to_liquid};
+use crate::collections::{Collection, load_collections, load_data_files, collections_to_liquid};
+use crate::collections::types::DataCollection;
 use crate::liquid::{create_jekyll_parser, create_site_object};
 use crate::markdown::MarkdownRenderer;
+use crate::markdown::links::RelativeLinks;
 use crate::builder::page::{Page, collect_pages};
 use crate::builder::types::BoxResult;
+use crate::utils::date;
+use crate::builder::diagnostics::{self, Diagnostic, Diagnostics};
+use crate::builder::profiler::{self, ProfileEntry, Profiler};
+use crate::builder::render_state::{self, RenderState};
 use crate::builder::site::{
     load_layouts, 
     load_includes, 
-    process_collections, 
-    process_pages,
-    data_to_liquid
+    render_collections, 
+    render_pages,
+    write_outputs,
+    strip_baseurl,
+    data_to_liquid,
+    document_output_path,
+    process_pagination,
+    process_related_posts,
+    generate_archives,
+    plan_feeds,
+    render_feeds,
+    generate_sitemap,
+    collect_redirects,
+    apply_redirect_to,
+    generate_redirects,
+    BuildManifest,
+    report_conflicts,
+    LayoutInfo,
+    RenderRecord,
+    RenderedOutput,
 };
+use crate::builder::site::cache::{IncrementalCache, FileHashes, OutputRecord, hash_bytes};
+use crate::directory::utils::is_convertible_file;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 use log::{info, debug, warn, error};
 use liquid::model::Value;
 use rayon::prelude::*;
-use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
-use serde::{Serialize, Deserialize};
+use regex::Regex;
+use walkdir::WalkDir;
 use chrono::Utc;
 
-/// The incremental build cache structure
-#[derive(Debug, Default, Serialize, Deserialize)]
-struct IncrementalCache {
-    /// Map of file paths to their last modification time
-    file_mtimes: HashMap<PathBuf, SystemTime>,
-    /// Map of file paths to their dependencies
-    dependencies: HashMap<PathBuf, HashSet<PathBuf>>,
-    /// Map of template paths to their dependents
-    dependents: HashMap<PathBuf, HashSet<PathBuf>>,
+/// Build statistics
+#[derive(Debug, Default, Clone)]
+pub struct BuildStats {
+    /// Number of pages processed
+    pub pages_count: usize,
+    /// Number of documents processed
+    pub documents_count: usize,
+    /// Number of static files copied
+    pub static_files_count: usize,
+    /// Number of outputs reused from the previous build
+    pub reused_count: usize,
+    /// Number of errors encountered
+    pub errors_count: usize,
+    /// Build duration
+    pub duration: std::time::Duration,
 }
 
-impl IncrementalCache {
-    /// Load the cache from disk
-    fn load(config: &Config) -> Self {
-        let cache_path = Path::new(&config.source).join(&config.cache_dir).join("cache.json");
-        if cache_path.exists() {
-            match fs::read_to_string(&cache_path) {
-                Ok(content) => {
-                    match serde_json::from_str(&content) {
-                        Ok(cache) => return cache,
-                        Err(e) => {
-                            warn!("Failed to parse cache file: {}", e);
-                            Self::default()
-                        }
-                    }
-                },
-                Err(e) => {
-                    warn!("Failed to read cache file: {}", e);
-                    Self::default()
-                }
-            }
-        } else {
-            Self::default()
-        }
-    }
+/// What a build found, for the caller to report
+#[derive(Debug, Default, Clone)]
+pub struct BuildOutcome {
+    /// Statistics of the build
+    pub stats: BuildStats,
+    /// Problems found while building the site
+    pub diagnostics: Vec<Diagnostic>,
+    /// Render timings per template, when profiling is enabled
+    pub profile: Vec<ProfileEntry>,
+}
 
-    /// Save the cache to disk
-    fn save(&self, config: &Config) -> BoxResult<()> {
-        let cache_dir = Path::new(&config.source).join(&config.cache_dir);
-        if !cache_dir.exists() {
-            fs::create_dir_all(&cache_dir)?;
-        }
-        
-        let cache_path = cache_dir.join("cache.json");
-        let json = serde_json::to_string(self)?;
-        fs::write(cache_path, json)?;
-        Ok(())
-    }
+/// A finished build, with what it found even when it failed
+#[derive(Debug)]
+pub struct BuildReport {
+    /// What the build found
+    pub outcome: BuildOutcome,
+    /// Whether the build succeeded
+    pub result: BoxResult<()>,
+}
 
-    /// Check if a file has been modified since the last build
-    fn is_modified(&self, path: &Path) -> bool {
-        match fs::metadata(path) {
-            Ok(metadata) => {
-                match metadata.modified() {
-                    Ok(mtime) => {
-                        match self.file_mtimes.get(path) {
-                            Some(cached_mtime) => mtime > *cached_mtime,
-                            None => true, // File not in cache, consider it modified
-                        }
-                    },
-                    Err(_) => true, // Can't get mtime, consider it modified
-                }
-            },
-            Err(_) => true, // Can't get metadata, consider it modified
-        }
-    }
+/// A Jekyll-compatible static site
+///
+/// A site is built in phases: [`Site::read`] loads the source files,
+/// [`Site::render`] renders pages and documents in memory and [`Site::write`]
+/// writes them to the destination. [`Site::process`] runs all three.
+pub struct Site {
+    config: Config,
+    dirs: DirectoryStructure,
+    collections: HashMap<String, Collection>,
+    pages: Vec<Page>,
+    layouts: HashMap<String, LayoutInfo>,
+    includes: HashMap<String, String>,
+    data: DataCollection,
+    outputs: Vec<RenderedOutput>,
+    diagnostics: Vec<Diagnostic>,
+    profile: Vec<ProfileEntry>,
+    /// Collects the problems reported while the site is built
+    collector: Diagnostics,
+    /// Collects render timings, when profiling is enabled
+    profiler: Option<Profiler>,
+    /// State templates share while the site is rendered
+    render_state: RenderState,
+    stats: BuildStats,
+    incremental: Option<IncrementalState>,
+}
 
-    /// Check if a file or any of its dependencies have been modified
-    fn needs_rebuild(&self, path: &Path) -> bool {
-        // Check if the file itself has been modified
-        if self.is_modified(path) {
-            return true;
-        }
+/// State carried between the phases of an incremental build
+#[derive(Default)]
+struct IncrementalState {
+    cache: IncrementalCache,
+    hashes: FileHashes,
+    /// Whether any page or document changed since the previous build
+    content_changed: bool,
+    /// Outputs generated by this build, used to remove stale files afterwards
+    planned_outputs: HashSet<PathBuf>,
+}
 
-        // Check if any of its dependencies have been modified
-        if let Some(deps) = self.dependencies.get(path) {
-            for dep in deps {
-                if self.is_modified(dep) {
-                    return true;
-                }
-            }
+impl Site {
+    /// Create a site from a configuration
+    pub fn new(config: Config) -> Self {
+        let dirs = DirectoryStructure::from_config(&config);
+        
+        Self {
+            config,
+            dirs,
+            collections: HashMap::new(),
+            pages: Vec::new(),
+            layouts: HashMap::new(),
+            includes: HashMap::new(),
+            data: DataCollection::default(),
+            outputs: Vec::new(),
+            diagnostics: Vec::new(),
+            profile: Vec::new(),
+            collector: Diagnostics::default(),
+            profiler: None,
+            render_state: RenderState::default(),
+            stats: BuildStats::default(),
+            incremental: None,
         }
-
-        false
     }
-
-    /// Update the file's modification time in the cache
-    fn update_mtime(&mut self, path: &Path) {
-        if let Ok(metadata) = fs::metadata(path) {
-            if let Ok(mtime) = metadata.modified() {
-                self.file_mtimes.insert(path.to_path_buf(), mtime);
-            }
-        }
+    
+    /// Create a site from the configuration files in a source directory
+    pub fn load<P: AsRef<Path>>(source: P) -> BoxResult<Self> {
+        Ok(Self::new(load_config(source, None)?))
     }
-
-    /// Add a dependency relationship between a file and its dependency
-    fn add_dependency(&mut self, file: &Path, dependency: &Path) {
-        self.dependencies
-            .entry(file.to_path_buf())
-            .or_insert_with(HashSet::new)
-            .insert(dependency.to_path_buf());
-
-        self.dependents
-            .entry(dependency.to_path_buf())
-            .or_insert_with(HashSet::new)
-            .insert(file.to_path_buf());
+    
+    /// The configuration of the site
+    pub fn config(&self) -> &Config {
+        &self.config
     }
-
-    /// Get all files that depend on the given file (directly or indirectly)
-    fn get_affected_files(&self, path: &Path) -> HashSet<PathBuf> {
-        let mut result = HashSet::new();
-        let mut to_process = vec![path.to_path_buf()];
-
-        while let Some(current) = to_process.pop() {
-            if let Some(deps) = self.dependents.get(&current) {
-                for dep in deps {
-                    if result.insert(dep.clone()) {
-                        to_process.push(dep.clone());
-                    }
-                }
-            }
-        }
-
-        result
+    
+    /// Pages of the site, including generated pagination and archive pages once rendered
+    pub fn pages(&self) -> &[Page] {
+        &self.pages
     }
-}
-
-/// Build statistics
-#[derive(Debug, Default)]
-struct BuildStats {
-    /// Number of pages processed
-    pages_count: usize,
-    /// Number of documents processed
-    documents_count: usize,
-    /// Number of static files copied
-    static_files_count: usize,
-    /// Number of errors encountered
-    errors_count: usize,
-    /// Build duration
-    duration: std::time::Duration,
-}
-
-/// Build a Jekyll-compatible static site
-pub fn build_site(config: &Config, _include_drafts: bool, _include_unpublished: bool) -> BoxResult<()> {
-    let start_time = std::time::Instant::now();
-    let incremental = config.incremental.unwrap_or(false);
     
-    // Setup build statistics
-    let mut stats = BuildStats::default();
+    /// Collections of the site, including posts
+    pub fn collections(&self) -> &HashMap<String, Collection> {
+        &self.collections
+    }
     
-    // Setup logging verbosity
-    if let Some(quiet) = config.quiet {
-        if quiet {
-            log::set_max_level(log::LevelFilter::Warn);
-        }
+    /// Outputs rendered by the last call to [`Site::render`]
+    pub fn outputs(&self) -> &[RenderedOutput] {
+        &self.outputs
     }
     
-    if let Some(verbose) = config.verbose {
-        if verbose {
-            log::set_max_level(log::LevelFilter::Debug);
-        }
+    /// Statistics of the build
+    pub fn stats(&self) -> &BuildStats {
+        &self.stats
     }
     
-    // Load incremental cache if enabled
-    let mut cache = if incremental {
-        info!("Incremental build enabled");
-        IncrementalCache::load(config)
-    } else {
-        IncrementalCache::default()
-    };
-
-    // Create directory structure
-    let dirs = DirectoryStructure::from_config(config);
-    info!("Using source directory: {}", dirs.source.display());
-    info!("Output will be generated in: {}", dirs.destination.display());
-
-    // Only clean destination if not doing incremental build
-    if !incremental {
-        clean_destination(config)?;
+    /// Problems found while building the site
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
     }
-
-    // Create destination directory and other required directories
-    dirs.create_site_directories()?;
-
-    // Load collections (includes posts)
-    info!("Loading collections...");
-    let mut collections = load_collections(config)?;
     
-    // Get posts collection for convenience
-    let _posts = collections.get("posts")
-        .ok_or("Posts collection not found")?
-        .clone();
+    /// Render timings per template, when profiling is enabled
+    pub fn profile(&self) -> &[ProfileEntry] {
+        &self.profile
+    }
     
-    // Count documents in all collections
-    let total_documents: usize = collections.values()
-        .map(|collection| collection.documents.len())
-        .sum();
-    debug!("Loaded {} documents from {} collections", total_documents, collections.len());
-    stats.documents_count = total_documents;
-
-    // Load pages
-    info!("Loading pages...");
-    let mut pages = collect_pages(&dirs)?;
-    stats.pages_count = pages.len();
-    debug!("Loaded {} pages", pages.len());
-
-    // Load layouts
-    info!("Loading layouts...");
-    let layouts = load_layouts(&dirs)?;
-    debug!("Loaded {} layouts", layouts.len());
-
-    // Load includes
-    info!("Loading includes...");
-    let includes = load_includes(&dirs)?;
-    debug!("Loaded {} includes", includes.len());
-
-    // Load data files
-    info!("Loading data files...");
-    let data = load_data_files(config)?;
-    debug!("Loaded {} data files", data.len());
-
-    // Update dependency tracking for includes and layouts
-    if incremental {
-        for (name, _) in &includes {
-            let path = dirs.includes_dir.join(format!("{}.html", name));
-            cache.update_mtime(&path);
+    /// The statistics, problems and render timings of the last build
+    pub fn outcome(&self) -> BuildOutcome {
+        BuildOutcome {
+            stats: self.stats.clone(),
+            diagnostics: self.diagnostics.clone(),
+            profile: self.profile.clone(),
         }
-
-        for (name, _layout) in &layouts {
-            let path = dirs.layouts_dir.join(format!("{}.html", name));
-            cache.update_mtime(&path);
-
-            // Track dependencies between layouts (for layout inheritance)
-            if let Some(layout_info) = layouts.get(name) {
-                // Check if layout has a parent layout in its front matter
-                if let Some(parent_layout) = &layout_info.front_matter.layout {
-                    let parent_path = dirs.layouts_dir.join(format!("{}.html", parent_layout));
-                    cache.add_dependency(&path, &parent_path);
-                }
+    }
+    
+    /// Read, render and write the site
+    pub fn process(&mut self) -> BoxResult<BuildOutcome> {
+        let start_time = Instant::now();
+        
+        self.read()?;
+        self.render()?;
+        self.write()?;
+        
+        let elapsed = start_time.elapsed();
+        self.stats.duration = elapsed;
+        
+        let stats = &self.stats;
+        info!("Site built in {:.2?}", elapsed);
+        info!("Pages: {}, Documents: {}, Static files: {}, Errors: {}", 
+              stats.pages_count, stats.documents_count, stats.static_files_count, stats.errors_count);
+        if self.incremental.is_some() {
+            info!("Reused {} unchanged outputs", stats.reused_count);
+        }
+        
+        // Generate build report if configured
+        let config = &self.config;
+        if config.build_report.unwrap_or(false) {
+            info!("Generating build report...");
+            match crate::report::generate_build_report(config, elapsed) {
+                Ok(_) => info!("Build report generated successfully"),
+                Err(e) => warn!("Failed to generate build report: {}", e),
             }
         }
-    }
-
-    // Copy static files (skip in incremental mode if not modified)
-    info!("Copying static files...");
-    #[allow(unused_assignments)]
-    let mut copied_count = 0;
-
-    // Use a thread pool for parallel file copying
-    if !incremental {
-        // Non-incremental: copy all static files
-        copied_count = dirs.copy_static_files()?;
-    } else {
-        // Incremental: only copy modified files
-        let static_files = dirs.get_static_files()?;
-        
-        // Use rayon for parallel processing of static files
-        let counter = Arc::new(Mutex::new(0));
-        let cache_arc = Arc::new(Mutex::new(&mut cache));
-        
-        static_files.par_iter().for_each(|(source, dest)| {
-            let is_modified = {
-                let cache_guard = cache_arc.lock().unwrap();
-                cache_guard.is_modified(source)
-            };
+        
+        // Collect render timings, saving them alongside the build report
+        if let Some(profiler) = &self.profiler {
+            self.profile = profiler.take(&config.source);
             
-            if is_modified {
-                if let Some(parent) = dest.parent() {
-                    let _ = fs::create_dir_all(parent);
-                }
-                
-                if let Err(e) = fs::copy(source, dest) {
-                    error!("Failed to copy static file from {} to {}: {}", 
-                           source.display(), dest.display(), e);
-                } else {
-                    let mut count = counter.lock().unwrap();
-                    *count += 1;
-                    
-                    let mut cache_guard = cache_arc.lock().unwrap();
-                    cache_guard.update_mtime(source);
+            if config.build_report.unwrap_or(false) {
+                let profile_path = config.destination.join("build-profile.json");
+                if let Err(e) = profiler::write_json(&self.profile, &profile_path) {
+                    warn!("Failed to write build profile: {}", e);
                 }
             }
-        });
+        }
         
-        copied_count = *counter.lock().unwrap();
+        if config.strict.unwrap_or(false) && self.stats.errors_count > 0 {
+            return Err(format!("Build failed with {} errors (strict mode)", self.stats.errors_count).into());
+        }
+        
+        Ok(self.outcome())
     }
     
-    stats.static_files_count = copied_count;
-    info!("Copied {} static files", copied_count);
-
-    // Create the Liquid parser with custom tags and filters
-    info!("Setting up template engine...");
-    let parser = create_jekyll_parser(config, includes)?;
-
-    // Create the Markdown renderer
-    let markdown_renderer = MarkdownRenderer::new(config);
-
-    // Create the site object with all collections and data
-    let mut site_data = create_site_object(config);
-    
-    // Add collections to site object
-    let collections_data = collections_to_liquid(&collections, config);
-    site_data.insert("collections".into(), Value::Object(collections_data));
-    
-    // Add pages to site object
-    let pages_array = pages.iter()
-        .map(|page| crate::builder::site::page_to_liquid(page))
-        .collect::<Vec<Value>>();
-    site_data.insert("pages".into(), Value::Array(pages_array));
+    /// Report problems and render timings on the current thread to this site,
+    /// and render with its state, until the scopes are dropped
+    fn enter(&self) -> (diagnostics::Scope, Option<profiler::Scope>, render_state::Scope) {
+        (self.collector.enter(), self.profiler.as_ref().map(Profiler::enter), self.render_state.enter())
+    }
     
-    // Add posts directly to site object
-    if let Some(posts_collection) = collections.get("posts") {
-        // Sort the posts by date (newest first)
-        let mut sorted_posts = posts_collection.documents.clone();
-        sorted_posts.sort_by(|a, b| {
-            let a_date = a.date.unwrap_or_else(|| Utc::now());
-            let b_date = b.date.unwrap_or_else(|| Utc::now());
-            b_date.cmp(&a_date) // Reverse chronological order
+    /// Read layouts, includes, data files, pages and collections from the source
+    pub fn read(&mut self) -> BoxResult<()> {
+        self.stats = BuildStats::default();
+        self.diagnostics.clear();
+        self.profile.clear();
+        self.collector = Diagnostics::default();
+        self.profiler = self.config.profile.unwrap_or(false).then(Profiler::default);
+        
+        // Dates are read and shown in the site's timezone, like Jekyll's TZ
+        let timezone = date::parse_timezone(self.config.timezone.as_deref()).unwrap_or_else(|e| {
+            warn!("{}, using UTC", e);
+            chrono_tz::Tz::UTC
         });
+        self.render_state = RenderState::new(timezone);
+        let _scope = self.enter();
+        
+        let config = &self.config;
+        let dirs = &self.dirs;
+        
+        // Load incremental cache if enabled
+        self.incremental = if config.incremental.unwrap_or(false) {
+            info!("Incremental build enabled");
+            Some(IncrementalState {
+                cache: IncrementalCache::load(config),
+                ..IncrementalState::default()
+            })
+        } else {
+            None
+        };
+        
+        info!("Using source directory: {}", dirs.source.display());
+        info!("Output will be generated in: {}", dirs.destination.display());
+        
+        // Load collections (includes posts)
+        info!("Loading collections...");
+        let collections = load_collections(config)?;
+        if !collections.contains_key("posts") {
+            return Err("Posts collection not found".into());
+        }
+        
+        // Count documents in all collections
+        let total_documents: usize = collections.values()
+            .map(|collection| collection.documents.len())
+            .sum();
+        debug!("Loaded {} documents from {} collections", total_documents, collections.len());
+        self.stats.documents_count = total_documents;
+        
+        // Load pages
+        info!("Loading pages...");
+        let mut pages = collect_pages(dirs)?;
+        if !config.unpublished.unwrap_or(false) {
+            pages.retain(|page| page.front_matter.published != Some(false));
+        }
+        self.stats.pages_count = pages.len();
+        debug!("Loaded {} pages", pages.len());
         
-        // Apply limit_posts if configured
-        if let Some(limit) = config.limit_posts {
-            if limit > 0 && limit < sorted_posts.len() {
-                sorted_posts.truncate(limit);
-                debug!("Limited posts to {} of {}", limit, posts_collection.documents.len());
+        // Load layouts
+        info!("Loading layouts...");
+        let layouts = load_layouts(dirs)?;
+        debug!("Loaded {} layouts", layouts.len());
+        
+        // Load includes
+        info!("Loading includes...");
+        let includes = load_includes(dirs)?;
+        debug!("Loaded {} includes", includes.len());
+        
+        // Load data files
+        info!("Loading data files...");
+        let data = load_data_files(config)?;
+        debug!("Loaded {} data files", data.len());
+        
+        // Hash every input so unchanged pages can be skipped
+        if let Some(state) = self.incremental.as_mut() {
+            let config_hash = hash_bytes(serde_json::to_value(config)?.to_string().as_bytes());
+            if state.cache.check_config(&config_hash) {
+                info!("Configuration changed, rebuilding everything");
+            }
+            
+            let mut inputs: Vec<PathBuf> = layouts.values().map(|layout| layout.path.clone()).collect();
+            inputs.extend(list_files(&config.source.join(&config.includes_dir)));
+            inputs.extend(list_files(&config.source.join(&config.data_dir)));
+            inputs.extend(pages.iter().map(|page| page.path.clone()));
+            for collection in collections.values() {
+                inputs.extend(collection.documents.iter().map(|doc| doc.path.clone()));
             }
+            state.hashes = FileHashes::compute(&inputs);
+            
+            // Listing pages only need rebuilding when some page or document changed
+            let content_paths: Vec<PathBuf> = pages.iter().map(|page| page.path.clone())
+                .chain(collections.values().flat_map(|c| c.documents.iter().map(|doc| doc.path.clone())))
+                .collect();
+            let content_hash = state.hashes.fingerprint(content_paths.iter());
+            state.content_changed = state.cache.update_content_hash(content_hash);
         }
         
-        // Convert to liquid values
-        let posts_array = sorted_posts.iter()
-            .map(|doc| crate::collections::document_to_liquid(doc))
-            .collect::<Vec<Value>>();
-        site_data.insert("posts".into(), Value::Array(posts_array.clone()));
+        self.collections = collections;
+        self.pages = pages;
+        self.layouts = layouts;
+        self.includes = includes;
+        self.data = data;
+        self.collect_diagnostics();
         
-        // Add HTML pages subset
-        let html_pages = pages.iter()
-            .filter(|page| {
-                if let Some(ext) = page.path.extension() {
-                    return ext == "html";
-                }
-                false
-            })
+        Ok(())
+    }
+    
+    /// Render pages and documents in memory
+    ///
+    /// Pagination and archive pages are generated first. In incremental
+    /// builds, outputs that are up to date are not rendered again.
+    pub fn render(&mut self) -> BoxResult<()> {
+        let _scope = self.enter();
+        let config = &self.config;
+        let collections = &mut self.collections;
+        let pages = &mut self.pages;
+        
+        // Create the Liquid parser with custom tags and filters
+        info!("Setting up template engine...");
+        let parser = create_jekyll_parser(config, self.includes.clone())?;
+        
+        // Create the Markdown renderer
+        let mut markdown_renderer = MarkdownRenderer::new(config);
+        
+        // Create the site object with all collections and data
+        let mut site_data = create_site_object(config);
+        
+        // Add collections to site object
+        let collections_data = collections_to_liquid(collections, config);
+        site_data.insert("collections".into(), Value::Object(collections_data));
+        
+        // Add pages to site object
+        let pages_array = pages.iter()
             .map(|page| crate::builder::site::page_to_liquid(page))
             .collect::<Vec<Value>>();
-        site_data.insert("html_pages".into(), Value::Array(html_pages));
-        
-        // Collect categories and tags from posts
-        let mut categories = std::collections::HashMap::new();
-        let mut tags = std::collections::HashMap::new();
-        
-        for doc in &posts_collection.documents {
-            // Process categories
-            for category in &doc.categories {
-                let category_posts = categories.entry(category.clone())
-                    .or_insert_with(Vec::new);
-                category_posts.push(crate::collections::document_to_liquid(doc));
+        site_data.insert("pages".into(), Value::Array(pages_array));
+        
+        // Add posts directly to site object
+        if let Some(posts_collection) = collections.get("posts") {
+            // Sort the posts by date (newest first)
+            let mut sorted_posts = posts_collection.documents.clone();
+            sorted_posts.sort_by(|a, b| {
+                let a_date = a.date.unwrap_or_else(|| Utc::now());
+                let b_date = b.date.unwrap_or_else(|| Utc::now());
+                b_date.cmp(&a_date) // Reverse chronological order
+            });
+            
+            // Apply limit_posts if configured
+            if let Some(limit) = config.limit_posts {
+                if limit > 0 && limit < sorted_posts.len() {
+                    sorted_posts.truncate(limit);
+                    debug!("Limited posts to {} of {}", limit, posts_collection.documents.len());
+                }
+            }
+            
+            // Convert to liquid values
+            let posts_array = sorted_posts.iter()
+                .map(|doc| crate::collections::document_to_liquid(doc))
+                .collect::<Vec<Value>>();
+            site_data.insert("posts".into(), Value::Array(posts_array.clone()));
+            
+            // Add HTML pages subset
+            let html_pages = pages.iter()
+                .filter(|page| {
+                    if let Some(ext) = page.path.extension() {
+                        return ext == "html";
+                    }
+                    false
+                })
+                .map(|page| crate::builder::site::page_to_liquid(page))
+                .collect::<Vec<Value>>();
+            site_data.insert("html_pages".into(), Value::Array(html_pages));
+            
+            // Collect categories and tags from posts
+            let mut categories = std::collections::HashMap::new();
+            let mut tags = std::collections::HashMap::new();
+            
+            for doc in &posts_collection.documents {
+                // Process categories
+                for category in &doc.categories {
+                    let category_posts = categories.entry(category.clone())
+                        .or_insert_with(Vec::new);
+                    category_posts.push(crate::collections::document_to_liquid(doc));
+                }
+                
+                // Process tags
+                for tag in &doc.tags {
+                    let tag_posts = tags.entry(tag.clone())
+                        .or_insert_with(Vec::new);
+                    tag_posts.push(crate::collections::document_to_liquid(doc));
+                }
+            }
+            
+            // Convert categories and tags to Liquid objects
+            let mut categories_obj = liquid::Object::new();
+            for (category, posts) in categories {
+                categories_obj.insert(category.into(), Value::Array(posts));
+            }
+            site_data.insert("categories".into(), Value::Object(categories_obj));
+            
+            let mut tags_obj = liquid::Object::new();
+            for (tag, posts) in tags {
+                tags_obj.insert(tag.into(), Value::Array(posts));
+            }
+            site_data.insert("tags".into(), Value::Object(tags_obj));
+            
+            // Handle related posts
+            process_related_posts(collections, config)?;
+        }
+        
+        // Add data files to site object
+        let data_object = data_to_liquid(&self.data);
+        site_data.insert("data".into(), Value::Object(data_object));
+        
+        // Process collections
+        // First sort collections for consistent output
+        for (_, collection) in collections.iter_mut() {
+            collection.sort_documents();
+            collection.set_next_prev_links();
+        }
+        
+        // First collect all documents that need URLs
+        for (_name, collection) in collections.iter_mut() {
+            let docs_count = collection.documents.len();
+            let mut urls = Vec::with_capacity(docs_count);
+            
+            // Generate URLs without mutating documents
+            for doc in &collection.documents {
+                let url = collection.generate_url(doc, config);
+                urls.push(url);
             }
             
-            // Process tags
-            for tag in &doc.tags {
-                let tag_posts = tags.entry(tag.clone())
-                    .or_insert_with(Vec::new);
-                tag_posts.push(crate::collections::document_to_liquid(doc));
+            // Now assign the URLs to documents
+            for (i, doc) in collection.documents.iter_mut().enumerate() {
+                doc.url = urls.get(i).cloned().flatten();
+                
+                // Generate excerpts if needed
+                doc.generate_excerpt(&config.excerpt_separator);
+            }
+        }
+        
+        // Process pagination for pages that ask for it
+        process_pagination(pages, collections, config)?;
+        
+        // Generate tag, category and date archives
+        pages.extend(generate_archives(collections, &self.layouts, config));
+        
+        // Relative links between sources go to the URLs just generated
+        if config.relative_links_enabled() {
+            markdown_renderer.set_relative_links(RelativeLinks::new(source_urls(pages, collections, config), config));
+        }
+        
+        // Now that all URLs are known, check that no two sources share an output
+        let outputs = content_outputs(pages, collections, &self.dirs);
+        let static_paths = static_files(&self.dirs, config)?;
+        let static_outputs = static_paths.iter().map(|(source, dest)| (dest.clone(), source.clone()));
+        let all_outputs: Vec<(PathBuf, PathBuf)> = outputs.iter().cloned().chain(static_outputs).collect();
+        report_conflicts(&all_outputs, config);
+        
+        // Feeds go where no page or static file already does
+        let taken: HashSet<PathBuf> = all_outputs.into_iter().map(|(output, _)| output).collect();
+        let mut feeds = plan_feeds(collections, &self.dirs, config, &taken);
+        
+        // Work out which outputs are out of date
+        let render_plan = match self.incremental.as_mut() {
+            Some(state) => {
+                let mut render_set = HashSet::new();
+                let document_outputs: HashMap<PathBuf, PathBuf> = outputs.iter()
+                    .map(|(output, source)| (source.clone(), output.clone()))
+                    .collect();
+                
+                for (output, source) in outputs {
+                    state.planned_outputs.insert(output.clone());
+                    if state.cache.needs_rebuild(&output, &source, &mut state.hashes, state.content_changed) {
+                        state.cache.invalidate(&output);
+                        render_set.insert(output);
+                    } else {
+                        self.stats.reused_count += 1;
+                    }
+                }
+                
+                // Feeds list rendered documents, so those are rendered again with them
+                let feed_outputs: Vec<(PathBuf, PathBuf)> = feeds.iter().flat_map(|feed| feed.outputs()).collect();
+                let mut feeds_changed = state.content_changed;
+                for (output, source) in &feed_outputs {
+                    state.planned_outputs.insert(output.clone());
+                    feeds_changed |= state.cache.needs_rebuild(output, source, &mut state.hashes, state.content_changed);
+                }
+                if feeds_changed {
+                    render_set.extend(feeds.iter()
+                        .flat_map(|feed| feed.documents.iter())
+                        .filter_map(|source| document_outputs.get(source).cloned()));
+                } else {
+                    self.stats.reused_count += feed_outputs.len();
+                    feeds.clear();
+                }
+                
+                info!("{} outputs changed, {} up to date", render_set.len(), self.stats.reused_count);
+                Some(render_set)
+            },
+            None => None,
+        };
+        
+        // Render collections (including posts)
+        self.outputs.clear();
+        match render_collections(collections, &self.layouts, &parser, &site_data, &markdown_renderer, &self.dirs, config, render_plan.as_ref()) {
+            Ok(outputs) => self.outputs.extend(outputs),
+            Err(e) => {
+                error!("Error rendering collections: {}", e);
+                self.stats.errors_count += 1;
             }
         }
         
-        // Convert categories and tags to Liquid objects
-        let mut categories_obj = liquid::Object::new();
-        for (category, posts) in categories {
-            categories_obj.insert(category.into(), Value::Array(posts));
+        // Render pages
+        match render_pages(pages, &self.layouts, &parser, &site_data, &markdown_renderer, config, render_plan.as_ref()) {
+            Ok(outputs) => self.outputs.extend(outputs),
+            Err(e) => {
+                error!("Error rendering pages: {}", e);
+                self.stats.errors_count += 1;
+            }
         }
-        site_data.insert("categories".into(), Value::Object(categories_obj));
         
-        let mut tags_obj = liquid::Object::new();
-        for (tag, posts) in tags {
-            tags_obj.insert(tag.into(), Value::Array(posts));
+        // Generate feeds from the rendered documents
+        self.outputs.extend(render_feeds(&feeds, collections, &self.data, config));
+        
+        // The sitemap lists every page, so it is generated on every build
+        let sitemap = generate_sitemap(pages, collections, &static_paths, &self.dirs, config, &taken);
+        if let Some(state) = self.incremental.as_mut() {
+            state.planned_outputs.extend(sitemap.iter().map(|output| output.record.output.clone()));
         }
-        site_data.insert("tags".into(), Value::Object(tags_obj));
+        self.outputs.extend(sitemap);
         
-        // Handle related posts
-        process_related_posts(&mut collections, config)?;
-    }
-    
-    // Add data files to site object
-    let data_object = data_to_liquid(&data);
-    site_data.insert("data".into(), Value::Object(data_object));
-
-    // Process collections
-    // First sort collections for consistent output
-    for (_, collection) in &mut collections {
-        collection.sort_documents();
-        collection.set_next_prev_links();
+        // Pages with redirect_to become redirects, old paths get redirect pages
+        let redirects = collect_redirects(pages, collections, config);
+        apply_redirect_to(&mut self.outputs, &redirects);
+        let redirect_outputs = generate_redirects(&redirects, &self.dirs, config, &taken);
+        if let Some(state) = self.incremental.as_mut() {
+            state.planned_outputs.extend(redirect_outputs.iter().map(|output| output.record.output.clone()));
+        }
+        self.outputs.extend(redirect_outputs);
+        
+        self.collect_diagnostics();
+        Ok(())
     }
     
-    // First collect all documents that need URLs
-    for (_name, collection) in &mut collections {
-        let docs_count = collection.documents.len();
-        let mut urls = Vec::with_capacity(docs_count);
+    /// Copy static files and write the rendered outputs to the destination
+    pub fn write(&mut self) -> BoxResult<()> {
+        let _scope = self.enter();
+        let config = &self.config;
+        let dirs = &self.dirs;
         
-        // Generate URLs without mutating documents
-        for doc in &collection.documents {
-            let url = collection.generate_url(doc, config);
-            urls.push(url);
+        // Only clean destination if not doing incremental build
+        if self.incremental.is_none() {
+            clean_destination(config)?;
         }
         
-        // Now assign the URLs to documents
-        for (i, doc) in collection.documents.iter_mut().enumerate() {
-            doc.url = urls.get(i).cloned().flatten();
+        // Create destination directory and other required directories
+        dirs.create_site_directories()?;
+        
+        // Outputs actually written by this build, as opposed to reused
+        let mut rebuilt_outputs = HashSet::new();
+        
+        // Copy static files (skip in incremental mode if not modified)
+        info!("Copying static files...");
+        let copied_count = match self.incremental.as_mut() {
+            // Non-incremental: copy all static files
+            None => dirs.copy_static_files()?,
+            Some(state) => {
+                // Incremental: only copy files whose content changed
+                let mut to_copy = Vec::new();
+                for (source, dest) in static_files(dirs, config)? {
+                    state.planned_outputs.insert(dest.clone());
+                    if state.cache.needs_rebuild(&dest, &source, &mut state.hashes, false) {
+                        state.cache.invalidate(&dest);
+                        to_copy.push((source, dest));
+                    } else {
+                        self.stats.reused_count += 1;
+                    }
+                }
+                
+                // Use rayon for parallel processing of static files
+                let copied: Vec<(PathBuf, PathBuf)> = to_copy.into_par_iter()
+                    .filter(|(source, dest)| {
+                        if let Some(parent) = dest.parent() {
+                            let _ = fs::create_dir_all(parent);
+                        }
+                        
+                        match fs::copy(source, dest) {
+                            Ok(_) => true,
+                            Err(e) => {
+                                error!("Failed to copy static file from {} to {}: {}", 
+                                       source.display(), dest.display(), e);
+                                false
+                            }
+                        }
+                    })
+                    .collect();
+                
+                let copied_count = copied.len();
+                for (source, dest) in copied {
+                    rebuilt_outputs.insert(dest.clone());
+                    let record = OutputRecord {
+                        source,
+                        ..OutputRecord::default()
+                    };
+                    state.cache.record(dest, record, &mut state.hashes);
+                }
+                
+                // Remove outputs whose sources were deleted or moved
+                let removed = state.cache.remove_stale_outputs(&state.planned_outputs);
+                if removed > 0 {
+                    info!("Removed {} stale output files", removed);
+                }
+                
+                copied_count
+            },
+        };
+        
+        self.stats.static_files_count = copied_count;
+        info!("Copied {} static files", copied_count);
+        
+        // Write the rendered pages and documents
+        let records = write_outputs(&self.outputs);
+        rebuilt_outputs.extend(records.iter().map(|record| record.output.clone()));
+        
+        // Save the incremental cache if enabled
+        if let Some(state) = self.incremental.as_mut() {
+            let collection_labels: Vec<String> = self.collections.keys().cloned().collect();
+            let tracker = DependencyTracker::new(config, &self.layouts, &collection_labels);
+            for record in &records {
+                let output_record = tracker.output_record(record);
+                state.cache.record(record.output.clone(), output_record, &mut state.hashes);
+            }
             
-            // Generate excerpts if needed
-            doc.generate_excerpt(&config.excerpt_separator);
+            if let Err(e) = state.cache.save(config) {
+                warn!("Failed to save incremental cache: {}", e);
+            }
         }
-    }
-    
-    // Process pagination if enabled
-    if let Some(paginate) = config.paginate {
-        if paginate > 0 {
-            info!("Processing pagination (per_page: {})", paginate);
-            process_pagination(&mut pages, &collections, paginate, config)?;
+        
+        // Write the build manifest if requested
+        if let Some(manifest_path) = &config.manifest {
+            let mut manifest = BuildManifest::new(config);
+            for (_, dest) in static_files(dirs, config)? {
+                manifest.add_static(dest, None);
+            }
+            // Generated files like feeds only exist as outputs, pages and
+            // documents are added again below with their details
+            for output in &self.outputs {
+                manifest.add_rendered(&output.record);
+            }
+            for collection in self.collections.values().filter(|collection| collection.output) {
+                for doc in &collection.documents {
+                    manifest.add_document(doc, document_output_path(doc, dirs), &self.layouts);
+                }
+            }
+            for page in &self.pages {
+                manifest.add_page(page, &self.layouts);
+            }
+            
+            let rebuilt = if self.incremental.is_some() { Some(&rebuilt_outputs) } else { None };
+            if let Err(e) = manifest.write(&config.destination.join(manifest_path), rebuilt) {
+                warn!("Failed to write build manifest: {}", e);
+            }
         }
+        
+        self.collect_diagnostics();
+        Ok(())
     }
     
-    // Process and render collections (including posts)
-    let collections_result = process_collections(&mut collections, &layouts, &parser, &site_data, &markdown_renderer, &dirs, config);
-    if let Err(e) = collections_result {
-        error!("Error processing collections: {}", e);
-        stats.errors_count += 1;
-    }
-    
-    // Process and render pages
-    let pages_result = process_pages(pages, &layouts, &parser, &site_data, &markdown_renderer, config);
-    if let Err(e) = pages_result {
-        error!("Error processing pages: {}", e);
-        stats.errors_count += 1;
+    /// Move the problems reported so far into the site, counting the errors
+    fn collect_diagnostics(&mut self) {
+        let problems = self.collector.take();
+        self.stats.errors_count += diagnostics::error_count(&problems);
+        self.diagnostics.extend(problems);
     }
+}
 
-    // Save the incremental cache if enabled
-    if incremental {
-        if let Err(e) = cache.save(config) {
-            warn!("Failed to save incremental cache: {}", e);
-        }
+/// Build a Jekyll-compatible static site
+///
+/// Returns what the build found along with its result, so the problems of a
+/// failed build can be reported too. Nothing is printed.
+pub fn build_site(config: &Config, include_drafts: bool, include_unpublished: bool) -> BuildReport {
+    // Command line flags take precedence over the configuration
+    let mut effective_config = config.clone();
+    if include_drafts {
+        effective_config.show_drafts = Some(true);
+    }
+    if include_unpublished {
+        effective_config.unpublished = Some(true);
     }
-
-    let elapsed = start_time.elapsed();
-    stats.duration = elapsed;
     
-    info!("Site built in {:.2?}", elapsed);
-    info!("Pages: {}, Documents: {}, Static files: {}, Errors: {}", 
-          stats.pages_count, stats.documents_count, stats.static_files_count, stats.errors_count);
-
-    // Generate build report if configured
-    if config.build_report.unwrap_or(false) {
-        info!("Generating build report...");
-        match crate::report::generate_build_report(config, elapsed) {
-            Ok(_) => info!("Build report generated successfully"),
-            Err(e) => warn!("Failed to generate build report: {}", e),
+    // Setup logging verbosity
+    if let Some(quiet) = effective_config.quiet {
+        if quiet {
+            log::set_max_level(log::LevelFilter::Warn);
         }
     }
-
-    Ok(())
-}
-
-/// Process related posts for all collections
-fn process_related_posts(collections: &mut HashMap<String, crate::collections::Collection>, config: &Config) -> BoxResult<()> {
-    if let Some(posts) = collections.get_mut("posts") {
-        // Only process if there are enough posts
-        if posts.documents.len() < 2 {
-            return Ok(());
-        }
-        
-        // Check if LSI is enabled
-        let use_lsi = config.lsi.unwrap_or(false);
-        
-        if use_lsi {
-            // LSI implementation would go here - requires NLP libraries
-            warn!("LSI related posts not implemented yet, falling back to date-based related posts");
-            generate_date_based_related_posts(posts)?;
-        } else {
-            // Use date-based related posts
-            generate_date_based_related_posts(posts)?;
+    
+    if let Some(verbose) = effective_config.verbose {
+        if verbose {
+            log::set_max_level(log::LevelFilter::Debug);
         }
     }
     
-    Ok(())
+    let mut site = Site::new(effective_config);
+    match site.process() {
+        Ok(outcome) => BuildReport { outcome, result: Ok(()) },
+        Err(e) => BuildReport { outcome: site.outcome(), result: Err(e) },
+    }
 }
 
-/// Generate date-based related posts
-fn generate_date_based_related_posts(posts: &mut crate::collections::Collection) -> BoxResult<()> {
-    // Sort posts by date
-    let mut sorted_posts = posts.documents.clone();
-    sorted_posts.sort_by(|a, b| {
-        let a_date = a.date.unwrap_or_else(|| Utc::now());
-        let b_date = b.date.unwrap_or_else(|| Utc::now());
-        b_date.cmp(&a_date) // Reverse chronological order
-    });
-    
-    // For each post, find 4 related posts
-    for i in 0..posts.documents.len() {
-        let mut related = Vec::new();
-        let current = &posts.documents[i];
-        
-        // Consider tags for better matching
-        let current_tags: HashSet<String> = current.tags.iter().cloned().collect();
-        
-        // Score other posts based on tag overlap and date proximity
-        let mut scored_posts: Vec<(usize, f64)> = Vec::new();
-        
-        for (j, post) in sorted_posts.iter().enumerate() {
-            if i == j {
-                continue; // Skip self
-            }
-            
-            // Base score on date proximity
-            let score = if let (Some(current_date), Some(post_date)) = (current.date, post.date) {
-                let diff = (current_date - post_date).num_days().abs() as f64;
-                1.0 / (1.0 + diff / 30.0) // Scale by month
-            } else {
-                0.1 // Default low score
-            };
-            
-            // Bonus for tag overlap
-            let post_tags: HashSet<String> = post.tags.iter().cloned().collect();
-            let tag_overlap = current_tags.intersection(&post_tags).count();
-            let tag_bonus = tag_overlap as f64 * 0.2;
-            
-            scored_posts.push((j, score + tag_bonus));
-        }
-        
-        // Sort by score
-        scored_posts.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        
-        // Take top 4 related posts
-        for (j, _) in scored_posts.iter().take(4) {
-            related.push(sorted_posts[*j].id.clone());
-        }
-        
-        // Update the document with related posts
-        posts.documents[i].related = related;
-    }
+/// Output paths of pages and documents, paired with their sources
+fn content_outputs(
+    pages: &[Page],
+    collections: &HashMap<String, Collection>,
+    dirs: &DirectoryStructure
+) -> Vec<(PathBuf, PathBuf)> {
+    let page_outputs = pages.iter()
+        .filter_map(|page| page.output_path.clone().map(|output| (output, page.path.clone())));
+    let document_outputs = collections.values()
+        .filter(|collection| collection.output)
+        .flat_map(|collection| collection.documents.iter())
+        .map(|doc| (document_output_path(doc, dirs), doc.path.clone()));
     
-    Ok(())
+    page_outputs.chain(document_outputs).collect()
 }
 
-/// Process pagination for index pages
-fn process_pagination(
-    pages: &mut Vec<Page>, 
-    collections: &HashMap<String, crate::collections::Collection>,
-    per_page: usize,
+/// URLs of pages and documents without the baseurl, by source path relative to the source directory
+fn source_urls(
+    pages: &[Page],
+    collections: &HashMap<String, Collection>,
     config: &Config
-) -> BoxResult<()> {
-    // Find index pages with pagination enabled
-    let mut paginated_pages = Vec::new();
+) -> HashMap<PathBuf, String> {
+    let relative = |path: &Path| path.strip_prefix(&config.source).unwrap_or(path).to_path_buf();
+    let mut urls = HashMap::new();
     
-    for (i, page) in pages.iter().enumerate() {
-        if page.front_matter.pagination.is_some() {
-            paginated_pages.push(i);
+    // Document URLs start with the baseurl, which the links add back
+    for doc in collections.values().filter(|collection| collection.output).flat_map(|collection| collection.documents.iter()) {
+        if let Some(url) = doc.url.as_ref().filter(|_| doc.should_write(config)) {
+            urls.insert(relative(&doc.path), strip_baseurl(url, config));
         }
     }
     
-    if paginated_pages.is_empty() {
-        return Ok(());
+    // Pages generated from one source, like pagination, link to the first
+    for page in pages {
+        if let Some(url) = &page.url {
+            let url = url.strip_suffix("index.html").unwrap_or(url);
+            urls.entry(relative(&page.path)).or_insert(url.to_string());
+        }
     }
     
-    // Get the posts collection
-    let posts = match collections.get("posts") {
-        Some(posts) => posts,
-        None => {
-            warn!("Pagination enabled but no posts collection found");
-            return Ok(());
-        }
-    };
+    urls
+}
+
+/// Static files copied to the destination as they are, as `(source, destination)` pairs
+fn static_files(dirs: &DirectoryStructure, config: &Config) -> BoxResult<Vec<(PathBuf, PathBuf)>> {
+    let cache_dir = config.source.join(&config.cache_dir);
     
-    // For each paginated page, create additional pages
-    let mut new_pages = Vec::new();
+    Ok(dirs.get_static_files()?
+        .into_iter()
+        .filter(|(source, _)| !is_convertible_file(source) && !source.starts_with(&cache_dir))
+        .collect())
+}
+
+/// List all files below a directory
+fn list_files(dir: &Path) -> Vec<PathBuf> {
+    if !dir.exists() {
+        return Vec::new();
+    }
     
-    for &index in &paginated_pages {
-        let page = &pages[index];
-        
-        // Get the posts to paginate
-        let mut paginate_posts = posts.documents.clone();
-        
-        // Sort posts by date (newest first)
-        paginate_posts.sort_by(|a, b| {
-            let a_date = a.date.unwrap_or_else(|| Utc::now());
-            let b_date = b.date.unwrap_or_else(|| Utc::now());
-            b_date.cmp(&a_date)
-        });
-        
-        // Filter posts as needed
-        // TODO: Implement category and tag filtering based on pagination config
+    WalkDir::new(dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.path().to_path_buf())
+        .collect()
+}
+
+/// Works out what a rendered output depends on for incremental builds
+struct DependencyTracker<'a> {
+    layouts: HashMap<&'a Path, &'a LayoutInfo>,
+    data_dir: PathBuf,
+    data_files: Vec<PathBuf>,
+    data_regex: Regex,
+    site_content_regex: Regex,
+}
+
+impl<'a> DependencyTracker<'a> {
+    fn new(config: &Config, layouts: &'a HashMap<String, LayoutInfo>, collection_labels: &[String]) -> Self {
+        let data_dir = config.source.join(&config.data_dir);
+        let data_files = list_files(&data_dir);
         
-        // Calculate the number of pages needed
-        let total_posts = paginate_posts.len();
-        let num_pages = (total_posts + per_page - 1) / per_page; // Ceiling division
+        // Variables that list other pages and documents of the site
+        let mut site_lists: Vec<String> = [
+            "posts", "pages", "html_pages", "documents", "categories", "tags",
+            "collections", "related_posts", "static_files",
+        ].iter().map(|name| name.to_string()).collect();
+        site_lists.extend(collection_labels.iter().map(|label| regex::escape(label)));
+        let site_content_regex = Regex::new(&format!(
+            r"\bsite\.(?:{})\b|\bpaginator\b|\bpage\.(?:next|previous|posts)\b|\brelated_posts\b",
+            site_lists.join("|")
+        )).unwrap();
         
-        if num_pages <= 1 {
-            // No pagination needed
-            continue;
+        Self {
+            layouts: layouts.values().map(|layout| (layout.path.as_path(), layout)).collect(),
+            data_dir,
+            data_files,
+            data_regex: Regex::new(r#"\bsite\.data(?:\.([A-Za-z0-9_-]+)|\[\s*["']([^"']+)["']\s*\])?"#).unwrap(),
+            site_content_regex,
         }
+    }
+    
+    /// Build the cache record for a rendered output
+    fn output_record(&self, record: &RenderRecord) -> OutputRecord {
+        let mut dependencies: BTreeSet<PathBuf> = record.layouts.iter()
+            .chain(record.includes.iter())
+            .cloned()
+            .collect();
         
-        // Get pagination path pattern
-        let paginate_path = config.paginate_path.clone();
-        
-        // Create pages for each chunk of posts
-        for page_num in 1..num_pages {
-            // Clone the original page
-            let mut new_page = page.clone();
-            
-            // Calculate post range for this page
-            let start = page_num * per_page;
-            let end = std::cmp::min(start + per_page, total_posts);
-            let page_posts = &paginate_posts[start..end];
-            
-            // Update pagination info in front matter
-            if let Some(ref mut pagination) = new_page.front_matter.pagination {
-                pagination.per_page = per_page;
-                // Use different variables to represent pagination state
-                
-                // Add the post references
-                pagination.posts = page_posts.iter()
-                    .map(|doc| doc.id.clone())
-                    .collect();
+        // Gather the template sources the output was rendered from
+        let mut templates = Vec::new();
+        if let Ok(content) = fs::read_to_string(&record.source) {
+            templates.push(content);
+        }
+        for layout in &record.layouts {
+            if let Some(layout_info) = self.layouts.get(layout.as_path()) {
+                templates.push(layout_info.content.clone());
             }
-            
-            // Update the output path
-            let page_path = paginate_path.replace(":num", &(page_num + 1).to_string());
-            let dest_path = Path::new(&config.destination).join(page_path.trim_start_matches('/'));
-            
-            // Ensure directory exists
-            if let Some(parent) = dest_path.parent() {
-                if !parent.exists() {
-                    fs::create_dir_all(parent)?;
-                }
+        }
+        for include in &record.includes {
+            if let Ok(content) = fs::read_to_string(include) {
+                templates.push(content);
             }
-            
-            // Set the new output path
-            new_page.output_path = Some(dest_path);
-            
-            // Add the new page
-            new_pages.push(new_page);
         }
         
-        // Update the first page (original page)
-        if let Some(ref mut pagination) = pages[index].front_matter.pagination {
-            pagination.per_page = per_page;
-            // Use different variables to represent pagination state
-            
-            // Add the post references for the first page
-            let end = std::cmp::min(per_page, total_posts);
-            let page_posts = &paginate_posts[0..end];
-            
-            pagination.posts = page_posts.iter()
-                .map(|doc| doc.id.clone())
-                .collect();
+        let mut uses_site_content = false;
+        for template in &templates {
+            uses_site_content |= self.site_content_regex.is_match(template);
+            dependencies.extend(self.referenced_data_files(template));
+        }
+        
+        OutputRecord {
+            source: record.source.clone(),
+            dependencies,
+            uses_site_content,
+            ..OutputRecord::default()
         }
     }
     
-    // Add all new pages to the pages vector
-    pages.extend(new_pages);
-    
-    Ok(())
+    /// Find the data files referenced through `site.data` in a template
+    fn referenced_data_files(&self, template: &str) -> Vec<PathBuf> {
+        let mut keys = HashSet::new();
+        for caps in self.data_regex.captures_iter(template) {
+            match caps.get(1).or_else(|| caps.get(2)) {
+                Some(key) => {
+                    keys.insert(key.as_str().replace('-', "_"));
+                },
+                // `site.data` used as a whole, e.g. iterated or indexed by a variable
+                None => return self.data_files.clone(),
+            }
+        }
+        
+        if keys.is_empty() {
+            return Vec::new();
+        }
+        
+        self.data_files.iter()
+            .filter(|path| {
+                // The data key is the first path component, minus the extension for files
+                let relative = path.strip_prefix(&self.data_dir).unwrap_or(path);
+                let key = if relative.components().count() > 1 {
+                    relative.components().next().map(|c| c.as_os_str().to_string_lossy().to_string())
+                } else {
+                    relative.file_stem().map(|stem| stem.to_string_lossy().to_string())
+                };
+                key.is_some_and(|key| keys.contains(&key.replace('-', "_")))
+            })
+            .cloned()
+            .collect()
+    }
 }
 
 /// Check if a file is excluded based on config
 pub fn is_excluded(path: &Path, config: &Config) -> bool {
     config.is_excluded(path)
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestSite;
+
+    #[test]
+    fn test_site_phases() {
+        let test_site = TestSite::new("site");
+        test_site.write("_layouts/default.html", "<main>{{ content }}</main>")
+            .write("_posts/2024-01-01-hello.md", "---\nlayout: default\ntitle: Hello\n---\nHello *world*")
+            .write("index.html", "---\nlayout: default\n---\n{% for post in site.posts %}{{ post.title }}{% endfor %}");
+        let mut site = Site::new(test_site.config());
+
+        site.read().unwrap();
+        assert_eq!(site.pages().len(), 1);
+        assert_eq!(site.collections()["posts"].documents.len(), 1);
+
+        // Rendering happens in memory
+        site.render().unwrap();
+        let index = site.outputs().iter()
+            .find(|output| output.record.source == test_site.path("index.html"))
+            .unwrap();
+        assert_eq!(index.content.as_deref(), Some("<main>Hello</main>"));
+        let output = index.record.output.clone();
+        assert!(!output.exists());
+
+        site.write().unwrap();
+        assert!(output.exists());
+        assert_eq!(site.stats().errors_count, 0);
+    }
+
+    #[test]
+    fn test_concurrent_sites() {
+        let build = |site: &TestSite, name: &str, pages: usize| {
+            for i in 0..pages {
+                site.write(&format!("{}-{}.md", name, i), "---\n---\n{{ x | }}");
+            }
+            Site::new(Config { profile: Some(true), ..site.config() }).process().unwrap()
+        };
+        let first = TestSite::new("concurrent-first");
+        let second = TestSite::new("concurrent-second");
+
+        // Each site only sees the problems and timings of its own pages
+        let outcomes = std::thread::scope(|scope| {
+            let first_outcome = scope.spawn(|| build(&first, "first", 20));
+            let second_outcome = scope.spawn(|| build(&second, "second", 30));
+            [(&first, 20, first_outcome.join().unwrap()), (&second, 30, second_outcome.join().unwrap())]
+        });
+        for (site, expected, outcome) in outcomes {
+            assert_eq!(diagnostics::error_count(&outcome.diagnostics), expected);
+            assert_eq!(outcome.stats.errors_count, expected);
+            assert!(outcome.diagnostics.iter().all(|diagnostic| diagnostic.file.starts_with(site.source())));
+            let pages = outcome.profile.iter().filter(|entry| entry.kind == profiler::TemplateKind::Page).count();
+            assert_eq!(pages, expected);
+        }
+    }
+
+    #[test]
+    fn test_site_timezones() {
+        let build = |name: &str, timezone: &str| {
+            let test_site = TestSite::new(name);
+            test_site.write("_layouts/post.html", "{{ page.date | date: '%d %H:%M' }}")
+                .write("_posts/2024-01-01-hello.md", "---\nlayout: post\ntitle: Hello\ndate: 2024-01-01 23:30:00 +0000\n---\nHello");
+            let site = Site::new(Config { timezone: Some(timezone.to_string()), ..test_site.config() });
+            (test_site, site)
+        };
+        let (new_york, mut first) = build("timezone-new-york", "America/New_York");
+        let (tokyo, mut second) = build("timezone-tokyo", "Asia/Tokyo");
+
+        // Each site keeps its own timezone while the other one is read
+        first.read().unwrap();
+        second.read().unwrap();
+        first.render().unwrap();
+        second.render().unwrap();
+        first.write().unwrap();
+        second.write().unwrap();
+
+        assert_eq!(new_york.read("2024/01/01/hello"), "01 18:30");
+        assert_eq!(tokyo.read("2024/01/02/hello"), "02 08:30");
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{debug, warn};
+use rayon::prelude::*;
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::Config;
+use crate::builder::types::BoxResult;
+
+/// Name of the incremental cache file inside the cache directory
+const CACHE_FILE: &str = "incremental.json";
+
+/// Compute the SHA-256 hash of a byte slice as a hex string
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Compute the SHA-256 hash of a file's contents
+pub fn hash_file(path: &Path) -> Option<String> {
+    fs::read(path).ok().map(|bytes| hash_bytes(&bytes))
+}
+
+/// Hashes of the input files of the current build
+#[derive(Debug, Default)]
+pub struct FileHashes {
+    hashes: HashMap<PathBuf, String>,
+}
+
+impl FileHashes {
+    /// Hash a set of files in parallel
+    pub fn compute(paths: &[PathBuf]) -> Self {
+        let hashes = paths.par_iter()
+            .filter_map(|path| hash_file(path).map(|hash| (path.clone(), hash)))
+            .collect();
+
+        Self { hashes }
+    }
+
+    /// Get the hash of a file, hashing it now if it was not part of the initial set
+    pub fn get(&mut self, path: &Path) -> Option<String> {
+        if let Some(hash) = self.hashes.get(path) {
+            return Some(hash.clone());
+        }
+
+        let hash = hash_file(path)?;
+        self.hashes.insert(path.to_path_buf(), hash.clone());
+        Some(hash)
+    }
+
+    /// Combined fingerprint of a set of files, independent of their order
+    pub fn fingerprint<'a>(&mut self, paths: impl Iterator<Item = &'a PathBuf>) -> String {
+        let mut entries: Vec<String> = paths
+            .map(|path| format!("{}:{}", path.display(), self.get(path).unwrap_or_default()))
+            .collect();
+        entries.sort();
+        hash_bytes(entries.join("\n").as_bytes())
+    }
+}
+
+/// What a generated output file was built from
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OutputRecord {
+    /// The source file the output was generated from
+    pub source: PathBuf,
+    /// Layouts, includes and data files the output used
+    pub dependencies: BTreeSet<PathBuf>,
+    /// Whether the output lists other site content (site.posts, paginator, ...)
+    pub uses_site_content: bool,
+    /// Content hashes of the source and dependencies when the output was written
+    #[serde(default)]
+    pub hashes: BTreeMap<PathBuf, String>,
+}
+
+/// The incremental build cache structure
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct IncrementalCache {
+    /// Fingerprint of the configuration used for the previous build
+    config_hash: String,
+    /// Fingerprint of all pages and documents of the previous build
+    content_hash: String,
+    /// Map of output paths to the inputs they were generated from
+    outputs: HashMap<PathBuf, OutputRecord>,
+}
+
+impl IncrementalCache {
+    /// Load the cache from disk
+    pub fn load(config: &Config) -> Self {
+        let cache_path = Path::new(&config.source).join(&config.cache_dir).join(CACHE_FILE);
+        if !cache_path.exists() {
+            return Self::default();
+        }
+
+        match fs::read_to_string(&cache_path) {
+            Ok(content) => match serde_json::from_str(&content) {
+                Ok(cache) => cache,
+                Err(e) => {
+                    warn!("Failed to parse cache file: {}", e);
+                    Self::default()
+                }
+            },
+            Err(e) => {
+                warn!("Failed to read cache file: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Save the cache to disk
+    pub fn save(&self, config: &Config) -> BoxResult<()> {
+        let cache_dir = Path::new(&config.source).join(&config.cache_dir);
+        if !cache_dir.exists() {
+            fs::create_dir_all(&cache_dir)?;
+        }
+
+        let cache_path = cache_dir.join(CACHE_FILE);
+        let json = serde_json::to_string(self)?;
+        fs::write(cache_path, json)?;
+        Ok(())
+    }
+
+    /// Check the configuration fingerprint, discarding the cache if it changed
+    ///
+    /// Returns true if the cache was discarded.
+    pub fn check_config(&mut self, config_hash: &str) -> bool {
+        if self.config_hash == config_hash {
+            return false;
+        }
+
+        let had_cache = !self.config_hash.is_empty();
+        *self = Self {
+            config_hash: config_hash.to_string(),
+            ..Self::default()
+        };
+        had_cache
+    }
+
+    /// Update the content fingerprint, returning true if it changed
+    pub fn update_content_hash(&mut self, content_hash: String) -> bool {
+        let changed = self.content_hash != content_hash;
+        self.content_hash = content_hash;
+        changed
+    }
+
+    /// Check if an output has to be regenerated
+    pub fn needs_rebuild(
+        &self,
+        output: &Path,
+        source: &Path,
+        hashes: &mut FileHashes,
+        content_changed: bool,
+    ) -> bool {
+        // Outputs that were never generated, or were removed since
+        let record = match self.outputs.get(output) {
+            Some(record) if output.exists() => record,
+            _ => return true,
+        };
+
+        // The output now comes from a different source
+        if record.source != source {
+            return true;
+        }
+
+        if record.uses_site_content && content_changed {
+            return true;
+        }
+
+        // Any input whose content differs from when the output was written
+        std::iter::once(source)
+            .chain(record.dependencies.iter().map(|dep| dep.as_path()))
+            .any(|path| match (record.hashes.get(path), hashes.get(path)) {
+                (Some(recorded), Some(current)) => *recorded != current,
+                (None, None) => false, // Never existed, like the stand-in source of a feed
+                _ => true, // New or removed file, consider it modified
+            })
+    }
+
+    /// Forget an output so it is regenerated on the next build
+    pub fn invalidate(&mut self, output: &Path) {
+        self.outputs.remove(output);
+    }
+
+    /// Record the inputs an output was generated from
+    pub fn record(&mut self, output: PathBuf, mut record: OutputRecord, hashes: &mut FileHashes) {
+        let inputs: Vec<PathBuf> = std::iter::once(&record.source)
+            .chain(record.dependencies.iter())
+            .cloned()
+            .collect();
+        for path in inputs {
+            if let Some(hash) = hashes.get(&path) {
+                record.hashes.insert(path, hash);
+            }
+        }
+
+        self.outputs.insert(output, record);
+    }
+
+    /// Remove outputs that are no longer generated from the destination
+    ///
+    /// Returns the number of files removed.
+    pub fn remove_stale_outputs(&mut self, planned: &HashSet<PathBuf>) -> usize {
+        let stale: Vec<PathBuf> = self.outputs.keys()
+            .filter(|output| !planned.contains(*output))
+            .cloned()
+            .collect();
+
+        let mut removed = 0;
+        for output in stale {
+            self.outputs.remove(&output);
+            if output.exists() {
+                match fs::remove_file(&output) {
+                    Ok(_) => {
+                        debug!("Removed stale output {}", output.display());
+                        removed += 1;
+                    },
+                    Err(e) => warn!("Failed to remove stale output {}: {}", output.display(), e),
+                }
+            }
+        }
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestSite;
+
+    #[test]
+    fn test_needs_rebuild_tracks_dependencies() {
+        let site = TestSite::new("cache");
+        let source = site.path("page.md");
+        let layout = site.path("default.html");
+        let output = site.path("page.html");
+        fs::write(&source, "content").unwrap();
+        fs::write(&layout, "{{ content }}").unwrap();
+        fs::write(&output, "rendered").unwrap();
+
+        let mut cache = IncrementalCache::default();
+        let mut hashes = FileHashes::compute(&[source.clone(), layout.clone()]);
+        assert!(cache.needs_rebuild(&output, &source, &mut hashes, false));
+
+        let record = OutputRecord {
+            source: source.clone(),
+            dependencies: [layout.clone()].into_iter().collect(),
+            ..OutputRecord::default()
+        };
+        cache.record(output.clone(), record, &mut hashes);
+        assert!(!cache.needs_rebuild(&output, &source, &mut hashes, true));
+
+        // Touching a file without changing its content is not a change
+        fs::write(&layout, "{{ content }}").unwrap();
+        let mut hashes = FileHashes::compute(&[source.clone(), layout.clone()]);
+        assert!(!cache.needs_rebuild(&output, &source, &mut hashes, false));
+
+        fs::write(&layout, "<main>{{ content }}</main>").unwrap();
+        let mut hashes = FileHashes::compute(&[source.clone(), layout.clone()]);
+        assert!(cache.needs_rebuild(&output, &source, &mut hashes, false));
+
+        // Outputs that are no longer planned get deleted
+        assert_eq!(cache.remove_stale_outputs(&HashSet::new()), 1);
+        assert!(!output.exists());
+    }
+}
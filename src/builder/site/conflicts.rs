@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::builder::diagnostics::{self, Category, Diagnostic, Severity};
+
+/// Sources that would be written to the same output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputConflict {
+    /// The contested output paths; more than one if they only differ in case
+    pub outputs: Vec<PathBuf>,
+    /// The sources writing to them, in order
+    pub sources: Vec<PathBuf>,
+}
+
+impl OutputConflict {
+    /// Whether the outputs are distinct paths that only differ in case
+    pub fn is_case_conflict(&self) -> bool {
+        self.outputs.len() > 1
+    }
+}
+
+/// Find outputs that more than one source would be written to
+///
+/// Takes `(output, source)` pairs. Outputs that only differ in case are
+/// conflicts too, since they overwrite each other on case-insensitive file
+/// systems such as the macOS default.
+pub fn find_conflicts(outputs: &[(PathBuf, PathBuf)]) -> Vec<OutputConflict> {
+    let mut groups: BTreeMap<String, BTreeMap<&Path, Vec<&Path>>> = BTreeMap::new();
+    for (output, source) in outputs {
+        let key = output.to_string_lossy().to_lowercase();
+        let sources = groups.entry(key).or_default().entry(output.as_path()).or_default();
+        // The same file listed twice is not a conflict
+        if !sources.contains(&source.as_path()) {
+            sources.push(source.as_path());
+        }
+    }
+
+    groups.into_values()
+        .filter(|paths| paths.len() > 1 || paths.values().any(|sources| sources.len() > 1))
+        .map(|paths| OutputConflict {
+            outputs: paths.keys().map(|output| output.to_path_buf()).collect(),
+            sources: paths.values().flatten().map(|source| source.to_path_buf()).collect(),
+        })
+        .collect()
+}
+
+/// Report outputs that more than one source would be written to
+///
+/// Conflicts are warnings, or errors that fail the build in strict mode.
+/// Returns the number of conflicts found.
+pub fn report_conflicts(outputs: &[(PathBuf, PathBuf)], config: &Config) -> usize {
+    let conflicts = find_conflicts(outputs);
+    let severity = if config.strict.unwrap_or(false) { Severity::Error } else { Severity::Warning };
+
+    for conflict in &conflicts {
+        let relative = |path: &PathBuf, root: &Path| path.strip_prefix(root).unwrap_or(path).display().to_string();
+        let outputs: Vec<String> = conflict.outputs.iter()
+            .map(|output| relative(output, &config.destination))
+            .collect();
+        let sources: Vec<String> = conflict.sources.iter()
+            .map(|source| relative(source, &config.source))
+            .collect();
+
+        let message = if conflict.is_case_conflict() {
+            format!("Conflict: {} only differ in case and overwrite each other on case-insensitive file systems. Written by: {}",
+                    outputs.join(", "), sources.join(", "))
+        } else {
+            format!("Conflict: {} is written by multiple files, the output may end up with unexpected contents: {}",
+                    outputs[0], sources.join(", "))
+        };
+
+        diagnostics::report(Diagnostic::new(severity, Category::Output, &conflict.sources[0], message));
+    }
+
+    conflicts.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_conflicts() {
+        let pair = |output: &str, source: &str| (PathBuf::from(output), PathBuf::from(source));
+        let outputs = vec![
+            pair("_site/about/index.html", "about.md"),
+            pair("_site/2024/hello.html", "_posts/2024-01-01-hello.md"),
+            pair("_site/about/index.html", "_posts/2024-02-01-about.md"),
+            pair("_site/Blog/index.html", "Blog/index.html"),
+            pair("_site/blog/index.html", "blog.md"),
+            pair("_site/css/main.css", "css/main.css"),
+            pair("_site/css/main.css", "css/main.css"),
+        ];
+
+        let conflicts = find_conflicts(&outputs);
+        assert_eq!(conflicts.len(), 2);
+
+        assert!(!conflicts[0].is_case_conflict());
+        assert_eq!(conflicts[0].outputs, vec![PathBuf::from("_site/about/index.html")]);
+        assert_eq!(conflicts[0].sources, vec![PathBuf::from("about.md"), PathBuf::from("_posts/2024-02-01-about.md")]);
+
+        assert!(conflicts[1].is_case_conflict());
+        assert_eq!(conflicts[1].sources, vec![PathBuf::from("Blog/index.html"), PathBuf::from("blog.md")]);
+    }
+}
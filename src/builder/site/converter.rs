@@ -20,8 +20,8 @@ pub fn page_to_liquid(page: &Page) -> liquid::model::Value {
     }
     
     if let Some(date) = &page.date {
-        // Convert DateTime to string in RFC3339 format
-        obj.insert("date".into(), Value::scalar(date.to_rfc3339()));
+        // Convert DateTime to string in RFC3339 format, in the site's timezone
+        obj.insert("date".into(), Value::scalar(crate::utils::date::local(*date).to_rfc3339()));
     }
     
     // Front matter properties (always include title and description for Jekyll compatibility)
@@ -67,6 +67,11 @@ pub fn page_to_liquid(page: &Page) -> liquid::model::Value {
         }
     }
     
+    // Variables set by generators
+    for (key, value) in page.data.iter() {
+        obj.insert(key.clone(), value.clone());
+    }
+    
     Value::Object(obj)
 }
 
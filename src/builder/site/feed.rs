@@ -0,0 +1,658 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use lazy_static::lazy_static;
+use liquid::model::ValueView;
+use log::{debug, info};
+use regex::Regex;
+use serde_json::json;
+
+use crate::config::{Config, FeedConfig};
+use crate::collections::Collection;
+use crate::collections::document::model::Document;
+use crate::collections::types::DataCollection;
+use crate::directory::DirectoryStructure;
+use crate::utils::date::local;
+use crate::builder::site::{absolute_document_url, RenderRecord, RenderedOutput};
+
+lazy_static! {
+    static ref TAG_REGEX: Regex = Regex::new(r"<[^>]*>").unwrap();
+    static ref WHITESPACE_REGEX: Regex = Regex::new(r"\s+").unwrap();
+}
+
+/// A feed of a collection, optionally of one category or tag
+#[derive(Debug, Clone)]
+pub struct Feed {
+    collection: String,
+    category: Option<String>,
+    tag: Option<String>,
+    files: Vec<FeedFile>,
+    /// Sources of the documents listed, newest first
+    pub documents: Vec<PathBuf>,
+}
+
+/// A feed in one format
+#[derive(Debug, Clone)]
+struct FeedFile {
+    format: &'static str,
+    path: String,
+    output: PathBuf,
+    /// Feeds have no source file, so the path a feed page would have in
+    /// the source directory stands in for it
+    source: PathBuf,
+}
+
+impl Feed {
+    /// Output files of the feed, paired with the source they are recorded with
+    pub fn outputs(&self) -> Vec<(PathBuf, PathBuf)> {
+        self.files.iter().map(|file| (file.output.clone(), file.source.clone())).collect()
+    }
+}
+
+/// Work out which feeds to generate and the documents they list
+///
+/// Follows jekyll-feed: a feed of posts, feeds of configured categories and
+/// collections, and optionally one per tag. Feeds whose path is already
+/// taken by a page or a static file are not generated.
+pub fn plan_feeds(
+    collections: &HashMap<String, Collection>,
+    dirs: &DirectoryStructure,
+    config: &Config,
+    taken: &HashSet<PathBuf>
+) -> Vec<Feed> {
+    let feed_config = match config.feed_config() {
+        Some(feed_config) => feed_config,
+        None => return Vec::new(),
+    };
+
+    let mut feeds = Vec::new();
+    let mut push = |collection: &str, category: Option<&str>, tag: Option<&str>, path: String| {
+        let documents = match collections.get(collection) {
+            Some(collection) if collection.output => feed_documents(collection, category, tag, &feed_config, config),
+            _ => return,
+        };
+        let files: Vec<_> = feed_config.format_paths(&path).into_iter()
+            .map(|(format, _, path)| FeedFile {
+                format,
+                output: dirs.destination.join(path.trim_start_matches('/')),
+                source: dirs.source.join(path.trim_start_matches('/')),
+                path,
+            })
+            .filter(|file| {
+                let free = !taken.contains(&file.output);
+                if !free {
+                    debug!("Not generating feed {}, the path is taken", file.path);
+                }
+                free
+            })
+            .collect();
+        if !files.is_empty() {
+            feeds.push(Feed {
+                collection: collection.to_string(),
+                category: category.map(str::to_string),
+                tag: tag.map(str::to_string),
+                files,
+                documents: documents.iter().map(|doc| doc.path.clone()).collect(),
+            });
+        }
+    };
+
+    // The posts feed, and a feed for each configured category of posts
+    let collection_options = feed_config.collections();
+    let posts_options = collection_options.iter().find(|(name, _)| name == "posts").map(|(_, options)| options);
+    push("posts", None, None, feed_config.posts_path());
+
+    let mut categories: BTreeSet<&String> = feed_config.categories.iter().flatten().collect();
+    categories.extend(posts_options.and_then(|options| options.categories.as_ref()).into_iter().flatten());
+    for category in categories {
+        push("posts", Some(category), None, format!("/feed/{}.xml", category));
+    }
+
+    // Tag feeds cover every tag of the posts in any feed
+    if let Some(posts) = collections.get("posts") {
+        let tags: BTreeSet<&String> = posts.documents.iter()
+            .filter(|doc| in_feed(doc, config))
+            .flat_map(|doc| doc.tags.iter())
+            .filter(|tag| feed_config.has_tag_feed(tag))
+            .collect();
+        for tag in tags {
+            if tag.contains(['/', '\\']) || tag.starts_with('.') {
+                debug!("Not generating a feed for tag '{}', it is not a valid file name", tag);
+                continue;
+            }
+            let path = format!("{}/{}.xml", feed_config.tags_path().trim_end_matches('/'), tag);
+            push("posts", None, Some(tag), path);
+        }
+    }
+
+    for (name, options) in collection_options.iter().filter(|(name, _)| name != "posts") {
+        let path = options.path.clone().unwrap_or_else(|| format!("/feed/{}.xml", name));
+        push(name, None, None, path);
+        for category in options.categories.iter().flatten() {
+            push(name, Some(category), None, format!("/feed/{}/{}.xml", name, category));
+        }
+    }
+
+    feeds
+}
+
+/// Render the planned feeds from the rendered documents
+pub fn render_feeds(
+    feeds: &[Feed],
+    collections: &HashMap<String, Collection>,
+    data: &DataCollection,
+    config: &Config
+) -> Vec<RenderedOutput> {
+    let feed_config = config.feed_config().unwrap_or_default();
+    let documents: HashMap<&Path, &Document> = collections.values()
+        .flat_map(|collection| collection.documents.iter())
+        .map(|doc| (doc.path.as_path(), doc))
+        .collect();
+    let authors_files = authors_data_files(config);
+
+    let mut outputs = Vec::new();
+    for feed in feeds {
+        let entries: Vec<Entry> = feed.documents.iter()
+            .filter_map(|path| documents.get(path.as_path()))
+            .map(|doc| Entry::new(doc, data, &feed_config, config))
+            .collect();
+        let channel = Channel::new(feed, data, &feed_config, config);
+
+        for file in &feed.files {
+            let self_url = config.absolute_url(&file.path);
+            let content = match file.format {
+                "rss" => rss(&channel, &self_url, &entries),
+                "json" => json_feed(&channel, &self_url, &entries),
+                _ => atom(&channel, &self_url, &entries),
+            };
+            outputs.push(RenderedOutput {
+                record: RenderRecord {
+                    source: file.source.clone(),
+                    output: file.output.clone(),
+                    layouts: Vec::new(),
+                    includes: authors_files.clone(),
+                },
+                content: Some(content),
+            });
+        }
+    }
+
+    if !outputs.is_empty() {
+        info!("Generated {} feeds", outputs.len());
+    }
+
+    outputs
+}
+
+/// Check if a document can appear in feeds
+fn in_feed(doc: &Document, config: &Config) -> bool {
+    doc.front_matter.rss
+        && doc.url.is_some()
+        && doc.should_write(config)
+        && (!doc.front_matter.is_draft() || config.show_drafts.unwrap_or(false))
+}
+
+/// The documents of a feed, newest first
+fn feed_documents<'a>(
+    collection: &'a Collection,
+    category: Option<&str>,
+    tag: Option<&str>,
+    feed_config: &FeedConfig,
+    config: &Config
+) -> Vec<&'a Document> {
+    let mut docs: Vec<&Document> = collection.documents.iter()
+        .filter(|doc| in_feed(doc, config))
+        .filter(|doc| category.is_none_or(|category| doc.categories.iter().any(|name| name == category)))
+        .filter(|doc| tag.is_none_or(|tag| doc.tags.iter().any(|name| name == tag)))
+        .collect();
+    docs.sort_by_key(|doc| std::cmp::Reverse(doc.date.or(doc.mtime)));
+    docs.truncate(feed_config.posts_limit);
+    docs
+}
+
+/// Data files that `_data/authors` is read from
+fn authors_data_files(config: &Config) -> Vec<PathBuf> {
+    let data_dir = config.source.join(&config.data_dir);
+    let mut files: Vec<PathBuf> = ["yml", "yaml", "json", "csv", "tsv"].iter()
+        .map(|extension| data_dir.join(format!("authors.{}", extension)))
+        .filter(|path| path.exists())
+        .collect();
+    if let Ok(entries) = std::fs::read_dir(data_dir.join("authors")) {
+        files.extend(entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()));
+    }
+    files.sort();
+    files
+}
+
+/// An author of a feed or entry
+#[derive(Debug, Clone, Default)]
+struct Author {
+    name: String,
+    email: Option<String>,
+    uri: Option<String>,
+}
+
+impl Author {
+    /// Resolve an author name or object, looking names up in `_data/authors`
+    ///
+    /// Authors without a name are left out of feeds, like jekyll-feed does.
+    fn resolve(value: &serde_yaml::Value, data: &DataCollection) -> Option<Self> {
+        let author = match value {
+            serde_yaml::Value::String(name) => Some(Self::from_data(name, data).unwrap_or_else(|| Author {
+                name: name.clone(),
+                ..Author::default()
+            })),
+            serde_yaml::Value::Mapping(map) => {
+                let field = |key: &str| map.get(key).and_then(|value| value.as_str()).map(str::to_string);
+                Some(Author {
+                    name: field("name").unwrap_or_default(),
+                    email: field("email"),
+                    uri: field("uri"),
+                })
+            },
+            _ => None,
+        };
+        author.filter(|author| !author.name.is_empty())
+    }
+
+    /// Look an author up in `_data/authors`
+    fn from_data(name: &str, data: &DataCollection) -> Option<Self> {
+        let entry = data.get("authors")?.as_object()?.get(name)?;
+        let author = match entry.as_object() {
+            Some(author) => author,
+            None => return Some(Author { name: entry.to_kstr().to_string(), ..Author::default() }),
+        };
+        let field = |key: &str| author.get(key).filter(|value| !value.is_nil()).map(|value| value.to_kstr().to_string());
+        Some(Author {
+            name: field("name").unwrap_or_else(|| name.to_string()),
+            email: field("email"),
+            uri: field("uri"),
+        })
+    }
+}
+
+/// The site's author, from `author` in the config
+fn site_author(config: &Config) -> Option<&serde_yaml::Value> {
+    config.site_data.author.as_ref().or_else(|| config.site_data.custom.get("author"))
+}
+
+/// A custom string setting of the site, like `lang`
+fn site_setting<'a>(config: &'a Config, key: &str) -> Option<&'a str> {
+    config.site_data.custom.get(key).and_then(|value| value.as_str())
+}
+
+/// What a feed says about itself
+struct Channel {
+    title: Option<String>,
+    subtitle: Option<String>,
+    home: String,
+    lang: Option<String>,
+    updated: DateTime<Tz>,
+    author: Option<Author>,
+    icon: Option<String>,
+    logo: Option<String>,
+}
+
+impl Channel {
+    fn new(feed: &Feed, data: &DataCollection, feed_config: &FeedConfig, config: &Config) -> Self {
+        // The site title, then the collection, category and tag, like jekyll-feed
+        let mut title = config.site_data.title.clone().unwrap_or_else(|| config.title.clone());
+        if feed.collection != "posts" {
+            title = format!("{} | {}", title, capitalize(&feed.collection));
+        }
+        for name in feed.category.iter().chain(feed.tag.iter()) {
+            title = format!("{} | {}", title, capitalize(name));
+        }
+
+        let description = config.site_data.description.clone().unwrap_or_else(|| config.description.clone());
+        Channel {
+            title: (!title.is_empty()).then_some(title),
+            subtitle: (!description.is_empty()).then_some(description),
+            home: config.absolute_url("/"),
+            lang: site_setting(config, "lang").map(str::to_string),
+            updated: local(Utc::now()),
+            author: site_author(config).and_then(|author| Author::resolve(author, data)),
+            icon: feed_config.icon.as_deref().map(|icon| config.absolute_url(icon)),
+            logo: feed_config.logo.as_deref().map(|logo| config.absolute_url(logo)),
+        }
+    }
+}
+
+/// A document as it appears in feeds
+struct Entry<'a> {
+    title: String,
+    url: String,
+    id: String,
+    published: Option<DateTime<Tz>>,
+    updated: Option<DateTime<Tz>>,
+    content: Option<&'a str>,
+    author: Option<Author>,
+    categories: Vec<&'a str>,
+    summary: Option<String>,
+    image: Option<String>,
+    lang: Option<&'a str>,
+}
+
+impl<'a> Entry<'a> {
+    fn new(
+        doc: &'a Document,
+        data: &DataCollection,
+        feed_config: &FeedConfig,
+        config: &Config
+    ) -> Self {
+        let url = absolute_document_url(doc.url.as_deref().unwrap_or("/"), config);
+        let published = doc.date.or(doc.mtime).map(local);
+
+        // Per-document feed settings override the site's
+        let excerpt_only = doc.front_matter.custom.get("feed")
+            .and_then(|feed| feed.get("excerpt_only"))
+            .and_then(|excerpt_only| excerpt_only.as_bool())
+            .unwrap_or(feed_config.excerpt_only);
+
+        // The document's author, its first author or the site's
+        let author = doc.front_matter.author.clone().map(serde_yaml::Value::String)
+            .or_else(|| doc.front_matter.authors.as_ref().and_then(|authors| authors.first().cloned()))
+            .or_else(|| site_author(config).cloned())
+            .and_then(|author| Author::resolve(&author, data));
+
+        // The description, or the excerpt as plain text
+        let summary = doc.front_matter.description.clone()
+            .or_else(|| doc.rendered_excerpt.clone())
+            .map(|summary| plain_text(&summary))
+            .filter(|summary| !summary.is_empty());
+
+        Entry {
+            title: plain_text(&doc.title().unwrap_or_default()),
+            id: url.strip_suffix(".html").unwrap_or(&url).to_string(),
+            url,
+            published,
+            updated: doc.front_matter.get_last_modified().map(local).or(published),
+            content: if excerpt_only { None } else { doc.rendered_content.as_deref().map(str::trim) },
+            author,
+            categories: doc.categories.iter().chain(doc.tags.iter()).map(String::as_str).collect(),
+            summary,
+            image: doc.front_matter.image.as_deref().map(|image| config.absolute_url(image)),
+            lang: doc.front_matter.custom.get("lang").and_then(|lang| lang.as_str()),
+        }
+    }
+}
+
+/// Write an Atom feed, with the markup of jekyll-feed
+fn atom(channel: &Channel, self_url: &str, entries: &[Entry]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    match &channel.lang {
+        Some(lang) => xml.push_str(&format!("<feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"{}\">\n", escape(lang))),
+        None => xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n"),
+    }
+    xml.push_str(&format!("<generator uri=\"{}\" version=\"{}\">Rustyll</generator>\n",
+                          env!("CARGO_PKG_HOMEPAGE"), env!("CARGO_PKG_VERSION")));
+    xml.push_str(&format!("<link href=\"{}\" rel=\"self\" type=\"application/atom+xml\" />\n", escape(self_url)));
+    match &channel.lang {
+        Some(lang) => xml.push_str(&format!("<link href=\"{}\" rel=\"alternate\" type=\"text/html\" hreflang=\"{}\" />\n",
+                                            escape(&channel.home), escape(lang))),
+        None => xml.push_str(&format!("<link href=\"{}\" rel=\"alternate\" type=\"text/html\" />\n", escape(&channel.home))),
+    }
+    xml.push_str(&format!("<updated>{}</updated>\n", xmlschema(&channel.updated)));
+    xml.push_str(&format!("<id>{}</id>\n", escape(self_url)));
+    if let Some(title) = &channel.title {
+        xml.push_str(&format!("<title type=\"html\">{}</title>\n", escape(title)));
+    }
+    if let Some(subtitle) = &channel.subtitle {
+        xml.push_str(&format!("<subtitle>{}</subtitle>\n", escape(subtitle)));
+    }
+    if let Some(author) = &channel.author {
+        atom_author(&mut xml, author);
+    }
+    if let Some(icon) = &channel.icon {
+        xml.push_str(&format!("<icon>{}</icon>\n", escape(icon)));
+    }
+    if let Some(logo) = &channel.logo {
+        xml.push_str(&format!("<logo>{}</logo>\n", escape(logo)));
+    }
+
+    for entry in entries {
+        match entry.lang {
+            Some(lang) => xml.push_str(&format!("<entry xml:lang=\"{}\">", escape(lang))),
+            None => xml.push_str("<entry>"),
+        }
+        xml.push_str(&format!("<title type=\"html\">{}</title>", escape(&entry.title)));
+        xml.push_str(&format!("<link href=\"{}\" rel=\"alternate\" type=\"text/html\" title=\"{}\" />",
+                              escape(&entry.url), escape(&entry.title)));
+        if let Some(published) = &entry.published {
+            xml.push_str(&format!("<published>{}</published>", xmlschema(published)));
+        }
+        if let Some(updated) = &entry.updated {
+            xml.push_str(&format!("<updated>{}</updated>", xmlschema(updated)));
+        }
+        xml.push_str(&format!("<id>{}</id>", escape(&entry.id)));
+        if let Some(content) = entry.content {
+            xml.push_str(&format!("<content type=\"html\" xml:base=\"{}\">{}</content>", escape(&entry.url), cdata(content)));
+        }
+        if let Some(author) = &entry.author {
+            atom_author(&mut xml, author);
+        }
+        for category in &entry.categories {
+            xml.push_str(&format!("<category term=\"{}\" />", escape(category)));
+        }
+        if let Some(summary) = &entry.summary {
+            xml.push_str(&format!("<summary type=\"html\">{}</summary>", cdata(summary)));
+        }
+        if let Some(image) = &entry.image {
+            xml.push_str(&format!("<media:thumbnail xmlns:media=\"http://search.yahoo.com/mrss/\" url=\"{}\" />", escape(image)));
+            xml.push_str(&format!("<media:content medium=\"image\" url=\"{}\" xmlns:media=\"http://search.yahoo.com/mrss/\" />", escape(image)));
+        }
+        xml.push_str("</entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+/// Write the author element of an Atom feed or entry
+fn atom_author(xml: &mut String, author: &Author) {
+    xml.push_str(&format!("<author><name>{}</name>", escape(&author.name)));
+    if let Some(email) = &author.email {
+        xml.push_str(&format!("<email>{}</email>", escape(email)));
+    }
+    if let Some(uri) = &author.uri {
+        xml.push_str(&format!("<uri>{}</uri>", escape(uri)));
+    }
+    xml.push_str("</author>");
+}
+
+/// Write an RSS 2.0 feed
+fn rss(channel: &Channel, self_url: &str, entries: &[Entry]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\" \
+                   xmlns:dc=\"http://purl.org/dc/elements/1.1/\" xmlns:media=\"http://search.yahoo.com/mrss/\">\n<channel>\n");
+    xml.push_str(&format!("<title>{}</title>\n", escape(channel.title.as_deref().unwrap_or(""))));
+    xml.push_str(&format!("<link>{}</link>\n", escape(&channel.home)));
+    xml.push_str(&format!("<description>{}</description>\n", escape(channel.subtitle.as_deref().unwrap_or(""))));
+    if let Some(lang) = &channel.lang {
+        xml.push_str(&format!("<language>{}</language>\n", escape(lang)));
+    }
+    xml.push_str(&format!("<lastBuildDate>{}</lastBuildDate>\n", rfc822(&channel.updated)));
+    xml.push_str(&format!("<generator>Rustyll {}</generator>\n", env!("CARGO_PKG_VERSION")));
+    xml.push_str(&format!("<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\" />\n", escape(self_url)));
+    if let Some(logo) = channel.logo.as_ref().or(channel.icon.as_ref()) {
+        xml.push_str(&format!("<image><url>{}</url><title>{}</title><link>{}</link></image>\n",
+                              escape(logo), escape(channel.title.as_deref().unwrap_or("")), escape(&channel.home)));
+    }
+
+    for entry in entries {
+        xml.push_str("<item>");
+        xml.push_str(&format!("<title>{}</title>", escape(&entry.title)));
+        xml.push_str(&format!("<link>{}</link>", escape(&entry.url)));
+        xml.push_str(&format!("<guid isPermaLink=\"true\">{}</guid>", escape(&entry.url)));
+        if let Some(published) = &entry.published {
+            xml.push_str(&format!("<pubDate>{}</pubDate>", rfc822(published)));
+        }
+        if let Some(author) = &entry.author {
+            xml.push_str(&format!("<dc:creator>{}</dc:creator>", escape(&author.name)));
+        }
+        for category in &entry.categories {
+            xml.push_str(&format!("<category>{}</category>", escape(category)));
+        }
+        if let Some(summary) = &entry.summary {
+            xml.push_str(&format!("<description>{}</description>", escape(summary)));
+        }
+        if let Some(content) = entry.content {
+            xml.push_str(&format!("<content:encoded>{}</content:encoded>", cdata(content)));
+        }
+        if let Some(image) = &entry.image {
+            xml.push_str(&format!("<media:content medium=\"image\" url=\"{}\" />", escape(image)));
+        }
+        xml.push_str("</item>\n");
+    }
+
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+/// Write a JSON Feed 1.1
+fn json_feed(channel: &Channel, self_url: &str, entries: &[Entry]) -> String {
+    let mut feed = json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": channel.title.as_deref().unwrap_or(""),
+        "home_page_url": channel.home,
+        "feed_url": self_url,
+    });
+    insert_some(&mut feed, "description", channel.subtitle.clone());
+    insert_some(&mut feed, "icon", channel.logo.clone());
+    insert_some(&mut feed, "favicon", channel.icon.clone());
+    insert_some(&mut feed, "language", channel.lang.clone());
+    if let Some(author) = &channel.author {
+        feed["authors"] = json!([json_author(author)]);
+    }
+
+    let items: Vec<serde_json::Value> = entries.iter().map(|entry| {
+        let mut item = json!({
+            "id": entry.id,
+            "url": entry.url,
+            "title": entry.title,
+        });
+        match entry.content {
+            Some(content) => item["content_html"] = json!(content),
+            None => item["content_text"] = json!(entry.summary.as_deref().unwrap_or("")),
+        }
+        insert_some(&mut item, "summary", entry.summary.clone());
+        insert_some(&mut item, "image", entry.image.clone());
+        insert_some(&mut item, "date_published", entry.published.map(|date| xmlschema(&date)));
+        insert_some(&mut item, "date_modified", entry.updated.map(|date| xmlschema(&date)));
+        insert_some(&mut item, "language", entry.lang.map(str::to_string));
+        if let Some(author) = &entry.author {
+            item["authors"] = json!([json_author(author)]);
+        }
+        if !entry.categories.is_empty() {
+            item["tags"] = json!(entry.categories);
+        }
+        item
+    }).collect();
+    feed["items"] = json!(items);
+
+    let mut output = serde_json::to_string_pretty(&feed).unwrap_or_default();
+    output.push('\n');
+    output
+}
+
+/// An author of a JSON Feed
+fn json_author(author: &Author) -> serde_json::Value {
+    let mut value = json!({ "name": author.name });
+    insert_some(&mut value, "url", author.uri.clone().or_else(|| author.email.as_ref().map(|email| format!("mailto:{}", email))));
+    value
+}
+
+/// Set a key of a JSON object if there is a value
+fn insert_some(object: &mut serde_json::Value, key: &str, value: Option<String>) {
+    if let Some(value) = value {
+        object[key] = json!(value);
+    }
+}
+
+/// A date in XML Schema format, like Jekyll's `date_to_xmlschema`
+fn xmlschema(date: &DateTime<Tz>) -> String {
+    date.format("%Y-%m-%dT%H:%M:%S%:z").to_string()
+}
+
+/// A date in RFC 822 format, like Jekyll's `date_to_rfc822`
+fn rfc822(date: &DateTime<Tz>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S %z").to_string()
+}
+
+/// Escape text for XML
+fn escape(text: &str) -> String {
+    html_escape::encode_double_quoted_attribute(text).to_string()
+}
+
+/// Wrap HTML in a CDATA section
+fn cdata(html: &str) -> String {
+    format!("<![CDATA[{}]]>", html.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+/// HTML as plain text on one line, like `strip_html | normalize_whitespace`
+fn plain_text(html: &str) -> String {
+    let text = TAG_REGEX.replace_all(html, "");
+    WHITESPACE_REGEX.replace_all(text.trim(), " ").to_string()
+}
+
+/// Capitalize the first letter and lowercase the rest, like Liquid's `capitalize`
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(|c| c.to_lowercase())).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestSite;
+
+    #[test]
+    fn test_feeds() {
+        let site = TestSite::new("feed");
+        site.write("_data/authors.yml", "ada:\n  name: Ada Lovelace\n  email: ada@example.com\n")
+            .write("_posts/2024-01-01-hello.md",
+                   "---\ntitle: Hello & welcome\nauthor: ada\ntags: [rust]\n---\nFirst *post* about {{ page.tags | first }}\n\nMore")
+            .write("_posts/2024-02-01-second.md",
+                   "---\ntitle: Second\ntags: [rust, web]\nfeed:\n  excerpt_only: true\n---\nSecond post")
+            .write("_posts/2024-03-01-hidden.md", "---\ntitle: Hidden\nrss: false\n---\nHidden");
+
+        let feed: FeedConfig = serde_yaml::from_str("tags:\n  only: rust\nformats: [atom, rss, json]").unwrap();
+        site.build(Config {
+            url: Some("https://example.com".to_string()),
+            base_url: "/blog".to_string(),
+            feed: Some(feed),
+            ..site.config()
+        });
+
+        let atom = site.read("feed.xml");
+        assert!(atom.contains("<link href=\"https://example.com/blog/feed.xml\" rel=\"self\" type=\"application/atom+xml\" />"));
+        assert!(atom.contains("<title type=\"html\">Hello &amp; welcome</title>"));
+        assert!(atom.contains("<author><name>Ada Lovelace</name><email>ada@example.com</email></author>"));
+        assert!(atom.contains("<content type=\"html\" xml:base=\"https://example.com/blog/2024/01/01/hello-welcome\"><![CDATA[<p>First <em>post</em> about rust</p>"));
+        assert!(atom.contains("<summary type=\"html\"><![CDATA[First post about rust]]></summary>"));
+        assert!(atom.contains("<category term=\"rust\" />"));
+        assert!(!atom.contains("Hidden"));
+
+        // The second post only has its excerpt, and is listed first
+        assert!(atom.find("Second").unwrap() < atom.find("Hello").unwrap());
+        assert!(!atom.contains("<![CDATA[<p>Second post"));
+
+        let tag_feed = site.read("feed/by_tag/rust.xml");
+        assert!(tag_feed.contains("<title type=\"html\">Your awesome site | Rust</title>"));
+        assert!(!site.output("feed/by_tag/web.xml").exists());
+
+        let rss = site.read("feed.rss");
+        assert!(rss.contains("<guid isPermaLink=\"true\">https://example.com/blog/2024/01/01/hello-welcome</guid>"));
+        let json: serde_json::Value = serde_json::from_str(&site.read("feed.json")).unwrap();
+        assert_eq!(json["items"][1]["authors"][0]["name"], "Ada Lovelace");
+
+        // Posts without an author have no author element
+        assert!(!atom.contains("<author><name></name>"));
+        assert!(json["items"][0].get("authors").is_none());
+    }
+}
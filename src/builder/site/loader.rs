@@ -1,15 +1,18 @@
 use std::collections::HashMap;
 use std::fs;
+use std::path::PathBuf;
 use log::debug;
 
 use crate::directory::DirectoryStructure;
 use crate::builder::types::BoxResult;
+use crate::builder::diagnostics::{self, Category};
 use crate::front_matter::FrontMatter;
 use crate::liquid::preprocess;
 
 /// Layout information including content and metadata
 #[derive(Debug, Clone)]
 pub struct LayoutInfo {
+    pub path: PathBuf,
     pub content: String,
     pub front_matter: FrontMatter,
 }
@@ -36,13 +39,18 @@ pub fn load_layouts(dirs: &DirectoryStructure) -> BoxResult<HashMap<String, Layo
                             // Parse front matter and extract both front matter and content
                             match crate::front_matter::utils::extract_front_matter(&content) {
                                 Ok((front_matter, body)) => (front_matter, body),
-                                Err(_) => (FrontMatter::default(), content), // If parsing fails, use original content
+                                Err(e) => {
+                                    // If parsing fails, use original content
+                                    diagnostics::error(Category::FrontMatter, &path, e.to_string());
+                                    (FrontMatter::default(), content)
+                                }
                             }
                         } else {
                             (FrontMatter::default(), content)
                         };
 
                         let layout_info = LayoutInfo {
+                            path: path.clone(),
                             content: preprocess::preprocess_liquid(&processed_content),
                             front_matter,
                         };
@@ -77,13 +85,18 @@ pub fn load_layouts(dirs: &DirectoryStructure) -> BoxResult<HashMap<String, Layo
                                     // Parse front matter and extract both front matter and content
                                     match crate::front_matter::utils::extract_front_matter(&content) {
                                         Ok((front_matter, body)) => (front_matter, body),
-                                        Err(_) => (FrontMatter::default(), content), // If parsing fails, use original content
+                                        Err(e) => {
+                                            // If parsing fails, use original content
+                                            diagnostics::error(Category::FrontMatter, &path, e.to_string());
+                                            (FrontMatter::default(), content)
+                                        }
                                     }
                                 } else {
                                     (FrontMatter::default(), content)
                                 };
 
                                 let layout_info = LayoutInfo {
+                                    path: path.clone(),
                                     content: preprocess::preprocess_liquid(&processed_content),
                                     front_matter,
                                 };
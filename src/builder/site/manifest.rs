@@ -0,0 +1,281 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use log::info;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::collections::document::model::Document;
+use crate::builder::page::Page;
+use crate::builder::types::BoxResult;
+use crate::builder::site::{strip_baseurl, with_baseurl, LayoutInfo, RenderRecord};
+use crate::builder::site::cache::hash_bytes;
+use crate::builder::site::processor::layout_chain;
+
+/// Source recorded for files copied to the destination as they are
+const STATIC_SOURCE: &str = "static";
+
+/// Whether an output was written by this build or kept from the previous one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStatus {
+    Rebuilt,
+    Reused,
+}
+
+/// An output file of the build
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestEntry {
+    /// Path of the output relative to the destination
+    pub path: String,
+    /// Source file relative to the site source, or "static" for copied files
+    pub source: String,
+    /// URL the output is served at, with the baseurl
+    pub url: String,
+    /// Layouts applied, innermost first
+    pub layouts: Vec<String>,
+    /// Collection of the document the output was generated from
+    pub collection: Option<String>,
+    /// SHA-256 hash of the output
+    pub hash: String,
+    /// Size of the output in bytes
+    pub size: u64,
+    /// Whether the output was rebuilt or reused
+    pub status: OutputStatus,
+    /// Absolute path of the output
+    #[serde(skip)]
+    output: PathBuf,
+}
+
+/// Every output file of a build, written after the build for deploy tooling
+#[derive(Debug, Serialize)]
+pub struct BuildManifest {
+    /// When the build finished
+    pub generated_at: String,
+    /// Output files, sorted by path
+    pub files: Vec<ManifestEntry>,
+    /// Configuration of the site, for its paths and baseurl
+    #[serde(skip)]
+    config: Config,
+}
+
+impl BuildManifest {
+    /// Create an empty manifest for a site
+    pub fn new(config: &Config) -> Self {
+        Self {
+            generated_at: String::new(),
+            files: Vec::new(),
+            config: config.clone(),
+        }
+    }
+
+    /// Add a page, including generated pagination and archive pages
+    pub fn add_page(&mut self, page: &Page, layouts: &HashMap<String, LayoutInfo>) {
+        let output = match &page.output_path {
+            Some(output) => output.clone(),
+            None => return,
+        };
+
+        // Files without front matter are copied as they are
+        if !page.process {
+            self.add_static(output, page.url.clone());
+            return;
+        }
+
+        let source = self.relative_source(&page.path);
+        self.push(output, source, page.url.clone(), layout_chain(page.front_matter.layout.as_deref(), layouts), None);
+    }
+
+    /// Add a document of a collection
+    pub fn add_document(&mut self, doc: &Document, output: PathBuf, layouts: &HashMap<String, LayoutInfo>) {
+        let source = self.relative_source(&doc.path);
+        let layouts = layout_chain(doc.front_matter.layout.as_deref(), layouts);
+        // Document URLs start with the baseurl, which is added back when writing
+        let url = doc.url.as_deref().map(|url| strip_baseurl(url, &self.config));
+        self.push(output, source, url, layouts, Some(doc.collection.clone()));
+    }
+
+    /// Add an output rendered by the build, like a feed, sitemap or redirect
+    pub fn add_rendered(&mut self, record: &RenderRecord) {
+        let source = self.relative_source(&record.source);
+        let layouts = record.layouts.iter()
+            .filter_map(|layout| layout.file_stem())
+            .map(|name| name.to_string_lossy().to_string())
+            .collect();
+        self.push(record.output.clone(), source, None, layouts, None);
+    }
+
+    /// Add a static file copied to the destination
+    pub fn add_static(&mut self, output: PathBuf, url: Option<String>) {
+        self.push(output, STATIC_SOURCE.to_string(), url, Vec::new(), None);
+    }
+
+    fn push(&mut self, output: PathBuf, source: String, url: Option<String>, layouts: Vec<String>, collection: Option<String>) {
+        self.files.push(ManifestEntry {
+            path: String::new(),
+            source,
+            url: url.unwrap_or_default(),
+            layouts,
+            collection,
+            hash: String::new(),
+            size: 0,
+            status: OutputStatus::Rebuilt,
+            output,
+        });
+    }
+
+    fn relative_source(&self, path: &Path) -> String {
+        path.strip_prefix(&self.config.source).unwrap_or(path).to_string_lossy().to_string()
+    }
+
+    /// Hash the written outputs and write the manifest as JSON
+    ///
+    /// Outputs that were not written (e.g. because rendering failed) are left
+    /// out. When `rebuilt` is given, outputs not in it are marked as reused.
+    pub fn write(mut self, path: &Path, rebuilt: Option<&HashSet<PathBuf>>) -> BoxResult<()> {
+        let destination = &self.config.destination;
+
+        // Later entries for the same output win, as they were written last
+        let mut seen = HashSet::new();
+        let mut files: Vec<ManifestEntry> = std::mem::take(&mut self.files).into_iter().rev()
+            .filter(|entry| entry.output != path && seen.insert(entry.output.clone()))
+            .collect();
+
+        files.par_iter_mut().for_each(|entry| {
+            if let Ok(bytes) = fs::read(&entry.output) {
+                entry.hash = hash_bytes(&bytes);
+                entry.size = bytes.len() as u64;
+            }
+        });
+        files.retain(|entry| !entry.hash.is_empty());
+
+        for entry in &mut files {
+            let relative = entry.output.strip_prefix(destination).unwrap_or(&entry.output);
+            entry.path = relative.to_string_lossy().replace('\\', "/");
+            if entry.url.is_empty() {
+                entry.url = format!("/{}", entry.path);
+            }
+            entry.url = with_baseurl(&entry.url, &self.config);
+            if rebuilt.is_some_and(|rebuilt| !rebuilt.contains(&entry.output)) {
+                entry.status = OutputStatus::Reused;
+            }
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        self.files = files;
+        self.generated_at = Utc::now().to_rfc3339();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&self)?)?;
+        info!("Wrote build manifest with {} files to {}", self.files.len(), path.display());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::front_matter::FrontMatter;
+    use crate::test_support::TestSite;
+
+    fn layout(name: &str, parent: Option<&str>) -> (String, LayoutInfo) {
+        let front_matter = FrontMatter { layout: parent.map(str::to_string), ..FrontMatter::default() };
+        (name.to_string(), LayoutInfo { path: PathBuf::from(format!("_layouts/{}.html", name)), content: String::new(), front_matter })
+    }
+
+    #[test]
+    fn test_build_manifest() {
+        let site = TestSite::new("manifest");
+        let config = site.config();
+        let layouts: HashMap<String, LayoutInfo> = [layout("post", Some("default")), layout("default", None)].into_iter().collect();
+        let write = |path: &str, content: &str| {
+            let path = config.destination.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        };
+
+        let front_matter = FrontMatter { layout: Some("default".to_string()), ..FrontMatter::default() };
+        let page = Page::generated(config.source.join("about.md"), "/about/".to_string(), front_matter, &config);
+        let about = write("about/index.html", "About");
+
+        let front_matter = FrontMatter { layout: Some("post".to_string()), ..FrontMatter::default() };
+        let mut doc = Document::new("a".to_string(), config.source.join("_posts/2024-01-01-a.md"), PathBuf::from("2024-01-01-a.md"),
+                                    "posts".to_string(), String::new(), front_matter);
+        doc.url = Some("/2024/01/01/a".to_string());
+        let post = write("2024/01/01/a", "Post");
+
+        let css = write("css/site.css", "body {}");
+
+        let mut manifest = BuildManifest::new(&config);
+        manifest.add_static(css, None);
+        manifest.add_static(config.destination.join("missing.txt"), None);
+        manifest.add_page(&page, &layouts);
+        manifest.add_document(&doc, post.clone(), &layouts);
+
+        // Only the page and post were written by this build
+        let rebuilt: HashSet<PathBuf> = [about, post].into_iter().collect();
+        manifest.write(&config.destination.join("manifest.json"), Some(&rebuilt)).unwrap();
+
+        let manifest: serde_json::Value = serde_json::from_str(&site.read("manifest.json")).unwrap();
+        assert!(!manifest["generated_at"].as_str().unwrap().is_empty());
+        let files = manifest["files"].as_array().unwrap();
+        let paths: Vec<&str> = files.iter().map(|entry| entry["path"].as_str().unwrap()).collect();
+        assert_eq!(paths, ["2024/01/01/a", "about/index.html", "css/site.css"]);
+
+        assert_eq!(files[0]["source"], "_posts/2024-01-01-a.md");
+        assert_eq!(files[0]["url"], "/2024/01/01/a");
+        assert_eq!(files[0]["layouts"], serde_json::json!(["post", "default"]));
+        assert_eq!(files[0]["collection"], "posts");
+        assert_eq!(files[0]["hash"], hash_bytes(b"Post"));
+        assert_eq!(files[0]["size"], 4);
+        assert_eq!(files[0]["status"], "rebuilt");
+
+        assert_eq!(files[1]["source"], "about.md");
+        assert_eq!(files[1]["url"], "/about/");
+        assert_eq!(files[1]["layouts"], serde_json::json!(["default"]));
+        assert_eq!(files[1]["status"], "rebuilt");
+
+        assert_eq!(files[2]["source"], "static");
+        assert_eq!(files[2]["url"], "/css/site.css");
+        assert_eq!(files[2]["hash"], hash_bytes(b"body {}"));
+        assert_eq!(files[2]["status"], "reused");
+    }
+
+    #[test]
+    fn test_manifest_generated_files() {
+        let site = TestSite::new("manifest-site");
+        site.write("_posts/2024-01-01-hello.md", "---\ntitle: Hello\nredirect_from: /old/\n---\nHello")
+            .write("about.md", "---\ntitle: About\n---\nAbout")
+            .write("css/site.css", "body {}");
+
+        site.build(Config {
+            url: Some("https://example.com".to_string()),
+            base_url: "/blog".to_string(),
+            manifest: Some("manifest.json".to_string()),
+            ..site.config()
+        });
+
+        // The manifest goes to the destination and does not list itself
+        assert!(!site.path("manifest.json").exists());
+        let manifest: serde_json::Value = serde_json::from_str(&site.read("manifest.json")).unwrap();
+        let urls: Vec<&str> = manifest["files"].as_array().unwrap().iter()
+            .map(|entry| entry["url"].as_str().unwrap())
+            .collect();
+        assert!(!urls.contains(&"/blog/manifest.json"));
+
+        // Files generated by the build are listed with the pages, documents
+        // and static files, each with the baseurl once
+        for url in ["/blog/2024/01/01/hello", "/blog/about.html", "/blog/css/site.css", "/blog/feed.xml",
+                    "/blog/sitemap.xml", "/blog/robots.txt", "/blog/old/index.html", "/blog/redirects.json"] {
+            assert!(urls.contains(&url), "{} missing from {:?}", url, urls);
+        }
+        assert!(urls.iter().all(|url| !url.contains("//")), "{:?}", urls);
+    }
+}
@@ -2,11 +2,28 @@ mod builder;
 mod loader;
 mod processor;
 mod converter;
+mod cache;
+mod pagination;
+mod archives;
+mod feed;
+mod sitemap;
+mod redirects;
+mod related;
+mod manifest;
+mod conflicts;
 
-pub use builder::build_site;
-pub use loader::{load_layouts, load_includes};
-pub use processor::{process_collections, process_pages};
+pub use builder::{build_site, Site, BuildStats, BuildOutcome, BuildReport};
+pub use loader::{load_layouts, load_includes, LayoutInfo};
+pub use processor::{render_collections, render_pages, write_outputs, document_output_path, RenderRecord, RenderedOutput};
 pub use converter::{page_to_liquid, data_to_liquid};
+pub use pagination::process_pagination;
+pub use archives::generate_archives;
+pub use feed::{plan_feeds, render_feeds};
+pub use sitemap::generate_sitemap;
+pub use redirects::{collect_redirects, apply_redirect_to, generate_redirects};
+pub use related::process_related_posts;
+pub use manifest::BuildManifest;
+pub use conflicts::report_conflicts;
 
 use std::path::Path;
 use crate::config::Config;
@@ -25,4 +42,30 @@ pub fn is_markdown_file(path: &Path, config: &Config) -> bool {
     }
     
     false
-} 
\ No newline at end of file
+}
+
+/// The absolute URL of a document, whose URL already starts with the baseurl
+pub fn absolute_document_url(url: &str, config: &Config) -> String {
+    let site_url = config.url.as_deref().unwrap_or("").trim_end_matches('/');
+    format!("{}/{}", site_url, url.trim_start_matches('/'))
+}
+
+/// Prefix a path of the site with the baseurl
+pub fn with_baseurl(path: &str, config: &Config) -> String {
+    let base_url = config.base_url.trim_matches('/');
+    if base_url.is_empty() {
+        path.to_string()
+    } else {
+        format!("/{}/{}", base_url, path.trim_start_matches('/'))
+    }
+}
+
+/// Remove the baseurl from a document URL
+pub fn strip_baseurl(url: &str, config: &Config) -> String {
+    let base_url = config.base_url.trim_matches('/');
+    let relative = match url.trim_start_matches('/').strip_prefix(base_url) {
+        Some(rest) if !base_url.is_empty() && (rest.is_empty() || rest.starts_with('/')) => rest,
+        _ => url,
+    };
+    format!("/{}", relative.trim_start_matches('/'))
+}
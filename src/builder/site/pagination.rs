@@ -0,0 +1,383 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+
+use log::{debug, info, warn};
+
+use crate::config::Config;
+use crate::collections::Collection;
+use crate::collections::document::model::Document;
+use crate::front_matter::types::Pagination;
+use crate::builder::page::{Page, Paginator, output_path_for_url};
+use crate::builder::types::BoxResult;
+
+/// Process pagination for index pages
+///
+/// Pages are paginated when their front matter contains a `pagination` block
+/// with `enabled: true` (jekyll-paginate-v2), or when `paginate` is set in the
+/// site configuration and the page is the `index.html` of the directory that
+/// `paginate_path` points into (jekyll-paginate).
+pub fn process_pagination(
+    pages: &mut Vec<Page>,
+    collections: &HashMap<String, Collection>,
+    config: &Config
+) -> BoxResult<()> {
+    let mut new_pages = Vec::new();
+
+    for page in pages.iter_mut() {
+        let (settings, pattern) = match pagination_settings(page, config) {
+            Some(settings) => settings,
+            None => continue,
+        };
+
+        // Gather, filter and sort the items to paginate
+        let items = collect_items(&settings, collections);
+        let total_posts = items.len();
+        let per_page = settings.per_page().max(1);
+        let total_pages = std::cmp::max(1, total_posts.div_ceil(per_page));
+        info!("Paginating {} ({} items, {} pages)", page.path.display(), total_posts, total_pages);
+
+        let first_page_url = first_page_url(page);
+        let page_url = |num: usize| -> String {
+            if num <= 1 {
+                first_page_url.clone()
+            } else {
+                pattern.replace(":num", &num.to_string())
+            }
+        };
+
+        let original_title = page.front_matter.title.clone().unwrap_or_default();
+        let mut paginated = Vec::with_capacity(total_pages);
+
+        for num in 1..=total_pages {
+            let start = std::cmp::min((num - 1) * per_page, total_posts);
+            let end = std::cmp::min(start + per_page, total_posts);
+            let page_items = &items[start..end];
+
+            let paginator = Paginator {
+                page: num,
+                per_page,
+                posts: page_items.iter()
+                    .map(|doc| crate::collections::document_to_liquid(doc))
+                    .collect(),
+                total_posts,
+                total_pages,
+                page_path: page_url(num),
+                first_page_path: page_url(1),
+                last_page_path: page_url(total_pages),
+                previous_page: if num > 1 { Some(num - 1) } else { None },
+                previous_page_path: if num > 1 { Some(page_url(num - 1)) } else { None },
+                next_page: if num < total_pages { Some(num + 1) } else { None },
+                next_page_path: if num < total_pages { Some(page_url(num + 1)) } else { None },
+            };
+
+            // Keep the pagination state in front matter as well
+            let mut state = settings.clone();
+            state.page = num;
+            state.per_page = Some(per_page);
+            state.total_pages = total_pages;
+            state.total_items = total_posts;
+            state.posts = page_items.iter().map(|doc| doc.id.clone()).collect();
+
+            paginated.push((num, paginator, state));
+        }
+
+        for (num, paginator, state) in paginated {
+            if num == 1 {
+                // The original page becomes the first page
+                page.paginator = Some(paginator);
+                if page.front_matter.pagination.is_some() {
+                    page.front_matter.pagination = Some(state);
+                }
+                continue;
+            }
+
+            let mut new_page = page.clone();
+            let url = page_url(num);
+            new_page.output_path = Some(output_path_for_url(&url, &config.destination));
+            new_page.url = Some(url);
+            new_page.paginator = Some(paginator);
+            if new_page.front_matter.pagination.is_some() {
+                new_page.front_matter.pagination = Some(state);
+            }
+
+            if let Some(title) = &settings.title {
+                new_page.front_matter.title = Some(title
+                    .replace(":title", &original_title)
+                    .replace(":num", &num.to_string()));
+            }
+
+            debug!("Created pagination page {}", new_page.url.as_deref().unwrap_or(""));
+            new_pages.push(new_page);
+        }
+    }
+
+    // Add all new pages to the pages vector
+    pages.extend(new_pages);
+
+    Ok(())
+}
+
+/// Determine the pagination settings and URL pattern for a page, if it is paginated
+fn pagination_settings(page: &Page, config: &Config) -> Option<(Pagination, String)> {
+    if !page.process {
+        return None;
+    }
+
+    // Site-wide defaults, with jekyll-paginate's `paginate` as the page size
+    let mut defaults = config.pagination.clone().unwrap_or_default();
+    if let Some(paginate) = config.paginate {
+        if paginate > 0 && config.pagination.is_none() {
+            defaults.per_page = Some(paginate);
+        }
+    }
+
+    // jekyll-paginate-v2: pagination block in front matter
+    if let Some(pagination) = &page.front_matter.pagination {
+        if pagination.enabled {
+            let settings = pagination.with_defaults(&defaults);
+            // Relative to the directory of the paginated page
+            let path = settings.path.as_deref().unwrap_or(&config.paginate_path);
+            let pattern = directory_url(&join_url(&page_directory_url(page), path));
+            return Some((settings, pattern));
+        }
+    }
+
+    // jekyll-paginate: the index.html in the paginate_path directory
+    let paginate = config.paginate.unwrap_or(0);
+    if paginate > 0 && is_paginate_path_index(page, config) {
+        let mut settings = defaults;
+        settings.per_page = Some(paginate);
+        return Some((settings, directory_url(&ensure_leading_slash(&config.paginate_path))));
+    }
+
+    None
+}
+
+/// Check if a page is the index page jekyll-paginate would paginate
+fn is_paginate_path_index(page: &Page, config: &Config) -> bool {
+    let file_name = page.path.file_name().map(|name| name.to_string_lossy().to_string());
+    if file_name.as_deref() != Some("index.html") {
+        return false;
+    }
+
+    // The directory containing the `:num` segment of paginate_path
+    let paginate_path = ensure_leading_slash(&config.paginate_path);
+    let num_segment_start = paginate_path.find(":num")
+        .and_then(|pos| paginate_path[..pos].rfind('/'))
+        .unwrap_or(0);
+    let paginate_dir = format!("{}/", paginate_path[..num_segment_start].trim_end_matches('/'));
+
+    page_directory_url(page) == paginate_dir
+}
+
+/// Gather the documents to paginate, filtered and sorted per the settings
+fn collect_items<'a>(settings: &Pagination, collections: &'a HashMap<String, Collection>) -> Vec<&'a Document> {
+    let collection = settings.collection();
+    let labels: Vec<&str> = collection.split(',')
+        .map(|label| label.trim())
+        .filter(|label| !label.is_empty())
+        .collect();
+
+    let mut items: Vec<&Document> = Vec::new();
+    for label in &labels {
+        if *label == "all" {
+            items.extend(collections.values().flat_map(|collection| collection.documents.iter()));
+        } else if let Some(collection) = collections.get(*label) {
+            items.extend(collection.documents.iter());
+        } else {
+            warn!("Pagination collection '{}' not found", label);
+        }
+    }
+
+    let categories = split_list(settings.category.as_deref());
+    let tags = split_list(settings.tag.as_deref());
+
+    items.retain(|doc| {
+        // Hidden posts never show up in pagination
+        let hidden = doc.front_matter.custom.get("hidden")
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false);
+
+        !hidden
+            && categories.iter().all(|category| contains_ignore_case(&doc.categories, category))
+            && tags.iter().all(|tag| contains_ignore_case(&doc.tags, tag))
+    });
+
+    let sort = settings.sort();
+    let descending = settings.is_descending();
+    items.sort_by(|a, b| compare_documents(a, b, &sort, descending));
+
+    items
+}
+
+/// Compare two documents by a sort field, keeping documents without a value last
+fn compare_documents(a: &Document, b: &Document, field: &str, descending: bool) -> Ordering {
+    let ordering = if field == "date" {
+        match (a.date, b.date) {
+            (Some(a_date), Some(b_date)) => a_date.cmp(&b_date),
+            (Some(_), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    } else {
+        match (sort_value(a, field), sort_value(b, field)) {
+            (Some(a_value), Some(b_value)) => compare_values(&a_value, &b_value),
+            (Some(_), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    };
+
+    if descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+/// Get the value of a sort field from a document's front matter
+fn sort_value(doc: &Document, field: &str) -> Option<String> {
+    match field {
+        "title" => doc.front_matter.title.clone(),
+        "weight" => doc.front_matter.weight.map(|weight| weight.to_string()),
+        "order" => doc.front_matter.order.map(|order| order.to_string()),
+        _ => doc.front_matter.custom.get(field).and_then(|value| match value {
+            serde_yaml::Value::String(s) => Some(s.clone()),
+            serde_yaml::Value::Number(n) => Some(n.to_string()),
+            serde_yaml::Value::Bool(b) => Some(b.to_string()),
+            _ => None,
+        }),
+    }
+}
+
+/// Compare sort values numerically when both are numbers
+fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a_num), Ok(b_num)) => a_num.partial_cmp(&b_num).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+/// Split a comma-separated filter value
+fn split_list(value: Option<&str>) -> Vec<String> {
+    value.map(|value| value.split(',')
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect())
+        .unwrap_or_default()
+}
+
+/// Check if a list contains a value, ignoring case
+fn contains_ignore_case(list: &[String], value: &str) -> bool {
+    list.iter().any(|item| item.eq_ignore_ascii_case(value))
+}
+
+/// The URL of the first page of a paginated page
+fn first_page_url(page: &Page) -> String {
+    let url = page.url.clone().unwrap_or_else(|| "/".to_string());
+    match url.strip_suffix("index.html") {
+        Some(dir) => dir.to_string(),
+        None => url,
+    }
+}
+
+/// The URL of the directory containing a page, with a trailing slash
+fn page_directory_url(page: &Page) -> String {
+    let url = first_page_url(page);
+    if url.ends_with('/') {
+        return url;
+    }
+
+    match url.rfind('/') {
+        Some(pos) => url[..=pos].to_string(),
+        None => "/".to_string(),
+    }
+}
+
+/// Join a relative URL pattern onto a directory URL
+fn join_url(dir: &str, path: &str) -> String {
+    format!("{}/{}", dir.trim_end_matches('/'), path.trim_start_matches('/'))
+}
+
+/// Make sure a URL starts with a slash
+fn ensure_leading_slash(url: &str) -> String {
+    if url.starts_with('/') {
+        url.to_string()
+    } else {
+        format!("/{}", url)
+    }
+}
+
+/// Add a trailing slash to URLs without an extension, like Jekyll's `/page2/`
+fn directory_url(url: &str) -> String {
+    if url.ends_with('/') || Path::new(url).extension().is_some() {
+        url.to_string()
+    } else {
+        format!("{}/", url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestSite;
+
+    const LIST: &str = "{% for post in paginator.posts %}{{ post.title }};{% endfor %}\
+                        {% if paginator.next_page %}next={{ paginator.next_page_path }}{% endif %}";
+
+    /// Build a site with three posts and the given pages
+    fn build(name: &str, pages: &[(&str, &str)], config: Config) -> TestSite {
+        let site = TestSite::new(name);
+        site.write("_posts/2024-01-01-a.md", "---\ntitle: A\ncategories: news\ntags: rust\nweight: 3\n---\nA")
+            .write("_posts/2024-02-01-b.md", "---\ntitle: B\ncategories: news\ntags: web\nweight: 1\n---\nB")
+            .write("_posts/2024-03-01-c.md", "---\ntitle: C\ncategories: other\ntags: rust\nweight: 2\n---\nC");
+        for (path, front_matter) in pages {
+            site.write(path, &format!("---\n{}---\n{}", front_matter, LIST));
+        }
+
+        let paths = site.config();
+        site.build(Config { source: paths.source, destination: paths.destination, ..config });
+        site
+    }
+
+    #[test]
+    fn test_pagination() {
+        let defaults = Pagination {
+            per_page: Some(1),
+            sort: Some("title".to_string()),
+            direction: Some("asc".to_string()),
+            collection: Some("missing".to_string()),
+            ..Pagination::default()
+        };
+        let config = Config { pagination: Some(defaults), ..Config::default() };
+        let site = build("pagination", &[
+            // Explicit settings win over site defaults, even when they are the built-in ones
+            ("index.html", "pagination:\n  enabled: true\n  per_page: 10\n  sort: date\n  direction: desc\n  collection: posts\n"),
+            ("news/index.html", "pagination:\n  enabled: true\n  collection: posts\n  category: news\n  permalink: /p/:num/\n"),
+            ("rust/index.html", "pagination:\n  enabled: true\n  collection: posts\n  per_page: 2\n  tag: rust\n  sort: weight\n"),
+        ], config);
+
+        assert_eq!(site.read("index.html").trim(), "C;B;A;");
+
+        // Filtered by category, sorted by the default title, one per page
+        assert_eq!(site.read("news/index.html").trim(), "A;next=/news/p/2/");
+        assert_eq!(site.read("news/p/2/index.html").trim(), "B;");
+        assert!(!site.output("news/p/3").exists());
+
+        // Filtered by tag, sorted by weight
+        assert_eq!(site.read("rust/index.html").trim(), "C;A;");
+    }
+
+    #[test]
+    fn test_paginate_path() {
+        let config = Config { paginate: Some(2), paginate_path: "/blog/page:num/".to_string(), ..Config::default() };
+        let site = build("paginate-path", &[("blog/index.html", ""), ("index.html", "")], config);
+
+        // Only the index in the paginate_path directory is paginated, newest first
+        assert_eq!(site.read("blog/index.html").trim(), "C;B;next=/blog/page2/");
+        assert_eq!(site.read("blog/page2/index.html").trim(), "A;");
+        assert_eq!(site.read("index.html").trim(), "");
+        assert!(!site.output("page2").exists());
+    }
+}
@@ -1,8 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::fs::File;
-use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use log::{info, debug, error, warn};
@@ -13,27 +11,75 @@ use crate::config::Config;
 use crate::directory::DirectoryStructure;
 use crate::markdown::MarkdownRenderer;
 use crate::collections::Collection;
+use crate::collections::document::model::Document;
 use crate::builder::page::Page;
 use crate::builder::types::BoxResult;
-use crate::liquid::create_globals;
+use crate::builder::diagnostics::{self, Category, Diagnostics};
+use crate::builder::profiler::{self, Profiler, TemplateKind};
+use crate::builder::render_state::RenderState;
+use crate::liquid::{create_globals, dependencies, errors, preprocess};
+use crate::liquid::strict::{self, Checked, StrictMode};
 use crate::builder::site::loader::LayoutInfo;
 use html_escape;
 
-/// Process and render collections
-pub fn process_collections(
+/// Record of an output file generated during rendering
+#[derive(Debug, Clone)]
+pub struct RenderRecord {
+    /// Source file the output was generated from
+    pub source: PathBuf,
+    /// Absolute path of the output
+    pub output: PathBuf,
+    /// Layout files applied, innermost first
+    pub layouts: Vec<PathBuf>,
+    /// Files read by include tags while rendering
+    pub includes: Vec<PathBuf>,
+}
+
+/// An output rendered in memory, waiting to be written
+#[derive(Debug, Clone)]
+pub struct RenderedOutput {
+    /// What the output was generated from
+    pub record: RenderRecord,
+    /// Rendered content, or None for files copied as they are
+    pub content: Option<String>,
+}
+
+/// Determine the output path of a document from its URL
+pub fn document_output_path(doc: &Document, dirs: &DirectoryStructure) -> PathBuf {
+    let relative_path = if let Some(url) = &doc.url {
+        // Remove leading slash from URL
+        Path::new(url.trim_start_matches('/')).to_path_buf()
+    } else {
+        // If no URL, use the relative path with .html extension
+        let mut output_path = doc.relative_path.clone();
+        output_path.set_extension("html");
+        output_path
+    };
+
+    dirs.destination.join(relative_path)
+}
+
+/// Render the documents of collections
+///
+/// When `render_only` is given, only documents whose output path is in the
+/// set are rendered; the others are assumed to be up to date.
+#[allow(clippy::too_many_arguments)]
+pub fn render_collections(
     collections: &mut HashMap<String, Collection>,
     layouts: &HashMap<String, LayoutInfo>,
     parser: &liquid::Parser,
     site_data: &Object,
     markdown_renderer: &MarkdownRenderer,
     dirs: &DirectoryStructure,
-    config: &Config
-) -> BoxResult<()> {
-    info!("Processing collections...");
+    config: &Config,
+    render_only: Option<&HashSet<PathBuf>>
+) -> BoxResult<Vec<RenderedOutput>> {
+    info!("Rendering collections...");
     
     // Use a thread-safe counter for statistics
     let processed_count = Arc::new(Mutex::new(0));
     let error_count = Arc::new(Mutex::new(0));
+    let outputs = Arc::new(Mutex::new(Vec::new()));
     
     // Get CPU count to optimize parallelism
     let cpu_count = num_cpus::get();
@@ -47,41 +93,11 @@ pub fn process_collections(
             continue;
         }
         
-        info!("Processing collection '{}'", label);
+        info!("Rendering collection '{}'", label);
         let collection_docs_count = collection.documents.len();
         
-        // Create parent directories for output files first (this avoids race conditions)
-        let output_paths: Vec<_> = collection.documents.iter()
-            .filter_map(|doc| {
-                // Determine output path
-                if let Some(url) = &doc.url {
-                    // Remove leading slash from URL
-                    let path_str = if url.starts_with('/') {
-                        &url[1..]
-                    } else {
-                        url
-                    };
-                    let relative_path = Path::new(path_str).to_path_buf();
-                    let output_path = dirs.destination.join(&relative_path);
-                    Some(output_path)
-                } else {
-                    // If no URL, use the relative path with .html extension
-                    let mut output_path = doc.relative_path.clone();
-                    output_path.set_extension("html");
-                    let output_path = dirs.destination.join(&output_path);
-                    Some(output_path)
-                }
-            })
-            .collect();
-            
-        // Create all output directories in parallel
-        output_paths.par_iter().for_each(|path| {
-            if let Some(parent) = path.parent() {
-                if let Err(e) = fs::create_dir_all(parent) {
-                    error!("Failed to create directory {}: {}", parent.display(), e);
-                }
-            }
-        });
+        // Related posts of each document, exposed as `site.related_posts`
+        let related_posts = related_posts_to_liquid(collection);
         
         // Thread-safe wrappers for resources
         let layouts = Arc::new(layouts.clone());
@@ -89,69 +105,76 @@ pub fn process_collections(
         let site_data = Arc::new(site_data.clone());
         let markdown_renderer = Arc::new(markdown_renderer);
         let config = Arc::new(config.clone());
+        let strict_mode = StrictMode::from_config(&config);
         let dirs = Arc::new(dirs.clone());
         
-        // Process documents in parallel
+        // Process documents in parallel, reporting to the site on each thread
+        let collector = Diagnostics::current();
+        let timings = Profiler::current();
+        let state = RenderState::current();
         collection.documents.par_iter_mut().for_each(|doc| {
-            // Create the output path for the document
-            let relative_path = if let Some(url) = &doc.url {
-                // Remove leading slash from URL
-                let path_str = if url.starts_with('/') {
-                    &url[1..]
-                } else {
-                    url
-                };
-                Path::new(path_str).to_path_buf()
-            } else {
-                // If no URL, use the relative path with .html extension
-                let mut output_path = doc.relative_path.clone();
-                output_path.set_extension("html");
-                output_path
-            };
+            let _collector = collector.as_ref().map(Diagnostics::enter);
+            let _timings = timings.as_ref().map(Profiler::enter);
+            let _state = state.as_ref().map(RenderState::enter);
             
             // Set the absolute output path
-            let output_path = dirs.destination.join(&relative_path);
+            let output_path = document_output_path(doc, &dirs);
             doc.output_path = Some(output_path.clone());
             
+            // Skip documents that are up to date
+            if let Some(set) = render_only {
+                if !set.contains(&output_path) {
+                    debug!("Skipping unchanged document {}", doc.path.display());
+                    return;
+                }
+            }
+            
+            // Track the includes used by this document
+            dependencies::start_recording();
+            diagnostics::set_current_file(&doc.path);
+            let _timer = profiler::start(TemplateKind::Page, doc.path.to_string_lossy());
+            let _frame = errors::enter(TemplateKind::Page, &doc.path);
+            
             // Create globals for this document
             let mut globals = create_globals(&config, Some(&site_data));
             globals.insert("page".into(), crate::collections::document_to_liquid(doc));
             globals.insert("content".into(), liquid::model::Value::scalar(doc.content.clone()));
-            
-            // Render content (markdown or liquid)
-            let rendered_content = match if is_markdown_file(&doc.path, &config) {
-                // Render markdown to HTML
-                let html_content = markdown_renderer.render(&doc.content);
-                
-                // Then process with Liquid - decode HTML entities before parsing
-                let decoded_content = html_escape::decode_html_entities(&html_content).to_string();
-                match parser.parse(&decoded_content) {
-                    Ok(template) => match template.render(&globals) {
-                        Ok(content) => Ok(content),
-                        Err(e) => Err(format!("Error rendering liquid in markdown: {}", e))
-                    },
-                    Err(e) => Err(format!("Error parsing liquid in markdown: {}", e))
+            if let Some(related) = related_posts.get(&doc.id) {
+                if let Some(liquid::model::Value::Object(site)) = globals.get_mut("site") {
+                    site.insert("related_posts".into(), related.clone());
                 }
-            } else {
-                // Just process with Liquid - decode HTML entities before parsing
-                let decoded_content = html_escape::decode_html_entities(&doc.content).to_string();
-                match parser.parse(&decoded_content) {
-                    Ok(template) => match template.render(&globals) {
-                        Ok(content) => Ok(content),
-                        Err(e) => Err(format!("Error rendering liquid: {}", e))
-                    },
-                    Err(e) => Err(format!("Error parsing liquid: {}", e))
+            }
+            
+            // Report undefined variables and unknown filters, per the Liquid error mode
+            let checked = match strict::check(&doc.content, &doc.path, &mut globals, &parser, strict_mode) {
+                Ok(checked) => checked,
+                Err(e) => {
+                    diagnostics::error(Category::Liquid, &doc.path, e);
+                    let mut error_count = error_count.lock().unwrap();
+                    *error_count += 1;
+                    return;
                 }
-            } {
+            };
+            
+            // Render content (markdown or liquid)
+            let is_markdown = is_markdown_file(&doc.path, &config);
+            let rendered_content = match render_content(&doc.content, &doc.path, is_markdown, &markdown_renderer, &parser, &checked, &globals) {
                 Ok(content) => content,
                 Err(e) => {
-                    error!("Error processing document {}: {}", doc.path.display(), e);
+                    diagnostics::error(Category::Liquid, &doc.path, e);
                     let mut error_count = error_count.lock().unwrap();
                     *error_count += 1;
                     return;
                 }
             };
             
+            // The excerpt is rendered the same way, for feeds and the seo tag
+            doc.rendered_excerpt = doc.front_matter.excerpt.as_ref().or(doc.excerpt.as_ref())
+                .and_then(|excerpt| render_content(excerpt, &doc.path, is_markdown, &markdown_renderer, &parser, &checked, &globals).ok());
+            if let (Some(excerpt), Some(liquid::model::Value::Object(page))) = (&doc.rendered_excerpt, globals.get_mut("page")) {
+                page.insert("excerpt".into(), liquid::model::Value::scalar(excerpt.clone()));
+            }
+            
             // Store the rendered content
             doc.rendered_content = Some(rendered_content.clone());
             
@@ -163,7 +186,7 @@ pub fn process_collections(
                 match apply_layout(&rendered_content, layout, &layouts, &parser, &globals, &config) {
                     Ok(content) => content,
                     Err(e) => {
-                        error!("Error applying layout to {}: {}", doc.path.display(), e);
+                        diagnostics::error(Category::Liquid, &doc.path, format!("Error applying layout '{}': {}", layout, e));
                         let mut error_count = error_count.lock().unwrap();
                         *error_count += 1;
                         return;
@@ -173,25 +196,18 @@ pub fn process_collections(
                 rendered_content
             };
             
-            // Write the final content to the output file
-            match File::create(&output_path) {
-                Ok(mut file) => {
-                    if let Err(e) = file.write_all(final_content.as_bytes()) {
-                        error!("Error writing to {}: {}", output_path.display(), e);
-                        let mut error_count = error_count.lock().unwrap();
-                        *error_count += 1;
-                    } else {
-                        debug!("Generated {}", output_path.display());
-                        let mut processed_count = processed_count.lock().unwrap();
-                        *processed_count += 1;
-                    }
+            debug!("Rendered {}", output_path.display());
+            *processed_count.lock().unwrap() += 1;
+            
+            outputs.lock().unwrap().push(RenderedOutput {
+                record: RenderRecord {
+                    source: doc.path.clone(),
+                    output: output_path,
+                    layouts: layout_paths(doc.front_matter.layout.as_deref(), &layouts),
+                    includes: dependencies::finish_recording(),
                 },
-                Err(e) => {
-                    error!("Error creating file {}: {}", output_path.display(), e);
-                    let mut error_count = error_count.lock().unwrap();
-                    *error_count += 1;
-                }
-            }
+                content: Some(final_content),
+            });
         });
         
         let errors = *error_count.lock().unwrap();
@@ -200,7 +216,7 @@ pub fn process_collections(
         } else {
             0
         };
-        info!("Processed {}/{} documents in collection '{}'",
+        info!("Rendered {}/{} documents in collection '{}'",
               processed,
               collection_docs_count,
               label);
@@ -209,42 +225,79 @@ pub fn process_collections(
     let total_processed = *processed_count.lock().unwrap();
     let total_errors = *error_count.lock().unwrap();
     if total_errors > 0 {
-        warn!("Completed with {} documents processed and {} errors", total_processed, total_errors);
+        warn!("Completed with {} documents rendered and {} errors", total_processed, total_errors);
     } else {
-        info!("Successfully processed {} documents", total_processed);
+        info!("Successfully rendered {} documents", total_processed);
     }
     
-    Ok(())
+    let outputs = std::mem::take(&mut *outputs.lock().unwrap());
+    Ok(outputs)
 }
 
-/// Process and render pages
-pub fn process_pages(
-    pages: Vec<Page>,
+/// Render the content of a page or document, converting Markdown before Liquid
+fn render_content(
+    content: &str,
+    path: &Path,
+    is_markdown: bool,
+    markdown_renderer: &MarkdownRenderer,
+    parser: &liquid::Parser,
+    checked: &Checked,
+    globals: &Object
+) -> Result<String, String> {
+    let html = if is_markdown { markdown_renderer.render(content) } else { content.to_string() };
+    
+    // Decode HTML entities before parsing
+    let decoded_content = preprocess::quote_post_url_names(&html_escape::decode_html_entities(&html));
+    let parsed = checked.apply(&decoded_content);
+    match parser.parse(&parsed) {
+        Ok(template) => template.render(globals)
+            .map_err(|e| errors::describe(&e, content, &parsed, path)),
+        Err(e) => Err(errors::describe(&e, content, &parsed, path))
+    }
+}
+
+/// Convert the related posts of each document in a collection to Liquid arrays
+fn related_posts_to_liquid(collection: &Collection) -> HashMap<String, liquid::model::Value> {
+    let documents: HashMap<&str, &Document> = collection.documents.iter()
+        .map(|doc| (doc.id.as_str(), doc))
+        .collect();
+    
+    collection.documents.iter()
+        .filter(|doc| !doc.related.is_empty())
+        .map(|doc| {
+            let related = doc.related.iter()
+                .filter_map(|id| documents.get(id.as_str()))
+                .map(|related| crate::collections::document_to_liquid(related))
+                .collect();
+            (doc.id.clone(), liquid::model::Value::Array(related))
+        })
+        .collect()
+}
+
+/// Render pages
+///
+/// When `render_only` is given, only pages whose output path is in the set
+/// are rendered; the others are assumed to be up to date.
+pub fn render_pages(
+    pages: &[Page],
     layouts: &HashMap<String, LayoutInfo>,
     parser: &liquid::Parser,
     site_data: &Object,
     markdown_renderer: &MarkdownRenderer,
-    config: &Config
-) -> BoxResult<()> {
-    info!("Processing pages...");
+    config: &Config,
+    render_only: Option<&HashSet<PathBuf>>
+) -> BoxResult<Vec<RenderedOutput>> {
+    info!("Rendering pages...");
     
     // Use a thread-safe counter for statistics
     let processed_count = Arc::new(Mutex::new(0));
     let error_count = Arc::new(Mutex::new(0));
-    let _total_pages = pages.len();
+    let outputs = Arc::new(Mutex::new(Vec::new()));
     
-    // Create output directories first to avoid race conditions
-    let output_paths: Vec<_> = pages.iter()
-        .filter_map(|page| page.output_path.as_ref().cloned())
+    // Skip pages that are up to date
+    let pages: Vec<&Page> = pages.iter()
+        .filter(|page| render_only.is_none_or(|set| page.output_path.as_ref().is_some_and(|path| set.contains(path))))
         .collect();
-        
-    output_paths.par_iter().for_each(|path| {
-        if let Some(parent) = path.parent() {
-            if let Err(e) = fs::create_dir_all(parent) {
-                error!("Failed to create directory {}: {}", parent.display(), e);
-            }
-        }
-    });
     
     // Thread-safe wrappers for resources
     let layouts = Arc::new(layouts.clone());
@@ -252,9 +305,17 @@ pub fn process_pages(
     let site_data = Arc::new(site_data.clone());
     let markdown_renderer = Arc::new(markdown_renderer);
     let config = Arc::new(config.clone());
+    let strict_mode = StrictMode::from_config(&config);
     
-    // Process pages in parallel
+    // Process pages in parallel, reporting to the site on each thread
+    let collector = Diagnostics::current();
+    let timings = Profiler::current();
+    let state = RenderState::current();
     pages.into_par_iter().for_each(|page| {
+        let _collector = collector.as_ref().map(Diagnostics::enter);
+        let _timings = timings.as_ref().map(Profiler::enter);
+        let _state = state.as_ref().map(RenderState::enter);
+        
         // Skip pages that don't have an output path
         if page.output_path.is_none() {
             debug!("Skipping page {} (no output path)", page.path.display());
@@ -266,56 +327,63 @@ pub fn process_pages(
         // Handle special static files
         if !page.process {
             // Just copy the file
-            if let Err(e) = fs::copy(&page.path, output_path) {
-                error!("Error copying static file {}: {}", page.path.display(), e);
-                let mut error_count = error_count.lock().unwrap();
-                *error_count += 1;
-            } else {
-                let mut processed_count = processed_count.lock().unwrap();
-                *processed_count += 1;
-            }
+            *processed_count.lock().unwrap() += 1;
+            outputs.lock().unwrap().push(RenderedOutput {
+                record: RenderRecord {
+                    source: page.path.clone(),
+                    output: output_path.clone(),
+                    layouts: Vec::new(),
+                    includes: Vec::new(),
+                },
+                content: None,
+            });
             return;
         }
         
+        // Track the includes used by this page
+        dependencies::start_recording();
+        diagnostics::set_current_file(&page.path);
+        let _timer = profiler::start(TemplateKind::Page, page.path.to_string_lossy());
+        let _frame = errors::enter(TemplateKind::Page, &page.path);
+        
         // Create globals for this page
         let mut globals = create_globals(&config, Some(&site_data));
-        globals.insert("page".into(), crate::builder::site::page_to_liquid(&page));
+        globals.insert("page".into(), crate::builder::site::page_to_liquid(page));
         globals.insert("content".into(), liquid::model::Value::scalar(page.content.clone()));
+        if let Some(paginator) = &page.paginator {
+            globals.insert("paginator".into(), paginator.to_liquid());
+        }
         
-        // Render content (markdown or liquid)
-        let rendered_content = match if is_markdown_file(&page.path, &config) {
-            // Render markdown to HTML
-            let html_content = markdown_renderer.render(&page.content);
-            
-            // Then process with Liquid - decode HTML entities before parsing
-            let decoded_content = html_escape::decode_html_entities(&html_content).to_string();
-            match parser.parse(&decoded_content) {
-                Ok(template) => match template.render(&globals) {
-                    Ok(content) => Ok(content),
-                    Err(e) => Err(format!("Error rendering liquid in markdown: {}", e))
-                },
-                Err(e) => Err(format!("Error parsing liquid in markdown: {}", e))
-            }
-        } else {
-            // Just process with Liquid - decode HTML entities before parsing
-            let decoded_content = html_escape::decode_html_entities(&page.content).to_string();
-            match parser.parse(&decoded_content) {
-                Ok(template) => match template.render(&globals) {
-                    Ok(content) => Ok(content),
-                    Err(e) => Err(format!("Error rendering liquid: {}", e))
-                },
-                Err(e) => Err(format!("Error parsing liquid: {}", e))
+        // Report undefined variables and unknown filters, per the Liquid error mode
+        let checked = match strict::check(&page.content, &page.path, &mut globals, &parser, strict_mode) {
+            Ok(checked) => checked,
+            Err(e) => {
+                diagnostics::error(Category::Liquid, &page.path, e);
+                let mut error_count = error_count.lock().unwrap();
+                *error_count += 1;
+                return;
             }
-        } {
+        };
+        
+        // Render content (markdown or liquid)
+        let is_markdown = is_markdown_file(&page.path, &config);
+        let rendered_content = match render_content(&page.content, &page.path, is_markdown, &markdown_renderer, &parser, &checked, &globals) {
             Ok(content) => content,
             Err(e) => {
-                error!("Error processing page {}: {}", page.path.display(), e);
+                diagnostics::error(Category::Liquid, &page.path, e);
                 let mut error_count = error_count.lock().unwrap();
                 *error_count += 1;
                 return;
             }
         };
         
+        // An excerpt from the front matter is rendered like the content
+        let excerpt = page.front_matter.excerpt.as_ref()
+            .and_then(|excerpt| render_content(excerpt, &page.path, is_markdown, &markdown_renderer, &parser, &checked, &globals).ok());
+        if let (Some(excerpt), Some(liquid::model::Value::Object(page))) = (excerpt, globals.get_mut("page")) {
+            page.insert("excerpt".into(), liquid::model::Value::scalar(excerpt));
+        }
+        
         // Update the globals with the rendered content
         globals.insert("content".into(), liquid::model::Value::scalar(rendered_content.clone()));
         
@@ -324,7 +392,7 @@ pub fn process_pages(
             match apply_layout(&rendered_content, layout, &layouts, &parser, &globals, &config) {
                 Ok(content) => content,
                 Err(e) => {
-                    error!("Error applying layout to {}: {}", page.path.display(), e);
+                    diagnostics::error(Category::Liquid, &page.path, format!("Error applying layout '{}': {}", layout, e));
                     let mut error_count = error_count.lock().unwrap();
                     *error_count += 1;
                     return;
@@ -334,36 +402,68 @@ pub fn process_pages(
             rendered_content
         };
         
-        // Write the final content to the output file
-        match File::create(output_path) {
-            Ok(mut file) => {
-                if let Err(e) = file.write_all(final_content.as_bytes()) {
-                    error!("Error writing to {}: {}", output_path.display(), e);
-                    let mut error_count = error_count.lock().unwrap();
-                    *error_count += 1;
-                } else {
-                    debug!("Generated {}", output_path.display());
-                    let mut processed_count = processed_count.lock().unwrap();
-                    *processed_count += 1;
-                }
+        debug!("Rendered {}", output_path.display());
+        *processed_count.lock().unwrap() += 1;
+        
+        outputs.lock().unwrap().push(RenderedOutput {
+            record: RenderRecord {
+                source: page.path.clone(),
+                output: output_path.clone(),
+                layouts: layout_paths(page.front_matter.layout.as_deref(), &layouts),
+                includes: dependencies::finish_recording(),
             },
-            Err(e) => {
-                error!("Error creating file {}: {}", output_path.display(), e);
-                let mut error_count = error_count.lock().unwrap();
-                *error_count += 1;
-            }
-        }
+            content: Some(final_content),
+        });
     });
     
     let total_processed = *processed_count.lock().unwrap();
     let total_errors = *error_count.lock().unwrap();
     if total_errors > 0 {
-        warn!("Completed with {} pages processed and {} errors", total_processed, total_errors);
+        warn!("Completed with {} pages rendered and {} errors", total_processed, total_errors);
     } else {
-        info!("Successfully processed {} pages", total_processed);
+        info!("Successfully rendered {} pages", total_processed);
     }
     
-    Ok(())
+    let outputs = std::mem::take(&mut *outputs.lock().unwrap());
+    Ok(outputs)
+}
+
+/// Write rendered outputs to the destination in parallel
+///
+/// Returns the records of the outputs that were written.
+pub fn write_outputs(outputs: &[RenderedOutput]) -> Vec<RenderRecord> {
+    // Create output directories first to avoid race conditions
+    let directories: HashSet<&Path> = outputs.iter()
+        .filter_map(|output| output.record.output.parent())
+        .collect();
+    for directory in directories {
+        if let Err(e) = fs::create_dir_all(directory) {
+            error!("Failed to create directory {}: {}", directory.display(), e);
+        }
+    }
+    
+    let collector = Diagnostics::current();
+    outputs.par_iter()
+        .filter_map(|output| {
+            let _collector = collector.as_ref().map(Diagnostics::enter);
+            let record = &output.record;
+            let result = match &output.content {
+                Some(content) => fs::write(&record.output, content),
+                None => fs::copy(&record.source, &record.output).map(|_| ()),
+            };
+            
+            match result {
+                Ok(()) => {
+                    debug!("Generated {}", record.output.display());
+                    Some(record.clone())
+                },
+                Err(e) => {
+                    diagnostics::error(Category::Output, &record.source, format!("Error writing to {}: {}", record.output.display(), e));
+                    None
+                }
+            }
+        })
+        .collect()
 }
 
 /// Apply a layout to content
@@ -379,11 +479,25 @@ pub fn apply_layout(
     let layout_info = layouts.get(layout_name)
         .ok_or_else(|| format!("Layout '{}' not found", layout_name))?;
 
-    // Create new template with the layout content
-    let template = parser.parse(&layout_info.content)?;
-    
-    // Render the layout with the content
-    let rendered = template.render(globals)?;
+    // Create new template with the layout content and render it, timing this layout alone
+    let rendered = {
+        let _timer = profiler::start(TemplateKind::Layout, layout_name);
+        let _frame = errors::enter(TemplateKind::Layout, &layout_info.path);
+        let strict_mode = StrictMode::from_config(config);
+        
+        // Missing variables are set to nil in a copy, leaving the page's globals alone
+        let mut checked_globals = None;
+        let checked = if strict_mode.is_lax() {
+            Checked::default()
+        } else {
+            let layout_globals = checked_globals.insert(globals.clone());
+            strict::check(&layout_info.content, &layout_info.path, layout_globals, parser, strict_mode)?
+        };
+        let parsed = checked.apply(&layout_info.content);
+        parser.parse(&parsed)
+            .and_then(|template| template.render(checked_globals.as_ref().unwrap_or(globals)))
+            .map_err(|e| errors::describe(&e, &layout_info.content, &parsed, &layout_info.path))?
+    };
     
     // Check if the layout has a parent layout
     if let Some(parent_layout) = get_parent_layout(layout_name, layouts) {
@@ -397,6 +511,30 @@ pub fn apply_layout(
     }
 }
 
+/// Get the files of a layout and all of its parent layouts, innermost first
+fn layout_paths(layout_name: Option<&str>, layouts: &HashMap<String, LayoutInfo>) -> Vec<PathBuf> {
+    layout_chain(layout_name, layouts).iter()
+        .map(|name| layouts[name].path.clone())
+        .collect()
+}
+
+/// Get the names of a layout and all of its parent layouts, innermost first
+pub fn layout_chain(layout_name: Option<&str>, layouts: &HashMap<String, LayoutInfo>) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut current = layout_name.map(|name| name.to_string());
+    
+    while let Some(name) = current {
+        // Guard against missing layouts and layouts that inherit from themselves
+        if !layouts.contains_key(&name) || names.contains(&name) {
+            break;
+        }
+        current = get_parent_layout(&name, layouts);
+        names.push(name);
+    }
+    
+    names
+}
+
 /// Extract the parent layout name from a layout
 fn get_parent_layout(layout_name: &str, layouts: &HashMap<String, LayoutInfo>) -> Option<String> {
     if let Some(layout_info) = layouts.get(layout_name) {
@@ -0,0 +1,299 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use log::{debug, info, warn};
+
+use crate::config::Config;
+use crate::collections::Collection;
+use crate::directory::DirectoryStructure;
+use crate::builder::page::{output_path_for_url, Page};
+use crate::builder::site::{absolute_document_url, strip_baseurl, with_baseurl, RenderRecord, RenderedOutput};
+
+/// Files of host redirect rules, by format
+const RULE_FILES: &[(&str, &str)] = &[
+    ("netlify", "_redirects"),
+    ("nginx", "redirects.nginx.conf"),
+    ("apache", ".htaccess"),
+];
+
+/// A redirect from an old path of the site to an absolute URL
+#[derive(Debug, Clone)]
+pub struct Redirect {
+    /// Path redirected, relative to the site root
+    pub from: String,
+    /// Absolute URL redirected to
+    pub to: String,
+    /// Source of the page asking for the redirect
+    pub source: PathBuf,
+    /// Whether the page itself is replaced, from `redirect_to`
+    pub replaces_page: bool,
+}
+
+/// Collect the redirects asked for by pages and documents
+///
+/// `redirect_from` paths redirect to the page, and a page with `redirect_to`
+/// redirects to that URL instead, like jekyll-redirect-from.
+pub fn collect_redirects(pages: &[Page], collections: &HashMap<String, Collection>, config: &Config) -> Vec<Redirect> {
+    let mut redirects = Vec::new();
+
+    let mut labels: Vec<&String> = collections.keys().collect();
+    labels.sort();
+    for label in labels {
+        let collection = &collections[label];
+        if !collection.output {
+            continue;
+        }
+        for doc in &collection.documents {
+            let url = match &doc.url {
+                Some(url) if doc.should_write(config) => url,
+                _ => continue,
+            };
+            // Document URLs already start with the baseurl
+            let own_url = absolute_document_url(url, config);
+            let relative_url = strip_baseurl(url, config);
+            add_redirects(&mut redirects, &doc.path, &relative_url, &own_url, &doc.front_matter.redirect_from, &doc.front_matter.redirect_to, config);
+        }
+    }
+
+    for page in pages {
+        if let Some(url) = &page.url {
+            // Redirects go to the directory rather than its index
+            let url = url.strip_suffix("index.html").unwrap_or(url);
+            let own_url = config.absolute_url(url);
+            add_redirects(&mut redirects, &page.path, url, &own_url, &page.front_matter.redirect_from, &page.front_matter.redirect_to, config);
+        }
+    }
+
+    redirects
+}
+
+/// Add the redirects of one page
+fn add_redirects(
+    redirects: &mut Vec<Redirect>,
+    source: &Path,
+    url: &str,
+    own_url: &str,
+    redirect_from: &Option<Vec<String>>,
+    redirect_to: &Option<String>,
+    config: &Config
+) {
+    // A page redirecting elsewhere sends its old paths straight to the target
+    let target = match redirect_to.as_deref().map(str::trim).filter(|to| !to.is_empty()) {
+        Some(to) => {
+            let to = config.absolute_url(to);
+            redirects.push(Redirect { from: url.to_string(), to: to.clone(), source: source.to_path_buf(), replaces_page: true });
+            to
+        },
+        None => own_url.to_string(),
+    };
+
+    for from in redirect_from.iter().flatten() {
+        let from = from.trim();
+        if from.is_empty() || from.contains("://") {
+            warn!("Ignoring redirect_from '{}' in {}, it must be a path of the site", from, source.display());
+            continue;
+        }
+        redirects.push(Redirect {
+            from: format!("/{}", from.trim_start_matches('/')),
+            to: target.clone(),
+            source: source.to_path_buf(),
+            replaces_page: false,
+        });
+    }
+}
+
+/// Replace the output of pages with `redirect_to` by a redirect page
+pub fn apply_redirect_to(outputs: &mut [RenderedOutput], redirects: &[Redirect]) {
+    let targets: HashMap<&Path, &str> = redirects.iter()
+        .filter(|redirect| redirect.replaces_page)
+        .map(|redirect| (redirect.source.as_path(), redirect.to.as_str()))
+        .collect();
+
+    for output in outputs {
+        if let (Some(to), Some(content)) = (targets.get(output.record.source.as_path()), output.content.as_mut()) {
+            *content = redirect_page(to);
+        }
+    }
+}
+
+/// Generate redirect pages, redirects.json and the configured host rules
+///
+/// Redirect pages go where no page or static file already does, and files
+/// the site already provides are left alone.
+pub fn generate_redirects(
+    redirects: &[Redirect],
+    dirs: &DirectoryStructure,
+    config: &Config,
+    taken: &HashSet<PathBuf>
+) -> Vec<RenderedOutput> {
+    if redirects.is_empty() {
+        return Vec::new();
+    }
+    let redirects_config = config.redirect_from.clone().unwrap_or_default();
+
+    let mut outputs = Vec::new();
+    let mut written = HashSet::new();
+    for redirect in redirects.iter().filter(|redirect| !redirect.replaces_page) {
+        let output = output_path_for_url(&redirect.from, &dirs.destination);
+        if taken.contains(&output) {
+            warn!("Not writing a redirect from {} to {}, a page is already there", redirect.from, redirect.to);
+            continue;
+        }
+        if !written.insert(output.clone()) {
+            warn!("{} is redirected more than once, keeping the first", redirect.from);
+            continue;
+        }
+        outputs.push(generated_output(redirect.source.clone(), output, redirect_page(&redirect.to)));
+    }
+
+    let mut files = Vec::new();
+    if redirects_config.json {
+        let map: serde_json::Map<String, serde_json::Value> = redirects.iter()
+            .map(|redirect| (redirect.from.clone(), serde_json::Value::String(redirect.to.clone())))
+            .collect();
+        files.push(("redirects.json", serde_json::Value::Object(map).to_string()));
+    }
+    for format in &redirects_config.formats {
+        match RULE_FILES.iter().find(|(name, _)| name.eq_ignore_ascii_case(format)) {
+            Some((name, file)) => files.push((file, host_rules(name, redirects, config))),
+            None => warn!("Unknown redirect format '{}', expected netlify, nginx or apache", format),
+        }
+    }
+
+    for (name, content) in files {
+        let output = dirs.destination.join(name);
+        if taken.contains(&output) {
+            debug!("Not generating {}, the site has one", name);
+            continue;
+        }
+        // Like feeds, the files are recorded with stand-in sources
+        outputs.push(generated_output(dirs.source.join(name), output, content));
+    }
+
+    info!("Generated {} redirects", redirects.len());
+    outputs
+}
+
+/// A generated file with its content
+fn generated_output(source: PathBuf, output: PathBuf, content: String) -> RenderedOutput {
+    RenderedOutput {
+        record: RenderRecord {
+            source,
+            output,
+            layouts: Vec::new(),
+            includes: Vec::new(),
+        },
+        content: Some(content),
+    }
+}
+
+/// A page sending browsers and crawlers to another URL, with the markup of jekyll-redirect-from
+fn redirect_page(to: &str) -> String {
+    let href = html_escape::encode_double_quoted_attribute(to);
+    // The URL is a JSON string in the script, which must not close the element
+    let location = serde_json::Value::String(to.to_string()).to_string().replace("</", "<\\/");
+    format!(
+        "<!DOCTYPE html>\n\
+         <html lang=\"en-US\">\n  \
+         <meta charset=\"utf-8\">\n  \
+         <title>Redirecting&hellip;</title>\n  \
+         <link rel=\"canonical\" href=\"{href}\">\n  \
+         <script>location={location}</script>\n  \
+         <meta http-equiv=\"refresh\" content=\"0; url={href}\">\n  \
+         <meta name=\"robots\" content=\"noindex\">\n  \
+         <h1>Redirecting&hellip;</h1>\n  \
+         <a href=\"{href}\">Click here if you are not redirected.</a>\n\
+         </html>\n"
+    )
+}
+
+/// Rules for a host to answer the redirects with a 301
+fn host_rules(format: &str, redirects: &[Redirect], config: &Config) -> String {
+    let mut rules = String::new();
+    match format {
+        "netlify" => {
+            for redirect in redirects {
+                rules.push_str(&format!("{}  {}  301\n", with_baseurl(&redirect.from, config), redirect.to));
+            }
+        },
+        "nginx" => {
+            rules.push_str("# Include in the http block, then redirect in the server block with\n");
+            rules.push_str("# if ($redirect_uri) { return 301 $redirect_uri; }\n");
+            rules.push_str("map $uri $redirect_uri {\n");
+            for redirect in redirects {
+                let pattern = format!("~^{}/?$", path_pattern(&redirect.from, config));
+                rules.push_str(&format!("    {} {};\n", nginx_quote(&pattern), nginx_quote(&redirect.to)));
+            }
+            rules.push_str("}\n");
+        },
+        _ => {
+            // Redirect would also match every path below the old one
+            for redirect in redirects {
+                let pattern = path_pattern(&redirect.from, config);
+                rules.push_str(&format!("RedirectMatch 301 ^{}/?$ {}\n", pattern, redirect.to.replace(' ', "%20")));
+            }
+        },
+    }
+    rules
+}
+
+/// A regular expression matching an old path, without its trailing slash
+fn path_pattern(from: &str, config: &Config) -> String {
+    regex::escape(with_baseurl(from, config).trim_end_matches('/'))
+}
+
+/// Quote a string for nginx
+fn nginx_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RedirectsConfig;
+    use crate::test_support::TestSite;
+
+    #[test]
+    fn test_redirects() {
+        let site = TestSite::new("redirects");
+        site.write("_posts/2024-01-01-hello.md", "---\ntitle: Hello\nredirect_from:\n  - /old-hello/\n  - /older-hello.html\n---\nHello")
+            .write("moved.md", "---\nredirect_to: https://elsewhere.com/moved/\nredirect_from: /gone/\n---\nMoved")
+            .write("about.md", "---\npermalink: /about/\nredirect_from: /about-us/\n---\nAbout");
+
+        site.build(Config {
+            url: Some("https://example.com".to_string()),
+            redirect_from: Some(RedirectsConfig { json: true, formats: vec!["netlify".to_string(), "apache".to_string()] }),
+            ..site.config()
+        });
+
+        let stub = site.read("old-hello/index.html");
+        assert!(stub.contains("<link rel=\"canonical\" href=\"https://example.com/2024/01/01/hello\">"));
+        assert!(stub.contains("<meta http-equiv=\"refresh\" content=\"0; url=https://example.com/2024/01/01/hello\">"));
+        assert!(stub.contains("<script>location=\"https://example.com/2024/01/01/hello\"</script>"));
+        assert!(site.output("older-hello.html").exists());
+
+        // Pages with redirect_to are replaced, and their old paths go straight to the target
+        let moved = site.read("moved.html");
+        assert!(moved.contains("url=https://elsewhere.com/moved/"));
+        assert!(!moved.contains("Moved"));
+        assert!(site.read("gone/index.html").contains("url=https://elsewhere.com/moved/"));
+
+        let json: serde_json::Value = serde_json::from_str(&site.read("redirects.json")).unwrap();
+        assert_eq!(json["/about-us/"], "https://example.com/about/");
+        assert_eq!(json["/moved.html"], "https://elsewhere.com/moved/");
+
+        assert!(site.read("_redirects").contains("/old-hello/  https://example.com/2024/01/01/hello  301\n"));
+        assert!(site.read(".htaccess").contains("RedirectMatch 301 ^/about\\-us/?$ https://example.com/about/\n"));
+
+        // Redirects are not in the sitemap
+        assert!(!site.read("sitemap.xml").contains("moved"));
+    }
+
+    #[test]
+    fn test_strip_baseurl() {
+        let config = Config { base_url: "/blog".to_string(), ..Config::default() };
+        assert_eq!(strip_baseurl("/blog/2024/hello.html", &config), "/2024/hello.html");
+        assert_eq!(strip_baseurl("/blogging/", &config), "/blogging/");
+        assert_eq!(with_baseurl("/old/", &config), "/blog/old/");
+    }
+}
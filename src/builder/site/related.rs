@@ -0,0 +1,458 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use log::{debug, info, warn};
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{Serialize, Deserialize};
+
+use crate::config::Config;
+use crate::collections::Collection;
+use crate::builder::types::BoxResult;
+use crate::builder::site::cache::hash_bytes;
+
+/// Number of related posts stored for each post, as in Jekyll
+const RELATED_POSTS_LIMIT: usize = 10;
+
+/// Number of LSI dimensions kept by the truncated SVD
+const LSI_DIMENSIONS: usize = 50;
+
+/// Iterations of the subspace iteration used to compute the truncated SVD
+const LSI_ITERATIONS: usize = 12;
+
+/// Name of the related posts cache file inside the cache directory
+const CACHE_FILE: &str = "related.json";
+
+lazy_static! {
+    // Markup that says nothing about what a post is about
+    static ref LIQUID_REGEX: Regex = Regex::new(r"(?s)\{%.*?%\}|\{\{.*?\}\}").unwrap();
+    static ref HTML_TAG_REGEX: Regex = Regex::new(r"<[^>]*>").unwrap();
+    static ref URL_REGEX: Regex = Regex::new(r"\(?https?://\S+").unwrap();
+    static ref WORD_REGEX: Regex = Regex::new(r"[\p{L}\p{N}]+(?:'[\p{L}]+)?").unwrap();
+}
+
+/// Common English words that carry no topical meaning
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "above", "after", "again", "against", "all", "also", "am", "an", "and",
+    "any", "are", "as", "at", "be", "because", "been", "before", "being", "below", "between",
+    "both", "but", "by", "can", "could", "did", "do", "does", "doing", "down", "during",
+    "each", "even", "few", "for", "from", "further", "get", "got", "had", "has", "have",
+    "having", "he", "her", "here", "hers", "herself", "him", "himself", "his", "how", "i",
+    "if", "in", "into", "is", "it", "its", "itself", "just", "like", "make", "many", "me",
+    "more", "most", "much", "must", "my", "myself", "new", "no", "nor", "not", "now", "of",
+    "off", "on", "once", "one", "only", "or", "other", "our", "ours", "ourselves", "out",
+    "over", "own", "really", "same", "she", "should", "so", "some", "such", "than", "that",
+    "the", "their", "theirs", "them", "themselves", "then", "there", "these", "they",
+    "this", "those", "through", "to", "too", "two", "under", "until", "up", "us", "use",
+    "used", "using", "very", "was", "way", "we", "well", "were", "what", "when", "where",
+    "which", "while", "who", "whom", "why", "will", "with", "would", "you", "your", "yours",
+    "yourself", "yourselves",
+];
+
+/// Related posts computed by a previous build
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RelatedCache {
+    /// Fingerprint of the post contents the related posts were computed from
+    fingerprint: String,
+    /// Map of post IDs to the IDs of their related posts
+    related: BTreeMap<String, Vec<String>>,
+}
+
+/// Process related posts for all collections
+///
+/// With `lsi` enabled, posts are related by the similarity of their content
+/// (TF-IDF weighted terms, reduced with a truncated SVD when there are more
+/// posts than LSI dimensions). Otherwise posts are related by date.
+pub fn process_related_posts(collections: &mut HashMap<String, Collection>, config: &Config) -> BoxResult<()> {
+    if let Some(posts) = collections.get_mut("posts") {
+        // Only process if there are enough posts
+        if posts.documents.len() < 2 {
+            return Ok(());
+        }
+
+        if config.lsi.unwrap_or(false) {
+            generate_lsi_related_posts(posts, config)?;
+        } else {
+            generate_date_based_related_posts(posts)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Generate content-based related posts, reusing the cached result when posts are unchanged
+fn generate_lsi_related_posts(posts: &mut Collection, config: &Config) -> BoxResult<()> {
+    let texts: Vec<String> = posts.documents.iter()
+        .map(|doc| format!("{}\n{}", doc.front_matter.title.as_deref().unwrap_or(""), doc.content))
+        .collect();
+
+    // Fingerprint of every post and the engine settings
+    let mut entries: Vec<String> = posts.documents.iter()
+        .zip(&texts)
+        .map(|(doc, text)| format!("{}:{}", doc.id, hash_bytes(text.as_bytes())))
+        .collect();
+    entries.sort();
+    entries.push(format!("{}:{}:{}", RELATED_POSTS_LIMIT, LSI_DIMENSIONS, LSI_ITERATIONS));
+    let fingerprint = hash_bytes(entries.join("\n").as_bytes());
+
+    let cache_path = Path::new(&config.source).join(&config.cache_dir).join(CACHE_FILE);
+    let cache = load_cache(&cache_path);
+
+    let related = if cache.fingerprint == fingerprint {
+        debug!("Reusing cached related posts");
+        cache.related
+    } else {
+        info!("Computing related posts for {} posts using LSI", texts.len());
+        let indices = related_indices(&texts, RELATED_POSTS_LIMIT);
+        let related: BTreeMap<String, Vec<String>> = posts.documents.iter()
+            .zip(indices)
+            .map(|(doc, related)| {
+                let ids = related.into_iter().map(|j| posts.documents[j].id.clone()).collect();
+                (doc.id.clone(), ids)
+            })
+            .collect();
+
+        let cache = RelatedCache { fingerprint, related };
+        if let Err(e) = save_cache(&cache_path, &cache) {
+            warn!("Failed to save related posts cache: {}", e);
+        }
+        cache.related
+    };
+
+    for doc in &mut posts.documents {
+        doc.related = related.get(&doc.id).cloned().unwrap_or_default();
+    }
+
+    Ok(())
+}
+
+/// Generate date-based related posts
+fn generate_date_based_related_posts(posts: &mut Collection) -> BoxResult<()> {
+    // For each post, find the closest posts by date
+    for i in 0..posts.documents.len() {
+        let current = &posts.documents[i];
+
+        // Consider tags for better matching
+        let current_tags: HashSet<&String> = current.tags.iter().collect();
+
+        // Score other posts based on tag overlap and date proximity
+        let mut scored_posts: Vec<(usize, f64)> = Vec::new();
+
+        for (j, post) in posts.documents.iter().enumerate() {
+            if i == j {
+                continue; // Skip self
+            }
+
+            // Base score on date proximity
+            let score = if let (Some(current_date), Some(post_date)) = (current.date, post.date) {
+                let diff = (current_date - post_date).num_days().abs() as f64;
+                1.0 / (1.0 + diff / 30.0) // Scale by month
+            } else {
+                0.1 // Default low score
+            };
+
+            // Bonus for tag overlap
+            let tag_overlap = post.tags.iter().filter(|tag| current_tags.contains(tag)).count();
+            let tag_bonus = tag_overlap as f64 * 0.2;
+
+            scored_posts.push((j, score + tag_bonus));
+        }
+
+        // Sort by score
+        scored_posts.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Update the document with related posts
+        let related = scored_posts.iter()
+            .take(RELATED_POSTS_LIMIT)
+            .map(|(j, _)| posts.documents[*j].id.clone())
+            .collect();
+        posts.documents[i].related = related;
+    }
+
+    Ok(())
+}
+
+/// Find the most similar texts for each text, most similar first
+///
+/// Texts that share no terms with a text are never related to it.
+fn related_indices(texts: &[String], limit: usize) -> Vec<Vec<usize>> {
+    let vectors = tfidf_vectors(texts);
+    let n = vectors.len();
+
+    let similarity: Box<dyn Fn(usize, usize) -> f64 + Sync> = if n > LSI_DIMENSIONS {
+        let space = LsiSpace::new(&vectors, LSI_DIMENSIONS);
+        Box::new(move |i, j| space.similarity(i, j))
+    } else {
+        // Fewer posts than dimensions, the SVD would not reduce anything
+        Box::new(|i, j| sparse_dot(&vectors[i], &vectors[j]))
+    };
+
+    (0..n).into_par_iter()
+        .map(|i| {
+            let mut scored: Vec<(usize, f64)> = (0..n)
+                .filter(|&j| j != i)
+                .map(|j| (j, similarity(i, j)))
+                .filter(|(_, score)| *score > 1e-9)
+                .collect();
+            scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(&b.0)));
+            scored.into_iter().take(limit).map(|(j, _)| j).collect()
+        })
+        .collect()
+}
+
+/// Split a post into lowercase terms, dropping markup and stop words
+fn tokenize(text: &str) -> Vec<String> {
+    let text = LIQUID_REGEX.replace_all(text, " ");
+    let text = HTML_TAG_REGEX.replace_all(&text, " ");
+    let text = URL_REGEX.replace_all(&text, " ");
+
+    WORD_REGEX.find_iter(&text)
+        .map(|word| word.as_str().to_lowercase().replace('\'', ""))
+        .filter(|word| word.chars().count() > 2)
+        .filter(|word| !word.chars().all(|c| c.is_ascii_digit()))
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// Build L2-normalized TF-IDF vectors, as sparse (term, weight) lists sorted by term
+fn tfidf_vectors(texts: &[String]) -> Vec<Vec<(usize, f64)>> {
+    let mut vocabulary: HashMap<String, usize> = HashMap::new();
+    let mut counts: Vec<BTreeMap<usize, usize>> = Vec::with_capacity(texts.len());
+
+    for text in texts {
+        let mut doc_counts = BTreeMap::new();
+        for term in tokenize(text) {
+            let next_id = vocabulary.len();
+            let id = *vocabulary.entry(term).or_insert(next_id);
+            *doc_counts.entry(id).or_insert(0) += 1;
+        }
+        counts.push(doc_counts);
+    }
+
+    // Number of posts each term appears in
+    let mut document_frequency = vec![0usize; vocabulary.len()];
+    for doc_counts in &counts {
+        for term in doc_counts.keys() {
+            document_frequency[*term] += 1;
+        }
+    }
+
+    let n = texts.len() as f64;
+    counts.into_iter()
+        .map(|doc_counts| {
+            // Sublinear term frequency and smoothed inverse document frequency
+            let mut vector: Vec<(usize, f64)> = doc_counts.into_iter()
+                .map(|(term, count)| {
+                    let tf = 1.0 + (count as f64).ln();
+                    let idf = ((1.0 + n) / (1.0 + document_frequency[term] as f64)).ln() + 1.0;
+                    (term, tf * idf)
+                })
+                .collect();
+
+            let norm = vector.iter().map(|(_, w)| w * w).sum::<f64>().sqrt();
+            if norm > 0.0 {
+                for (_, w) in &mut vector {
+                    *w /= norm;
+                }
+            }
+            vector
+        })
+        .collect()
+}
+
+/// Dot product of two sparse vectors sorted by term
+fn sparse_dot(a: &[(usize, f64)], b: &[(usize, f64)]) -> f64 {
+    let (mut i, mut j, mut sum) = (0, 0, 0.0);
+    while i < a.len() && j < b.len() {
+        match a[i].0.cmp(&b[j].0) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                sum += a[i].1 * b[j].1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    sum
+}
+
+/// Posts projected onto the dominant singular vectors of the term-document matrix
+///
+/// With A the term-document matrix, the truncated SVD keeps the rank-k
+/// approximation of the document similarity matrix G = AᵀA. It is computed
+/// by subspace iteration on G: Q spans its dominant k-dimensional eigenspace,
+/// and G_k = Q (QᵀGQ) Qᵀ. Similarities are the cosines of G_k.
+struct LsiSpace {
+    /// Orthonormal basis of the dominant subspace, one row per post
+    basis: Vec<Vec<f64>>,
+    /// Rows of Q (QᵀGQ), so that G_k[i][j] = projected[i] · basis[j]
+    projected: Vec<Vec<f64>>,
+    /// Norms of the posts in the reduced space, sqrt(G_k[i][i])
+    norms: Vec<f64>,
+}
+
+impl LsiSpace {
+    fn new(vectors: &[Vec<(usize, f64)>], dimensions: usize) -> Self {
+        let n = vectors.len();
+        let k = dimensions.min(n);
+
+        // Deterministic pseudo-random start so builds are reproducible
+        let mut seed: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut basis: Vec<Vec<f64>> = (0..n)
+            .map(|_| (0..k).map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                (seed % 2000) as f64 / 1000.0 - 1.0
+            }).collect())
+            .collect();
+        orthonormalize(&mut basis, k);
+
+        for _ in 0..LSI_ITERATIONS {
+            basis = multiply_gram(vectors, &basis, k);
+            orthonormalize(&mut basis, k);
+        }
+
+        // B = Qᵀ G Q, then Q B
+        let gram_basis = multiply_gram(vectors, &basis, k);
+        let mut b = vec![vec![0.0; k]; k];
+        for (q_row, g_row) in basis.iter().zip(&gram_basis) {
+            for r in 0..k {
+                for c in 0..k {
+                    b[r][c] += q_row[r] * g_row[c];
+                }
+            }
+        }
+        let projected: Vec<Vec<f64>> = basis.iter()
+            .map(|q_row| (0..k).map(|c| (0..k).map(|r| q_row[r] * b[r][c]).sum()).collect())
+            .collect();
+
+        let norms = projected.iter().zip(&basis)
+            .map(|(p, q)| dense_dot(p, q).max(0.0).sqrt())
+            .collect();
+
+        Self { basis, projected, norms }
+    }
+
+    /// Cosine similarity of two posts in the reduced space
+    fn similarity(&self, i: usize, j: usize) -> f64 {
+        let norm = self.norms[i] * self.norms[j];
+        if norm <= f64::EPSILON {
+            return 0.0;
+        }
+        dense_dot(&self.projected[i], &self.basis[j]) / norm
+    }
+}
+
+/// Compute G Q = Aᵀ (A Q) without forming G
+fn multiply_gram(vectors: &[Vec<(usize, f64)>], basis: &[Vec<f64>], k: usize) -> Vec<Vec<f64>> {
+    // A Q, one row per term
+    let terms = vectors.iter()
+        .flat_map(|vector| vector.iter().map(|(term, _)| term + 1))
+        .max()
+        .unwrap_or(0);
+    let mut term_rows = vec![vec![0.0; k]; terms];
+    for (vector, q_row) in vectors.iter().zip(basis) {
+        for (term, weight) in vector {
+            for (value, q) in term_rows[*term].iter_mut().zip(q_row) {
+                *value += weight * q;
+            }
+        }
+    }
+
+    // Aᵀ (A Q), one row per post
+    vectors.par_iter()
+        .map(|vector| {
+            let mut row = vec![0.0; k];
+            for (term, weight) in vector {
+                for (value, t) in row.iter_mut().zip(&term_rows[*term]) {
+                    *value += weight * t;
+                }
+            }
+            row
+        })
+        .collect()
+}
+
+/// Orthonormalize the columns of a row-major matrix with modified Gram-Schmidt
+///
+/// Columns that are linearly dependent on earlier ones become zero.
+fn orthonormalize(rows: &mut [Vec<f64>], k: usize) {
+    for c in 0..k {
+        for p in 0..c {
+            let dot: f64 = rows.iter().map(|row| row[c] * row[p]).sum();
+            for row in rows.iter_mut() {
+                row[c] -= dot * row[p];
+            }
+        }
+
+        let norm = rows.iter().map(|row| row[c] * row[c]).sum::<f64>().sqrt();
+        for row in rows.iter_mut() {
+            row[c] = if norm > 1e-10 { row[c] / norm } else { 0.0 };
+        }
+    }
+}
+
+/// Dot product of two dense vectors
+fn dense_dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Load the related posts cache, or an empty one
+fn load_cache(path: &Path) -> RelatedCache {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Save the related posts cache
+fn save_cache(path: &Path, cache: &RelatedCache) -> BoxResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string(cache)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus(count: usize) -> Vec<String> {
+        let topics = [
+            "rust borrow checker lifetimes ownership compiler traits",
+            "sourdough bread flour yeast oven baking crust",
+            "marathon running training pace shoes endurance",
+        ];
+        (0..count)
+            .map(|i| format!("{} {} post{}", topics[i % 3], topics[i % 3], i))
+            .collect()
+    }
+
+    #[test]
+    fn test_related_posts_share_topics() {
+        let mut texts = corpus(3);
+        texts.push("Ownership and lifetimes in the Rust compiler {% include note.html %}".to_string());
+
+        let related = related_indices(&texts, 10);
+        assert_eq!(related[3], vec![0]);
+        assert_eq!(related[0], vec![3]);
+        assert!(related[1].is_empty());
+    }
+
+    #[test]
+    fn test_lsi_related_posts_share_topics() {
+        // More posts than LSI dimensions, so the truncated SVD is used
+        let texts = corpus(LSI_DIMENSIONS + 10);
+
+        let related = related_indices(&texts, 5);
+        for (i, related) in related.iter().enumerate() {
+            assert_eq!(related.len(), 5);
+            assert!(related.iter().all(|j| j % 3 == i % 3), "post {} related to {:?}", i, related);
+        }
+    }
+}
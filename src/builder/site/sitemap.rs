@@ -0,0 +1,222 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::{debug, info};
+
+use crate::config::{Config, SitemapConfig};
+use crate::collections::Collection;
+use crate::directory::DirectoryStructure;
+use crate::utils::date::local;
+use crate::builder::page::Page;
+use crate::builder::site::{absolute_document_url, RenderRecord, RenderedOutput};
+
+/// Most URLs a sitemap may list, from the sitemaps protocol
+const MAX_URLS: usize = 50_000;
+
+/// Generate sitemap.xml and robots.txt
+///
+/// Follows jekyll-sitemap: documents, HTML pages and static files with the
+/// configured extensions are listed unless they have `sitemap: false` or
+/// redirect elsewhere.
+/// Past 50,000 URLs, sitemap.xml becomes an index of numbered sitemaps.
+/// Files the site already provides are left alone.
+pub fn generate_sitemap(
+    pages: &[Page],
+    collections: &HashMap<String, Collection>,
+    static_files: &[(PathBuf, PathBuf)],
+    dirs: &DirectoryStructure,
+    config: &Config,
+    taken: &HashSet<PathBuf>
+) -> Vec<RenderedOutput> {
+    let sitemap_config = match config.sitemap_config() {
+        Some(sitemap_config) => sitemap_config,
+        None => return Vec::new(),
+    };
+
+    let mut files = Vec::new();
+    if taken.contains(&dirs.destination.join("sitemap.xml")) {
+        debug!("Not generating sitemap.xml, the site has one");
+    } else {
+        let urls = sitemap_urls(pages, collections, static_files, dirs, &sitemap_config, config);
+        files.extend(sitemap_files(&urls, MAX_URLS, config));
+    }
+
+    if sitemap_config.robots && !taken.contains(&dirs.destination.join("robots.txt")) {
+        files.push(("robots.txt".to_string(), format!("Sitemap: {}\n", config.absolute_url("/sitemap.xml"))));
+    }
+
+    if files.iter().any(|(name, _)| name.starts_with("sitemap")) {
+        info!("Generated sitemap");
+    }
+
+    // Like feeds, the files are recorded with stand-in sources
+    files.into_iter()
+        .map(|(name, content)| RenderedOutput {
+            record: RenderRecord {
+                source: dirs.source.join(&name),
+                output: dirs.destination.join(&name),
+                layouts: Vec::new(),
+                includes: Vec::new(),
+            },
+            content: Some(content),
+        })
+        .collect()
+}
+
+/// The URLs listed in the sitemap with their last modification date
+fn sitemap_urls(
+    pages: &[Page],
+    collections: &HashMap<String, Collection>,
+    static_files: &[(PathBuf, PathBuf)],
+    dirs: &DirectoryStructure,
+    sitemap_config: &SitemapConfig,
+    config: &Config
+) -> Vec<(String, Option<DateTime<Utc>>)> {
+    let mut urls = Vec::new();
+
+    // Documents of each collection, then pages, then static files
+    let mut labels: Vec<&String> = collections.keys().collect();
+    labels.sort();
+    for label in labels {
+        let collection = &collections[label];
+        if !collection.output {
+            continue;
+        }
+        for doc in &collection.documents {
+            let url = match &doc.url {
+                Some(url) if doc.should_write(config) && doc.front_matter.sitemap != Some(false) && doc.front_matter.redirect_to.is_none() => url,
+                _ => continue,
+            };
+            let modified = doc.front_matter.get_last_modified().or(doc.date);
+            urls.push((without_index(&absolute_document_url(url, config)), modified));
+        }
+    }
+
+    let mut html_pages: Vec<(&str, &Page)> = pages.iter()
+        .filter(|page| page.front_matter.sitemap != Some(false) && page.front_matter.redirect_to.is_none())
+        .filter(|page| page.output_path.as_deref().is_some_and(is_html))
+        .filter_map(|page| page.url.as_deref().map(|url| (url, page)))
+        .filter(|(url, _)| *url != "/404.html")
+        .collect();
+    html_pages.sort_by_key(|(url, _)| *url);
+    for (url, page) in html_pages {
+        let modified = page.front_matter.get_last_modified().or(page.date);
+        urls.push((without_index(&config.absolute_url(url)), modified));
+    }
+
+    let mut listed: Vec<&(PathBuf, PathBuf)> = static_files.iter()
+        .filter(|(_, dest)| sitemap_config.lists_static_file(dest))
+        .collect();
+    listed.sort_by(|a, b| a.1.cmp(&b.1));
+    for (source, dest) in listed {
+        let relative = dest.strip_prefix(&dirs.destination).unwrap_or(dest);
+        let url = format!("/{}", relative.to_string_lossy().replace('\\', "/"));
+        let modified = fs::metadata(source).and_then(|metadata| metadata.modified()).ok().map(DateTime::<Utc>::from);
+        urls.push((config.absolute_url(&url), modified));
+    }
+
+    urls
+}
+
+/// Write the sitemap, split into an index and numbered sitemaps if it is too long
+fn sitemap_files(urls: &[(String, Option<DateTime<Utc>>)], max_urls: usize, config: &Config) -> Vec<(String, String)> {
+    if urls.len() <= max_urls {
+        return vec![("sitemap.xml".to_string(), urlset(urls))];
+    }
+
+    let mut files = Vec::new();
+    let mut index = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    index.push_str("<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for (i, chunk) in urls.chunks(max_urls).enumerate() {
+        let name = format!("sitemap-{}.xml", i + 1);
+        index.push_str(&format!("<sitemap>\n<loc>{}</loc>\n", escape(&config.absolute_url(&name))));
+        if let Some(modified) = chunk.iter().filter_map(|(_, modified)| *modified).max() {
+            index.push_str(&format!("<lastmod>{}</lastmod>\n", xmlschema(modified)));
+        }
+        index.push_str("</sitemap>\n");
+        files.push((name, urlset(chunk)));
+    }
+    index.push_str("</sitemapindex>\n");
+
+    files.insert(0, ("sitemap.xml".to_string(), index));
+    files
+}
+
+/// A sitemap listing URLs, with the markup of jekyll-sitemap
+fn urlset(urls: &[(String, Option<DateTime<Utc>>)]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<urlset xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+                  xsi:schemaLocation=\"http://www.sitemaps.org/schemas/sitemap/0.9 http://www.sitemaps.org/schemas/sitemap/0.9/sitemap.xsd\" \
+                  xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for (loc, modified) in urls {
+        xml.push_str(&format!("<url>\n<loc>{}</loc>\n", escape(loc)));
+        if let Some(modified) = modified {
+            xml.push_str(&format!("<lastmod>{}</lastmod>\n", xmlschema(*modified)));
+        }
+        xml.push_str("</url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// Check if an output is an HTML page
+fn is_html(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| matches!(extension.to_string_lossy().as_ref(), "html" | "htm" | "xhtml"))
+}
+
+/// Drop `index.html` from a URL, like jekyll-sitemap
+fn without_index(url: &str) -> String {
+    match url.strip_suffix("/index.html") {
+        Some(directory) => format!("{}/", directory),
+        None => url.to_string(),
+    }
+}
+
+/// A date in XML Schema format, in the site's timezone
+fn xmlschema(date: DateTime<Utc>) -> String {
+    local(date).to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+/// Escape text for XML
+fn escape(text: &str) -> String {
+    html_escape::encode_double_quoted_attribute(text).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestSite;
+
+    #[test]
+    fn test_sitemap() {
+        let site = TestSite::new("sitemap");
+        site.write("_posts/2024-01-01-hello.md", "---\ntitle: Hello\nlast_modified_at: 2024-02-03\n---\nHello")
+            .write("_posts/2024-01-02-hidden.md", "---\ntitle: Hidden\nsitemap: false\n---\nHidden")
+            .write("index.html", "---\n---\nHome")
+            .write("404.html", "---\n---\nNot found")
+            .write("docs/guide.pdf", "%PDF")
+            .write("docs/notes.txt", "Notes");
+
+        let config = Config { url: Some("https://example.com".to_string()), ..site.config() };
+        site.build(config.clone());
+
+        let sitemap = site.read("sitemap.xml");
+        assert!(sitemap.contains("<loc>https://example.com/</loc>"));
+        assert!(sitemap.contains("<loc>https://example.com/docs/guide.pdf</loc>"));
+        assert!(sitemap.contains("hello</loc>\n<lastmod>2024-02-03T00:00:00+00:00</lastmod>"));
+        assert!(!sitemap.contains("hidden"));
+        assert!(!sitemap.contains("404"));
+        assert!(!sitemap.contains("notes.txt"));
+
+        assert_eq!(site.read("robots.txt"), "Sitemap: https://example.com/sitemap.xml\n");
+
+        // Long sitemaps are split behind an index
+        let urls: Vec<(String, Option<DateTime<Utc>>)> = (0..5).map(|i| (format!("https://example.com/{}/", i), None)).collect();
+        let files = sitemap_files(&urls, 2, &config);
+        assert_eq!(files.len(), 4);
+        assert!(files[0].1.contains("<sitemap>\n<loc>https://example.com/sitemap-3.xml</loc>\n</sitemap>"));
+        assert!(files[3].1.contains("<loc>https://example.com/4/</loc>"));
+    }
+}
@@ -37,7 +37,7 @@ pub fn watch_site(config: &Config, include_drafts: bool, include_unpublished: bo
     watcher.watch(&config.source, RecursiveMode::Recursive)?;
     
     // Initial build
-    build_site(config, include_drafts, include_unpublished)?;
+    build_site(config, include_drafts, include_unpublished).result?;
     
     info!("Watching for changes. Press Ctrl+C to stop.");
     
@@ -58,12 +58,12 @@ pub fn watch_site(config: &Config, include_drafts: bool, include_unpublished: bo
                     std::thread::sleep(DEBOUNCE_DURATION - elapsed);
                     
                     // Drain any additional events that came in during sleep
-                    while let Ok(_) = rx.try_recv() {}
+                    while rx.try_recv().is_ok() {}
                 }
                 
                 // Rebuild the site
                 info!("File change detected, rebuilding site...");
-                if let Err(e) = build_site(config, include_drafts, include_unpublished) {
+                if let Err(e) = build_site(config, include_drafts, include_unpublished).result {
                     error!("Error rebuilding site: {}", e);
                 }
                 
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 
 use crate::builder;
 use crate::config;
+use crate::cli::summary;
 use crate::cli::types::Commands;
 use crate::cli::logging::set_log_level;
 
@@ -19,6 +20,12 @@ pub async fn handle_build_command(
         baseurl,
         drafts,
         unpublished,
+        future,
+        lsi,
+        strict,
+        profile,
+        manifest,
+        incremental,
         verbose,
         quiet,
         config: cfg_files,
@@ -71,6 +78,30 @@ pub async fn handle_build_command(
         
         config.safe_mode = safe_mode;
         
+        if *future {
+            config.future = Some(true);
+        }
+        
+        if *lsi {
+            config.lsi = Some(true);
+        }
+        
+        if *strict {
+            config.strict = Some(true);
+        }
+        
+        if *profile {
+            config.profile = Some(true);
+        }
+        
+        if let Some(manifest) = manifest {
+            config.manifest = Some(manifest.clone());
+        }
+        
+        if *incremental {
+            config.incremental = Some(true);
+        }
+        
         // Apply baseurl if provided
         if let Some(base) = baseurl {
             config.base_url = base.clone();
@@ -78,9 +109,17 @@ pub async fn handle_build_command(
 
         // Build the site
         info!("Building site...");
-        match builder::build_site(&config, *drafts, *unpublished) {
+        let report = builder::build_site(&config, *drafts, *unpublished);
+        summary::print_outcome(&report.outcome, &config.source);
+        match report.result {
             Ok(_) => info!("Site built successfully at {}", config.destination.display()),
-            Err(e) => error!("Failed to build site: {}", e),
+            Err(e) => {
+                error!("Failed to build site: {}", e);
+                // Keep watching so the next change can fix the build
+                if !*watch {
+                    std::process::exit(1);
+                }
+            }
         }
 
         // Watch for changes if requested
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use crate::builder;
 use crate::config;
 use crate::server;
+use crate::cli::summary;
 use crate::cli::types::Commands;
 use crate::cli::logging::set_log_level;
 use crate::server::config::ServerConfig;
@@ -26,6 +27,7 @@ pub async fn handle_serve_command(
         config: cfg_files,
         drafts,
         unpublished,
+        future,
         source: serve_source,
         destination: serve_destination,
         baseurl,
@@ -70,6 +72,10 @@ pub async fn handle_serve_command(
         
         config.safe_mode = safe_mode;
         
+        if *future {
+            config.future = Some(true);
+        }
+        
         // Apply baseurl if provided
         if let Some(base) = baseurl {
             config.base_url = base.clone();
@@ -77,18 +83,21 @@ pub async fn handle_serve_command(
 
         // First build the site
         info!("Building site before serving...");
-        match builder::build_site(&config, *drafts, *unpublished) {
+        let report = builder::build_site(&config, *drafts, *unpublished);
+        summary::print_outcome(&report.outcome, &config.source);
+        match report.result {
             Ok(_) => info!("Site built successfully at {}", config.destination.display()),
             Err(e) => {
                 error!("Failed to build site: {}", e);
-                return;
+                std::process::exit(1);
             }
         }
 
         // Start server
         info!("Starting server at http://{}:{}", host, port);
         let server_config = ServerConfig::new(host, *port, *livereload)
-            .with_open_url(*open_url);
+            .with_open_url(*open_url)
+            .with_site_url(config.url.as_deref().unwrap_or(""));
 
         // If watching for changes, start a watcher thread
         if *watch {
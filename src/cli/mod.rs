@@ -1,6 +1,7 @@
 pub mod types;
 pub mod commands;
 pub mod logging;
+pub mod summary;
 
 use clap::Parser;
 use crate::config;
@@ -71,7 +72,9 @@ pub async fn run() {
             };
 
             log::info!("Building site...");
-            match crate::builder::build_site(&config, false, false) {
+            let report = crate::builder::build_site(&config, false, false);
+            summary::print_outcome(&report.outcome, &config.source);
+            match report.result {
                 Ok(_) => log::info!("Site built successfully at {}", config.destination.display()),
                 Err(e) => log::error!("Failed to build site: {}", e),
             }
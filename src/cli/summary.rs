@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::builder::diagnostics::{self, Category, Diagnostic};
+use crate::builder::profiler::ProfileEntry;
+use crate::builder::site::BuildOutcome;
+
+/// Number of rows printed in the profile table, like Jekyll
+const TABLE_ROWS: usize = 50;
+
+/// Print the render timings and problems of a build
+pub fn print_outcome(outcome: &BuildOutcome, source: &Path) {
+    print_profile(&outcome.profile);
+    print_diagnostics(&outcome.diagnostics, source);
+}
+
+/// Print the slowest templates as a table
+pub fn print_profile(entries: &[ProfileEntry]) {
+    if entries.is_empty() {
+        return;
+    }
+
+    let rows = &entries[..entries.len().min(TABLE_ROWS)];
+    let name_width = rows.iter().map(|entry| entry.name.len()).max().unwrap_or(0).max("Template".len());
+
+    println!();
+    println!("{:<8}  {:<name_width$}  {:>6}  {:>10}  {:>10}", "Kind", "Template", "Count", "Total (ms)", "Avg (ms)");
+    println!("{}", "-".repeat(8 + name_width + 6 + 10 + 10 + 8));
+    for entry in rows {
+        println!("{:<8}  {:<name_width$}  {:>6}  {:>10.3}  {:>10.3}",
+                 entry.kind.to_string(), entry.name, entry.count, entry.total_ms, entry.average_ms);
+    }
+    if entries.len() > rows.len() {
+        println!("... and {} more", entries.len() - rows.len());
+    }
+    println!();
+}
+
+/// Print the problems of a build, grouped by category and file
+pub fn print_diagnostics(problems: &[Diagnostic], source: &Path) {
+    if problems.is_empty() {
+        return;
+    }
+
+    let errors = diagnostics::error_count(problems);
+    let warnings = problems.len() - errors;
+    eprintln!();
+    eprintln!("Build finished with {} error{} and {} warning{}:",
+              errors, if errors == 1 { "" } else { "s" },
+              warnings, if warnings == 1 { "" } else { "s" });
+
+    let mut groups: BTreeMap<Category, BTreeMap<PathBuf, Vec<&Diagnostic>>> = BTreeMap::new();
+    for diagnostic in problems {
+        let file = diagnostic.file.strip_prefix(source).unwrap_or(&diagnostic.file).to_path_buf();
+        groups.entry(diagnostic.category).or_default().entry(file).or_default().push(diagnostic);
+    }
+
+    for (category, files) in groups {
+        let count: usize = files.values().map(|entries| entries.len()).sum();
+        eprintln!();
+        eprintln!("{} ({})", category, count);
+        for (file, entries) in files {
+            eprintln!("  {}", file.display());
+            for diagnostic in entries {
+                let position = match (diagnostic.line, diagnostic.column) {
+                    (Some(line), Some(column)) => format!("{}:{} ", line, column),
+                    (Some(line), None) => format!("{} ", line),
+                    _ => String::new(),
+                };
+                // Keep multi-line messages (e.g. Liquid parse errors) indented under their file
+                let message = diagnostic.message.trim_end().replace('\n', "\n      ");
+                eprintln!("    {}{}: {}", position, diagnostic.severity, message);
+            }
+        }
+    }
+    eprintln!();
+}
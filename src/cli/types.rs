@@ -69,6 +69,22 @@ pub enum Commands {
         #[arg(long, default_value_t = false)]
         unpublished: bool,
 
+        /// Publish posts with a future date
+        #[arg(long, default_value_t = false)]
+        future: bool,
+
+        /// Fail the build if any page has errors
+        #[arg(long, default_value_t = false)]
+        strict: bool,
+
+        /// Print render timings per template
+        #[arg(long, default_value_t = false)]
+        profile: bool,
+
+        /// Write a JSON manifest of every output file, relative to the destination
+        #[arg(long, value_name = "FILE")]
+        manifest: Option<String>,
+
         /// Silence output
         #[arg(short, long, default_value_t = false)]
         quiet: bool,
@@ -132,6 +148,10 @@ pub enum Commands {
         /// Render posts that were marked as unpublished
         #[arg(long, default_value_t = false)]
         unpublished: bool,
+
+        /// Publish posts with a future date
+        #[arg(long, default_value_t = false)]
+        future: bool,
         
         /// Source directory (defaults to ./)
         #[arg(short, long, value_name = "DIR")]
@@ -24,8 +24,8 @@ pub fn document_to_liquid(doc: &Document) -> liquid::model::Value {
     }
     
     if let Some(date) = &doc.date {
-        // Convert DateTime to string in RFC3339 format
-        obj.insert("date".into(), Value::scalar(date.to_rfc3339()));
+        // Convert DateTime to string in RFC3339 format, in the site's timezone
+        obj.insert("date".into(), Value::scalar(crate::utils::date::local(*date).to_rfc3339()));
     }
     
     // Path properties
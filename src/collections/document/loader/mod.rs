@@ -8,6 +8,7 @@ use walkdir::WalkDir;
 use crate::config::{Config, CollectionConfig};
 use crate::collections::document::model::{Collection, Document};
 use crate::collections::types::BoxResult;
+use crate::builder::diagnostics::{self, Category};
 
 pub use parsers::{parse_document, parse_post, parse_draft};
 
@@ -60,7 +61,9 @@ pub fn load_collections(config: &Config) -> BoxResult<HashMap<String, Collection
     }
     
     // Load posts separately since they have special handling
-    load_posts(&mut posts_collection, config, false, false)?;
+    let include_drafts = config.show_drafts.unwrap_or(false);
+    let include_unpublished = config.unpublished.unwrap_or(false);
+    load_posts(&mut posts_collection, config, include_drafts, include_unpublished)?;
     collections.insert("posts".to_string(), posts_collection);
     
     Ok(collections)
@@ -83,7 +86,15 @@ fn load_collection_documents(collection: &mut Collection, config: &Config) -> Bo
         if path.is_file() {
             let extension = path.extension().unwrap_or_default().to_string_lossy();
             if extension == "md" || extension == "markdown" {
-                if let Some(doc) = parse_document(path, &collection.directory, &collection.label)? {
+                let include_unpublished = config.unpublished.unwrap_or(false);
+                let parsed = parse_document(path, &collection.directory, &collection.label, include_unpublished);
+                if let Some(doc) = report_parse_error(path, parsed) {
+                    // Skip documents that should not be published yet
+                    if !doc.should_write(config) {
+                        debug!("Skipping future-dated document: {}", path.display());
+                        continue;
+                    }
+                    
                     // Generate URL for the document
                     let url = collection.generate_url(&doc, config);
                     
@@ -121,7 +132,13 @@ fn load_posts(
             if path.is_file() {
                 let extension = path.extension().unwrap_or_default().to_string_lossy();
                 if extension == "md" || extension == "markdown" {
-                    if let Some(doc) = parse_post(path, &posts_dir, include_unpublished)? {
+                    if let Some(doc) = report_parse_error(path, parse_post(path, &posts_dir, include_unpublished)) {
+                        // Skip posts that should not be published yet
+                        if !doc.should_write(config) {
+                            debug!("Skipping future-dated post: {}", path.display());
+                            continue;
+                        }
+                        
                         // Generate URL for the document
                         let url = collection.generate_url(&doc, config);
                         
@@ -137,7 +154,7 @@ fn load_posts(
     
     // Load drafts if requested
     if include_drafts {
-        let drafts_dir = config.source.join("_drafts");
+        let drafts_dir = config.source.join(&config.drafts_dir);
         if drafts_dir.exists() {
             // Walk the directory and find all markdown files
             for entry in WalkDir::new(&drafts_dir).follow_links(true) {
@@ -147,7 +164,7 @@ fn load_posts(
                 if path.is_file() {
                     let extension = path.extension().unwrap_or_default().to_string_lossy();
                     if extension == "md" || extension == "markdown" {
-                        if let Some(doc) = parse_draft(path, &drafts_dir, include_unpublished)? {
+                        if let Some(doc) = report_parse_error(path, parse_draft(path, &drafts_dir, include_unpublished)) {
                             // Generate URL for the document
                             let url = collection.generate_url(&doc, config);
                             
@@ -167,8 +184,64 @@ fn load_posts(
     Ok(())
 }
 
+/// Record a document that failed to load, so the rest of the site still builds
+fn report_parse_error(path: &Path, parsed: BoxResult<Option<Document>>) -> Option<Document> {
+    match parsed {
+        Ok(doc) => doc,
+        Err(e) => {
+            diagnostics::error(Category::FrontMatter, path, e.to_string());
+            None
+        }
+    }
+}
+
 // Helper function to load documents from a directory
 fn load_documents(dir: &Path, collection: &str, _config: &Config) -> BoxResult<Vec<Document>> {
     // ... existing implementation ...
     Ok(Vec::new()) // Replace with actual implementation
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::*;
+    use crate::test_support::TestSite;
+
+    /// Titles of the posts loaded with the given settings
+    fn titles(site: &TestSite, drafts: bool, unpublished: bool, future: bool) -> Vec<String> {
+        let config = Config {
+            show_drafts: Some(drafts),
+            unpublished: Some(unpublished),
+            future: Some(future),
+            ..site.config()
+        };
+        let collections = load_collections(&config).unwrap();
+        let mut titles: Vec<String> = collections["posts"].documents.iter()
+            .filter_map(|doc| doc.title())
+            .collect();
+        titles.sort();
+        titles
+    }
+
+    #[test]
+    fn test_load_posts() {
+        let site = TestSite::new("loader");
+        site.write("_posts/2024-01-01-post.md", "---\ntitle: Post\n---\nPost")
+            .write("_posts/2024-01-02-hidden.md", "---\ntitle: Hidden\npublished: false\n---\nHidden")
+            .write("_posts/2999-01-01-later.md", "---\ntitle: Later\n---\nLater")
+            .write("_drafts/draft.md", "---\ntitle: Draft\n---\nDraft")
+            .write("_drafts/hidden-draft.md", "---\ntitle: Hidden draft\npublished: false\n---\nHidden");
+        assert_eq!(titles(&site, false, false, false), ["Post"]);
+        assert_eq!(titles(&site, true, false, false), ["Draft", "Post"]);
+        assert_eq!(titles(&site, false, true, false), ["Hidden", "Post"]);
+        assert_eq!(titles(&site, false, false, true), ["Later", "Post"]);
+        assert_eq!(titles(&site, true, true, true), ["Draft", "Hidden", "Hidden draft", "Later", "Post"]);
+
+        // Drafts without a date are dated by their modification time
+        let config = Config { show_drafts: Some(true), ..site.config() };
+        let collections = load_collections(&config).unwrap();
+        let draft = collections["posts"].documents.iter().find(|doc| doc.title().as_deref() == Some("Draft")).unwrap();
+        let mtime = fs::metadata(site.path("_drafts/draft.md")).unwrap().modified().unwrap();
+        assert_eq!(draft.date.map(|date| date.timestamp()), Some(chrono::DateTime::<chrono::Utc>::from(mtime).timestamp()));
+        assert_eq!(draft.state, crate::collections::document::model::DocumentState::Draft);
+    }
+}
@@ -1,16 +1,17 @@
 use std::path::Path;
 use std::fs;
-use chrono::{DateTime, Utc, NaiveDate, TimeZone};
+use chrono::{DateTime, Utc, NaiveDate};
 use regex::Regex;
 use log::debug;
-use crate::collections::document::model::Document;
+use crate::collections::document::model::{Document, DocumentState};
 use crate::collections::types::BoxResult;
 
 /// Parse a document file for a regular collection
 pub fn parse_document(
     path: &Path,
     collection_dir: &Path,
-    collection_label: &str
+    collection_label: &str,
+    include_unpublished: bool
 ) -> BoxResult<Option<Document>> {
     debug!("Parsing document: {}", path.display());
     
@@ -20,10 +21,12 @@ pub fn parse_document(
     // Extract front matter and content
     let (front_matter, content) = crate::front_matter::extract_front_matter(&content)?;
     
-    // Skip if draft and published is false
+    // Skip if published is false and we're not including unpublished
     if let Some(false) = front_matter.published {
-        debug!("Skipping unpublished document: {}", path.display());
-        return Ok(None);
+        if !include_unpublished {
+            debug!("Skipping unpublished document: {}", path.display());
+            return Ok(None);
+        }
     }
     
     // Create document ID
@@ -70,7 +73,7 @@ pub fn parse_post(
     // Try to extract date from filename if not in front matter
     if front_matter.date.is_none() {
         if let Some(dt) = extract_date_from_filename(path) {
-            front_matter.date = Some(dt.to_string());
+            front_matter.date = Some(dt.format("%Y-%m-%d %H:%M:%S %z").to_string());
         }
     }
     
@@ -115,9 +118,14 @@ pub fn parse_draft(
         }
     }
     
-    // Drafts always get the current date
-    let now = Utc::now();
-    front_matter.date = Some(now.to_string());
+    // Drafts without a date in front matter are dated by their modification time
+    if front_matter.date.is_none() {
+        let mtime = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        front_matter.date = Some(mtime.format("%Y-%m-%d %H:%M:%S %z").to_string());
+    }
     
     // Create document ID
     let id = if let Some(rel_path) = path.strip_prefix(drafts_dir).ok() {
@@ -126,7 +134,7 @@ pub fn parse_draft(
         path.file_name().unwrap().to_string_lossy().to_string()
     };
     
-    let document = Document::new(
+    let mut document = Document::new(
         id,
         path.to_path_buf(),
         path.strip_prefix(drafts_dir)?.to_path_buf(),
@@ -134,6 +142,7 @@ pub fn parse_draft(
         content.to_string(),
         front_matter,
     );
+    document.state = DocumentState::Draft;
     
     Ok(Some(document))
 }
@@ -149,9 +158,8 @@ pub fn extract_date_from_filename(path: &Path) -> Option<DateTime<Utc>> {
         let day: u32 = caps.get(3)?.as_str().parse().ok()?;
         
         let naive_date = NaiveDate::from_ymd_opt(year, month, day)?;
-        let datetime = naive_date.and_hms_opt(0, 0, 0)?;
         
-        return Some(Utc.from_utc_datetime(&datetime));
+        return Some(crate::utils::date::local_midnight(naive_date));
     }
     
     None
@@ -1,11 +1,12 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use chrono::{DateTime, Utc, NaiveDateTime, NaiveDate, Datelike};
+use chrono::{DateTime, Utc, Datelike};
 use serde::{Serialize, Deserialize};
 
 use crate::front_matter::FrontMatter;
 use crate::config::{Config, CollectionConfig};
 use crate::config::permalink::PermalinkStyle;
+use crate::utils::date::local;
 
 /// A collection of documents
 #[derive(Debug, Clone)]
@@ -86,6 +87,9 @@ pub struct Document {
     /// Rendered content after processing
     pub rendered_content: Option<String>,
     
+    /// Excerpt after rendering, like the content
+    pub rendered_excerpt: Option<String>,
+    
     /// Front matter data
     pub front_matter: FrontMatter,
     
@@ -172,6 +176,7 @@ impl Document {
             date: parse_date_string(date),
             content,
             rendered_content: None,
+            rendered_excerpt: None,
             front_matter,
             excerpt: None,
             state,
@@ -185,8 +190,18 @@ impl Document {
         }
     }
     
+    /// Check if this document is dated in the future
+    pub fn is_future(&self) -> bool {
+        self.date.is_some_and(|date| date > Utc::now())
+    }
+    
     /// Check if this document should be written to the output
     pub fn should_write(&self, config: &Config) -> bool {
+        // Future-dated documents are only published when requested
+        if self.is_future() && !config.future.unwrap_or(false) {
+            return false;
+        }
+        
         match self.state {
             DocumentState::Published => true,
             DocumentState::Draft => config.show_drafts.unwrap_or(false),
@@ -366,7 +381,7 @@ impl Collection {
         replacements.insert(":output_ext".to_string(), String::from(".html"));
         
         // Date-based replacements
-        if let Some(date) = &doc.date {
+        if let Some(date) = doc.date.map(local) {
             replacements.insert(":year".to_string(), date.format("%Y").to_string());
             replacements.insert(":month".to_string(), date.format("%m").to_string());
             replacements.insert(":day".to_string(), date.format("%d").to_string());
@@ -433,26 +448,57 @@ impl Collection {
 
 /// Parse a date string into a DateTime<Utc>
 fn parse_date_string(date: Option<String>) -> Option<DateTime<Utc>> {
-    if let Some(date_str) = date {
-        // Try to parse the date using various formats
-        if let Ok(parsed) = chrono::DateTime::parse_from_rfc3339(&date_str) {
-            return Some(parsed.with_timezone(&Utc));
-        }
+    date.and_then(|date| crate::utils::date::parse_date(&date))
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Try Jekyll date format with timezone (YYYY-MM-DD HH:MM:SS +ZZZZ)
-        if let Ok(parsed) = chrono::DateTime::parse_from_str(&date_str, "%Y-%m-%d %H:%M:%S %z") {
-            return Some(parsed.with_timezone(&Utc));
-        }
+    fn document(front_matter: &str, date: DateTime<Utc>) -> Document {
+        let front_matter: FrontMatter = serde_yaml::from_str(front_matter).unwrap();
+        let mut doc = Document::new("post".to_string(), PathBuf::from("post.md"), PathBuf::from("post.md"),
+                                    "posts".to_string(), String::new(), front_matter);
+        doc.date = Some(date);
+        doc
+    }
 
-        // Try other date formats without timezone
-        if let Ok(parsed) = NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%d %H:%M:%S") {
-            return Some(parsed.and_utc());
-        }
+    #[test]
+    fn test_should_write() {
+        let past = Utc::now() - chrono::Duration::days(1);
+        let future = Utc::now() + chrono::Duration::days(1);
+        let config = |drafts: bool, unpublished: bool, future: bool| Config {
+            show_drafts: Some(drafts),
+            unpublished: Some(unpublished),
+            future: Some(future),
+            ..Config::default()
+        };
 
-        if let Ok(parsed) = NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
-            return Some(parsed.and_hms_opt(0, 0, 0).unwrap().and_utc());
-        }
-    }
+        let published = document("title: Post", past);
+        assert!(published.should_write(&config(false, false, false)));
+
+        let unpublished = document("published: false", past);
+        assert_eq!(unpublished.state, DocumentState::Unpublished);
+        assert!(!unpublished.should_write(&config(true, false, true)));
+        assert!(unpublished.should_write(&config(false, true, false)));
 
-    None
-} 
\ No newline at end of file
+        let mut draft = document("title: Draft", past);
+        draft.state = DocumentState::Draft;
+        assert!(!draft.should_write(&config(false, true, true)));
+        assert!(draft.should_write(&config(true, false, false)));
+
+        // Future documents need `future`, on top of what their state needs
+        let scheduled = document("title: Later", future);
+        assert!(scheduled.is_future());
+        assert!(!scheduled.should_write(&config(true, true, false)));
+        assert!(scheduled.should_write(&config(false, false, true)));
+        let mut scheduled_draft = document("title: Later", future);
+        scheduled_draft.state = DocumentState::Draft;
+        assert!(!scheduled_draft.should_write(&config(false, false, true)));
+        assert!(scheduled_draft.should_write(&config(true, false, true)));
+
+        // A document dated now is no longer in the future
+        let now = document("title: Now", Utc::now());
+        assert!(!now.is_future());
+        assert!(now.should_write(&config(false, false, false)));
+    }
+}
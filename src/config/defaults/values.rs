@@ -50,6 +50,11 @@ pub fn default_base_url() -> String {
     "".to_string()
 }
 
+/// Default paginate path
+pub fn default_paginate_path() -> String {
+    "/page:num".to_string()
+}
+
 /// Default excluded files
 pub fn default_exclude() -> Vec<String> {
     vec![
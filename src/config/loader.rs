@@ -123,11 +123,13 @@ fn parse_yaml_config(content: &str, path: &Path) -> BoxResult<Config> {
                      "title", "description", "repository", "safe_mode", "markdown_ext",
                      "markdown_extensions", "markdown_config", "keep_files", "posts_dir",
                      "drafts_dir", "url", "highlighter", "permalink", "site_data",
-                     "incremental", "build_report", "show_drafts", "future", "unpublished",
+                     "incremental", "build_report", "profile", "show_drafts", "future", "unpublished",
                      "limit_posts", "lsi", "encoding", "timezone", "excerpt_separator",
-                     "paginate", "paginate_path", "kramdown", "liquid", "jekyll", "server",
+                     "paginate", "paginate_path", "pagination", "archives", "jekyll-archives",
+                     "kramdown", "liquid", "jekyll", "server",
                      "strict_front_matter", "category_dir", "tag_dir", "liquid_config",
-                     "sass", "webrick", "quiet", "verbose", "trace", "strict_variables"].contains(&key_str.as_str()) {
+                     "sass", "webrick", "quiet", "verbose", "trace", "strict_variables", "strict",
+                     "manifest", "sitemap", "redirect_from", "relative_links"].contains(&key_str.as_str()) {
                     debug!("Adding key '{}' to top_level_keys", key_str);
                     top_level_keys.insert(key_str.clone(), value.clone());
                 } else {
@@ -293,4 +295,89 @@ fn merge_configs(target: &mut Config, source: &Config) {
     if !source.highlighter.is_empty() && source.highlighter != "rouge" {
         target.highlighter = source.highlighter.clone();
     }
+    
+    // Optional settings are merged when the source sets them
+    if source.incremental.is_some() {
+        target.incremental = source.incremental;
+    }
+    
+    if source.profile.is_some() {
+        target.profile = source.profile;
+    }
+    
+    if source.show_drafts.is_some() {
+        target.show_drafts = source.show_drafts;
+    }
+    
+    if source.future.is_some() {
+        target.future = source.future;
+    }
+    
+    if source.unpublished.is_some() {
+        target.unpublished = source.unpublished;
+    }
+    
+    if source.strict.is_some() {
+        target.strict = source.strict;
+    }
+    
+    if source.manifest.is_some() {
+        target.manifest = source.manifest.clone();
+    }
+    
+    if source.lsi.is_some() {
+        target.lsi = source.lsi;
+    }
+    
+    if source.timezone.is_some() {
+        target.timezone = source.timezone.clone();
+    }
+    
+    if source.liquid.is_some() {
+        target.liquid = source.liquid.clone();
+    }
+    
+    if source.kramdown.is_some() {
+        target.kramdown = source.kramdown.clone();
+    }
+    
+    if source.markdown_extensions.is_some() {
+        target.markdown_extensions = source.markdown_extensions.clone();
+    }
+    
+    if source.markdown_config != crate::config::MarkdownExtensions::default() {
+        target.markdown_config = source.markdown_config.clone();
+    }
+    
+    if source.paginate.is_some() {
+        target.paginate = source.paginate;
+    }
+    
+    if source.paginate_path != crate::config::defaults::default_paginate_path() {
+        target.paginate_path = source.paginate_path.clone();
+    }
+    
+    if source.pagination.is_some() {
+        target.pagination = source.pagination.clone();
+    }
+    
+    if source.archives.is_some() {
+        target.archives = source.archives.clone();
+    }
+    
+    if source.feed.is_some() {
+        target.feed = source.feed.clone();
+    }
+    
+    if source.sitemap.is_some() {
+        target.sitemap = source.sitemap.clone();
+    }
+    
+    if source.redirect_from.is_some() {
+        target.redirect_from = source.redirect_from.clone();
+    }
+    
+    if source.relative_links.is_some() {
+        target.relative_links = source.relative_links.clone();
+    }
 } 
\ No newline at end of file
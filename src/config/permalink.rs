@@ -109,6 +109,8 @@ pub fn process_permalink(
 
 /// Add date-based placeholders to the map
 fn add_date_placeholders(placeholders: &mut HashMap<String, String>, date: DateTime<Utc>) {
+    // Posts are filed under their date in the site's timezone
+    let date = crate::utils::date::local(date);
     placeholders.insert("year".to_string(), date.year().to_string());
     placeholders.insert("month".to_string(), format!("{:02}", date.month()));
     placeholders.insert("day".to_string(), format!("{:02}", date.day()));
@@ -125,19 +127,85 @@ fn add_date_placeholders(placeholders: &mut HashMap<String, String>, date: DateT
     placeholders.insert("i_day".to_string(), date.day().to_string());
 }
 
+/// Characters kept by the `pretty` slugify mode besides letters and numbers
+const PRETTY_CHARS: &str = "._~!$&'()+,;=@";
+
+/// How `slugify` replaces characters, like Jekyll's slugify modes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SlugifyMode {
+    /// Only whitespace is replaced
+    Raw,
+    /// Everything but letters and numbers is replaced
+    #[default]
+    Default,
+    /// Like default, but URL-safe punctuation such as `._~!$&'()+,;=@` is kept
+    Pretty,
+    /// Everything but ASCII letters and numbers is replaced
+    Ascii,
+    /// Like default, after accents are removed from Latin characters
+    Latin,
+}
+
+impl SlugifyMode {
+    /// Get the mode with the given name, or `None` for unknown names
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "raw" => Some(SlugifyMode::Raw),
+            "default" => Some(SlugifyMode::Default),
+            "pretty" => Some(SlugifyMode::Pretty),
+            "ascii" => Some(SlugifyMode::Ascii),
+            "latin" => Some(SlugifyMode::Latin),
+            _ => None,
+        }
+    }
+
+    fn keeps(self, c: char) -> bool {
+        match self {
+            SlugifyMode::Raw => !c.is_whitespace(),
+            SlugifyMode::Default | SlugifyMode::Latin => c.is_alphanumeric(),
+            SlugifyMode::Pretty => c.is_alphanumeric() || PRETTY_CHARS.contains(c),
+            SlugifyMode::Ascii => c.is_ascii_alphanumeric(),
+        }
+    }
+}
+
 /// Convert a string to a URL-friendly slug
-fn slugify(input: &str) -> String {
-    let mut slug = input
-        .to_lowercase()
-        .replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "-");
-    
-    // Replace multiple consecutive dashes with a single dash
-    while slug.contains("--") {
-        slug = slug.replace("--", "-");
+pub fn slugify(input: &str) -> String {
+    slugify_with_mode(input, SlugifyMode::Default)
+}
+
+/// Convert a string to a slug like Jekyll's `slugify` with the given mode
+///
+/// Each sequence of characters the mode does not keep becomes a single
+/// hyphen, a leading and trailing hyphen is removed and the result is
+/// lowercased.
+pub fn slugify_with_mode(input: &str, mode: SlugifyMode) -> String {
+    // Like Jekyll, only Latin characters are transliterated
+    let input: String = if mode == SlugifyMode::Latin {
+        input.chars()
+            .map(|c| match c {
+                '\u{80}'..='\u{24F}' | '\u{1E00}'..='\u{1EFF}' => deunicode::deunicode_char(c).unwrap_or("?").to_string(),
+                c if c.is_ascii() => c.to_string(),
+                _ => "?".to_string(),
+            })
+            .collect()
+    } else {
+        input.to_string()
+    };
+
+    let mut slug = String::with_capacity(input.len());
+    let mut replacing = false;
+    for c in input.chars() {
+        if mode.keeps(c) {
+            slug.push(c);
+            replacing = false;
+        } else if !replacing {
+            slug.push('-');
+            replacing = true;
+        }
     }
-    
-    // Remove leading and trailing dashes
-    slug = slug.trim_start_matches('-').trim_end_matches('-').to_string();
-    
-    slug
-} 
\ No newline at end of file
+
+    let slug = slug.strip_prefix('-').unwrap_or(&slug);
+    let slug = slug.strip_suffix('-').unwrap_or(slug);
+    slug.to_lowercase()
+}
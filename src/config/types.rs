@@ -10,6 +10,8 @@ use glob_match::glob_match;
 
 use crate::config::defaults;
 use crate::builder::processor::yaml_to_liquid;
+use crate::front_matter::types::Pagination;
+use crate::front_matter::types::deserializers::deserialize_string_or_seq;
 
 /// Site data configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -57,6 +59,311 @@ impl Default for LiquidConfig {
     }
 }
 
+/// Archive page generation configuration (jekyll-archives style)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ArchivesConfig {
+    /// Archive types to generate (year, month, day, categories, tags) or "all"
+    #[serde(default, deserialize_with = "deserialize_string_or_seq")]
+    pub enabled: Option<Vec<String>>,
+    
+    /// Default layout for archive pages
+    #[serde(default = "default_archive_layout")]
+    pub layout: String,
+    
+    /// Layouts for specific archive types (year, month, day, category, tag)
+    #[serde(default)]
+    pub layouts: HashMap<String, String>,
+    
+    /// Permalink patterns for specific archive types
+    #[serde(default)]
+    pub permalinks: HashMap<String, String>,
+}
+
+impl ArchivesConfig {
+    /// Check if an archive type (year, month, day, category, tag) is enabled
+    pub fn is_enabled(&self, archive_type: &str) -> bool {
+        let plural = match archive_type {
+            "category" => "categories",
+            "tag" => "tags",
+            other => other,
+        };
+        
+        self.enabled.as_ref().is_some_and(|enabled| {
+            enabled.iter().any(|name| name == "all" || name == archive_type || name == plural)
+        })
+    }
+    
+    /// Get the layout for an archive type
+    pub fn layout_for(&self, archive_type: &str) -> &str {
+        self.layouts.get(archive_type).unwrap_or(&self.layout)
+    }
+    
+    /// Get the permalink pattern for an archive type
+    pub fn permalink_for(&self, archive_type: &str) -> String {
+        if let Some(permalink) = self.permalinks.get(archive_type) {
+            return permalink.clone();
+        }
+        
+        match archive_type {
+            "year" => "/:year/",
+            "month" => "/:year/:month/",
+            "day" => "/:year/:month/:day/",
+            "category" => "/category/:name/",
+            _ => "/tag/:name/",
+        }.to_string()
+    }
+}
+
+/// Feed generation configuration (jekyll-feed style)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedConfig {
+    /// Path of the posts feed
+    #[serde(default = "default_feed_path")]
+    pub path: String,
+    
+    /// Maximum number of entries in a feed
+    #[serde(default = "default_feed_posts_limit")]
+    pub posts_limit: usize,
+    
+    /// Whether entries only have a summary instead of the full content
+    #[serde(default)]
+    pub excerpt_only: bool,
+    
+    /// Post categories that get a feed of their own
+    #[serde(default, deserialize_with = "deserialize_string_or_seq")]
+    pub categories: Option<Vec<String>>,
+    
+    /// Per-tag feeds, either `true` or options
+    #[serde(default)]
+    pub tags: Option<FeedTags>,
+    
+    /// Collections that get a feed of their own
+    #[serde(default)]
+    pub collections: Option<FeedCollections>,
+    
+    /// Formats to write: atom, rss and json
+    #[serde(default, deserialize_with = "deserialize_string_or_seq")]
+    pub formats: Option<Vec<String>>,
+    
+    /// Icon of the feed
+    #[serde(default)]
+    pub icon: Option<String>,
+    
+    /// Logo of the feed
+    #[serde(default)]
+    pub logo: Option<String>,
+}
+
+/// Per-tag feeds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FeedTags {
+    /// Feeds for every tag, or none
+    Enabled(bool),
+    /// Feeds for some tags
+    Options(FeedTagOptions),
+}
+
+/// Which tags get a feed and where they go
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FeedTagOptions {
+    /// Only these tags get a feed
+    #[serde(default, deserialize_with = "deserialize_string_or_seq")]
+    pub only: Option<Vec<String>>,
+    
+    /// Tags that get no feed
+    #[serde(default, deserialize_with = "deserialize_string_or_seq")]
+    pub except: Option<Vec<String>>,
+    
+    /// Directory of the tag feeds
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// Collections with a feed, as a list of names or per-collection options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FeedCollections {
+    /// Collection names, with default paths
+    Names(Vec<String>),
+    /// Options for each collection
+    Options(HashMap<String, FeedCollectionConfig>),
+}
+
+/// Feed options for a collection
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FeedCollectionConfig {
+    /// Path of the collection's feed
+    #[serde(default)]
+    pub path: Option<String>,
+    
+    /// Categories of the collection that get a feed of their own
+    #[serde(default, deserialize_with = "deserialize_string_or_seq")]
+    pub categories: Option<Vec<String>>,
+}
+
+impl Default for FeedConfig {
+    fn default() -> Self {
+        FeedConfig {
+            path: default_feed_path(),
+            posts_limit: default_feed_posts_limit(),
+            excerpt_only: false,
+            categories: None,
+            tags: None,
+            collections: None,
+            formats: None,
+            icon: None,
+            logo: None,
+        }
+    }
+}
+
+/// Feed formats, with their MIME type and file extension
+const FEED_FORMATS: [(&str, &str, &str); 3] = [
+    ("atom", "application/atom+xml", "xml"),
+    ("rss", "application/rss+xml", "rss"),
+    ("json", "application/feed+json", "json"),
+];
+
+impl FeedConfig {
+    /// Check if a feed format (atom, rss, json) is written
+    pub fn has_format(&self, format: &str) -> bool {
+        match &self.formats {
+            Some(formats) => formats.iter().any(|name| name.eq_ignore_ascii_case(format)),
+            None => format == "atom",
+        }
+    }
+    
+    /// The paths of a feed in each format written, as (format, MIME type, path)
+    ///
+    /// `path` is the path of the Atom feed, other formats swap its extension.
+    pub fn format_paths(&self, path: &str) -> Vec<(&'static str, &'static str, String)> {
+        let stem = match path.rfind('.') {
+            Some(dot) if !path[dot..].contains('/') => &path[..dot],
+            _ => path,
+        };
+        FEED_FORMATS.iter()
+            .filter(|(format, _, _)| self.has_format(format))
+            .map(|&(format, mime_type, extension)| {
+                let path = if format == "atom" { path.to_string() } else { format!("{}.{}", stem, extension) };
+                (format, mime_type, path)
+            })
+            .collect()
+    }
+    
+    /// Path of the posts feed, which `collections.posts.path` can override
+    pub fn posts_path(&self) -> String {
+        self.collections().into_iter()
+            .find(|(name, _)| name == "posts")
+            .and_then(|(_, options)| options.path)
+            .unwrap_or_else(|| self.path.clone())
+    }
+    
+    /// Check if a tag gets a feed of its own
+    pub fn has_tag_feed(&self, tag: &str) -> bool {
+        match &self.tags {
+            Some(FeedTags::Enabled(enabled)) => *enabled,
+            Some(FeedTags::Options(options)) => {
+                options.only.as_ref().is_none_or(|only| only.iter().any(|name| name == tag))
+                    && !options.except.as_ref().is_some_and(|except| except.iter().any(|name| name == tag))
+            },
+            None => false,
+        }
+    }
+    
+    /// Directory of the tag feeds
+    pub fn tags_path(&self) -> &str {
+        match &self.tags {
+            Some(FeedTags::Options(FeedTagOptions { path: Some(path), .. })) => path,
+            _ => "/feed/by_tag/",
+        }
+    }
+    
+    /// Collections with a feed of their own and their options, sorted by name
+    pub fn collections(&self) -> Vec<(String, FeedCollectionConfig)> {
+        let mut collections: Vec<(String, FeedCollectionConfig)> = match &self.collections {
+            Some(FeedCollections::Names(names)) => names.iter()
+                .map(|name| (name.clone(), FeedCollectionConfig::default()))
+                .collect(),
+            Some(FeedCollections::Options(options)) => options.iter()
+                .map(|(name, options)| (name.clone(), options.clone()))
+                .collect(),
+            None => Vec::new(),
+        };
+        collections.sort_by(|a, b| a.0.cmp(&b.0));
+        collections
+    }
+}
+
+/// Sitemap generation configuration (jekyll-sitemap style)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SitemapConfig {
+    /// Extensions of the static files listed, besides pages and documents
+    #[serde(default = "default_sitemap_extensions", deserialize_with = "deserialize_extensions")]
+    pub extensions: Vec<String>,
+    
+    /// Whether to write a robots.txt pointing at the sitemap
+    #[serde(default = "default_true")]
+    pub robots: bool,
+}
+
+impl Default for SitemapConfig {
+    fn default() -> Self {
+        SitemapConfig {
+            extensions: default_sitemap_extensions(),
+            robots: true,
+        }
+    }
+}
+
+impl SitemapConfig {
+    /// Check if a static file is listed in the sitemap
+    pub fn lists_static_file(&self, path: &Path) -> bool {
+        path.extension().is_some_and(|extension| {
+            let extension = extension.to_string_lossy();
+            self.extensions.iter().any(|listed| listed.eq_ignore_ascii_case(&extension))
+        })
+    }
+}
+
+/// Redirect configuration (jekyll-redirect-from style)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedirectsConfig {
+    /// Whether to write redirects.json, mapping old paths to their targets
+    #[serde(default = "default_true")]
+    pub json: bool,
+    
+    /// Rules for hosts to issue real redirects: `netlify`, `nginx` or `apache`
+    #[serde(default)]
+    pub formats: Vec<String>,
+}
+
+impl Default for RedirectsConfig {
+    fn default() -> Self {
+        RedirectsConfig {
+            json: true,
+            formats: Vec::new(),
+        }
+    }
+}
+
+/// Relative link configuration (jekyll-relative-links style)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelativeLinksConfig {
+    /// Whether links to Markdown sources are rewritten to their URLs
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// Deserialize file extensions, with or without their leading dot
+fn deserialize_extensions<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let extensions = deserialize_string_or_seq(deserializer)?.unwrap_or_default();
+    Ok(extensions.into_iter().map(|extension| extension.trim_start_matches('.').to_string()).collect())
+}
+
 /// Kramdown markdown engine configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KramdownConfig {
@@ -113,8 +420,15 @@ impl Default for KramdownConfig {
     }
 }
 
+impl KramdownConfig {
+    /// Whether the input is kramdown's GFM parser, which has its own heading IDs
+    pub fn is_gfm(&self) -> bool {
+        self.input.eq_ignore_ascii_case("GFM")
+    }
+}
+
 /// Markdown extensions configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MarkdownExtensions {
     /// Whether to enable math support
     #[serde(default)]
@@ -133,7 +447,7 @@ pub struct MarkdownExtensions {
     pub toc: bool,
     
     /// Whether to enable footnotes
-    #[serde(default)]
+    #[serde(default = "default_true")]
     pub footnotes: bool,
     
     /// Whether to enable task lists
@@ -141,6 +455,19 @@ pub struct MarkdownExtensions {
     pub task_lists: bool,
 }
 
+impl Default for MarkdownExtensions {
+    fn default() -> Self {
+        MarkdownExtensions {
+            math: false,
+            diagrams: false,
+            typographic: true,
+            toc: false,
+            footnotes: true,
+            task_lists: true,
+        }
+    }
+}
+
 /// Site configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -264,6 +591,10 @@ pub struct Config {
     #[serde(default)]
     pub build_report: Option<bool>,
     
+    /// Whether to collect and print render timings per template
+    #[serde(default)]
+    pub profile: Option<bool>,
+    
     /// Whether to show drafts
     #[serde(default)]
     pub show_drafts: Option<bool>,
@@ -301,9 +632,33 @@ pub struct Config {
     pub paginate: Option<usize>,
     
     /// Paginate path
-    #[serde(default = "default_paginate_path")]
+    #[serde(default = "defaults::default_paginate_path")]
     pub paginate_path: String,
     
+    /// Site-wide pagination defaults (jekyll-paginate-v2 style)
+    #[serde(default)]
+    pub pagination: Option<Pagination>,
+    
+    /// Archive pages for tags, categories and dates
+    #[serde(default, alias = "jekyll-archives")]
+    pub archives: Option<ArchivesConfig>,
+    
+    /// Atom, RSS and JSON feeds of posts and collections
+    #[serde(default)]
+    pub feed: Option<FeedConfig>,
+    
+    /// sitemap.xml and robots.txt
+    #[serde(default)]
+    pub sitemap: Option<SitemapConfig>,
+    
+    /// Redirect pages and rules for `redirect_from` and `redirect_to`
+    #[serde(default)]
+    pub redirect_from: Option<RedirectsConfig>,
+    
+    /// Rewriting of relative links between sources
+    #[serde(default)]
+    pub relative_links: Option<RelativeLinksConfig>,
+    
     /// Whether to be quiet in output
     #[serde(default)]
     pub quiet: Option<bool>,
@@ -312,6 +667,14 @@ pub struct Config {
     #[serde(default)]
     pub verbose: Option<bool>,
     
+    /// Whether to fail the build when any page has errors
+    #[serde(default)]
+    pub strict: Option<bool>,
+    
+    /// File to write the build manifest to, relative to the destination directory
+    #[serde(default)]
+    pub manifest: Option<String>,
+    
     /// Whether to do strict front matter parsing
     #[serde(default)]
     pub strict_front_matter: Option<bool>,
@@ -438,11 +801,6 @@ fn default_excerpt_separator() -> String {
     "\n\n".to_string()
 }
 
-/// Default paginate path
-fn default_paginate_path() -> String {
-    "/page:num".to_string()
-}
-
 /// Default keep files
 fn default_keep_files() -> Vec<String> {
     vec![".git".to_string(), ".svn".to_string()]
@@ -453,7 +811,24 @@ fn default_include() -> Option<Vec<String>> {
     Some(vec![".htaccess".to_string()])
 }
 
+/// Default layout for archive pages
+fn default_archive_layout() -> String {
+    "archive".to_string()
+}
+
 /// Default true boolean
+fn default_feed_path() -> String {
+    "/feed.xml".to_string()
+}
+
+fn default_feed_posts_limit() -> usize {
+    10
+}
+
+fn default_sitemap_extensions() -> Vec<String> {
+    vec!["pdf".to_string()]
+}
+
 fn default_true() -> bool {
     true
 }
@@ -528,6 +903,7 @@ impl Default for Config {
             site_data: SiteData::default(),
             incremental: None,
             build_report: None,
+            profile: None,
             show_drafts: None,
             future: None,
             unpublished: None,
@@ -537,9 +913,17 @@ impl Default for Config {
             timezone: None,
             excerpt_separator: default_excerpt_separator(),
             paginate: None,
-            paginate_path: default_paginate_path(),
+            paginate_path: defaults::default_paginate_path(),
+            pagination: None,
+            archives: None,
+            feed: None,
+            sitemap: None,
+            redirect_from: None,
+            relative_links: None,
             quiet: None,
             verbose: None,
+            strict: None,
+            manifest: None,
             strict_front_matter: None,
             kramdown: None,
             liquid: None,
@@ -633,6 +1017,76 @@ impl Config {
         obj
     }
     
+    /// Whether a markdown feature is on in `markdown_config` or listed in `markdown_extensions`
+    pub fn markdown_extension(&self, name: &str) -> bool {
+        let listed = self.markdown_extensions.as_ref()
+            .is_some_and(|names| names.iter().any(|listed| listed == name));
+        let enabled = match name {
+            "math" => self.markdown_config.math,
+            "diagrams" => self.markdown_config.diagrams,
+            "typographic" => self.markdown_config.typographic,
+            "toc" => self.markdown_config.toc,
+            "footnotes" => self.markdown_config.footnotes,
+            "task_lists" => self.markdown_config.task_lists,
+            _ => false,
+        };
+        listed || enabled
+    }
+
+    /// Feed settings, if feeds are generated
+    ///
+    /// Feeds are generated when there is a `feed` block, or when
+    /// `jekyll-feed` is among the plugins, which it is by default.
+    pub fn feed_config(&self) -> Option<FeedConfig> {
+        if self.feed.is_some() {
+            return self.feed.clone();
+        }
+        
+        let has_plugin = self.plugins.is_empty() || self.plugins.iter().any(|plugin| plugin == "jekyll-feed");
+        has_plugin.then(FeedConfig::default)
+    }
+    
+    /// Sitemap settings, if a sitemap is generated
+    ///
+    /// Like feeds, the sitemap is generated with a `sitemap` block or the
+    /// `jekyll-sitemap` plugin, which is among the default plugins.
+    pub fn sitemap_config(&self) -> Option<SitemapConfig> {
+        if self.sitemap.is_some() {
+            return self.sitemap.clone();
+        }
+        
+        let has_plugin = self.plugins.is_empty() || self.plugins.iter().any(|plugin| plugin == "jekyll-sitemap");
+        has_plugin.then(SitemapConfig::default)
+    }
+    
+    /// Whether relative links between sources are rewritten
+    ///
+    /// Like feeds, this follows a `relative_links` block or the
+    /// `jekyll-relative-links` plugin, and is on by default.
+    pub fn relative_links_enabled(&self) -> bool {
+        match &self.relative_links {
+            Some(relative_links) => relative_links.enabled,
+            None => self.plugins.is_empty() || self.plugins.iter().any(|plugin| plugin == "jekyll-relative-links"),
+        }
+    }
+    
+    /// Turn a path of the site into an absolute URL with `url` and `baseurl`
+    pub fn absolute_url(&self, path: &str) -> String {
+        if path.contains("://") {
+            return path.to_string();
+        }
+        
+        let mut url = self.url.as_deref().unwrap_or("").trim_end_matches('/').to_string();
+        let base_url = self.base_url.trim_matches('/');
+        if !base_url.is_empty() {
+            url.push('/');
+            url.push_str(base_url);
+        }
+        url.push('/');
+        url.push_str(path.trim_start_matches('/'));
+        url
+    }
+
     /// Check if a file should be excluded based on exclude/include patterns
     pub fn is_excluded(&self, path: &Path) -> bool {
         let rel_path_str = path.to_string_lossy();
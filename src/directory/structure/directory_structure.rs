@@ -37,11 +37,11 @@ impl DirectoryStructure {
         let _destination = &config.destination;
 
         // Create structure with paths relative to source
-        let _layouts_dir = source.join(&config.layouts_dir);
-        let _includes_dir = source.join(&config.includes_dir);
+        let layouts_dir = source.join(&config.layouts_dir);
+        let includes_dir = source.join(&config.includes_dir);
         let posts_dir = source.join(&config.posts_dir);
         let drafts_dir = source.join(&config.drafts_dir);
-        let _data_dir = source.join(&config.data_dir);
+        let data_dir = source.join(&config.data_dir);
         
         // Default directory names for sass and plugins
         let sass_dir = source.join("_sass");
@@ -56,9 +56,9 @@ impl DirectoryStructure {
         DirectoryStructure {
             source: config.source.clone(),
             destination: config.destination.clone(),
-            layouts_dir: config.layouts_dir.clone(),
-            includes_dir: config.includes_dir.clone(),
-            data_dir: config.data_dir.clone(),
+            layouts_dir,
+            includes_dir,
+            data_dir,
             posts_dir: posts_dir,
             drafts_dir: drafts_dir,
             sass_dir,
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::error::Error;
 use serde::{Deserialize, Serialize};
 use serde_yaml;
-use chrono::{DateTime, Utc, TimeZone};
+use chrono::{DateTime, Utc};
 use liquid::Object;
 use liquid::model::Value;
 use super::deserializers::deserialize_string_or_seq;
@@ -10,15 +10,18 @@ use super::deserializers::deserialize_string_or_seq;
 type BoxResult<T> = Result<T, Box<dyn Error>>;
 
 /// Pagination configuration in front matter
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+///
+/// Accepts both the rustyll field names and the jekyll-paginate-v2 ones
+/// (`permalink`, `sort_field`, `sort_reverse`). Settings which are not given
+/// are `None`, so site defaults can fill them in before the built-in ones.
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Pagination {
     /// Enable pagination
     #[serde(default)]
     pub enabled: bool,
     
-    /// Number of items per page
-    #[serde(default = "default_per_page")]
-    pub per_page: usize,
+    /// Number of items per page (default: 10)
+    pub per_page: Option<usize>,
     
     /// Current page number (1-indexed)
     #[serde(default = "default_page")]
@@ -32,20 +35,25 @@ pub struct Pagination {
     #[serde(skip_deserializing)]
     pub total_items: usize,
     
-    /// Path format for pagination pages
+    /// Path format for pagination pages, relative to the paginated page
+    #[serde(alias = "permalink")]
     pub path: Option<String>,
     
-    /// Sort field for paginated items
-    #[serde(default = "default_sort")]
-    pub sort: String,
+    /// Title format for pagination pages (e.g. ":title - page :num")
+    pub title: Option<String>,
+    
+    /// Sort field for paginated items (default: date)
+    #[serde(alias = "sort_field")]
+    pub sort: Option<String>,
+    
+    /// Sort direction, asc or desc (default: desc)
+    pub direction: Option<String>,
     
-    /// Sort direction (asc or desc)
-    #[serde(default = "default_sort_direction")]
-    pub direction: String,
+    /// Reverse the sort order (jekyll-paginate-v2), overrides `direction`
+    pub sort_reverse: Option<bool>,
     
     /// Collection to paginate (default: posts)
-    #[serde(default = "default_collection")]
-    pub collection: String,
+    pub collection: Option<String>,
     
     /// Category filter
     pub category: Option<String>,
@@ -58,6 +66,100 @@ pub struct Pagination {
     pub posts: Vec<String>,
 }
 
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            per_page: None,
+            page: default_page(),
+            total_pages: 0,
+            total_items: 0,
+            path: None,
+            title: None,
+            sort: None,
+            direction: None,
+            sort_reverse: None,
+            collection: None,
+            category: None,
+            tag: None,
+            posts: Vec::new(),
+        }
+    }
+}
+
+impl Pagination {
+    /// Fill settings which are not given from site-wide defaults
+    pub fn with_defaults(&self, defaults: &Pagination) -> Pagination {
+        let mut merged = self.clone();
+        
+        if merged.per_page.is_none() {
+            merged.per_page = defaults.per_page;
+        }
+        
+        if merged.path.is_none() {
+            merged.path = defaults.path.clone();
+        }
+        
+        if merged.title.is_none() {
+            merged.title = defaults.title.clone();
+        }
+        
+        if merged.sort.is_none() {
+            merged.sort = defaults.sort.clone();
+        }
+        
+        if merged.direction.is_none() {
+            merged.direction = defaults.direction.clone();
+        }
+        
+        if merged.sort_reverse.is_none() {
+            merged.sort_reverse = defaults.sort_reverse;
+        }
+        
+        if merged.collection.is_none() {
+            merged.collection = defaults.collection.clone();
+        }
+        
+        if merged.category.is_none() {
+            merged.category = defaults.category.clone();
+        }
+        
+        if merged.tag.is_none() {
+            merged.tag = defaults.tag.clone();
+        }
+        
+        merged
+    }
+    
+    /// Number of items per page
+    pub fn per_page(&self) -> usize {
+        self.per_page.unwrap_or_else(default_per_page)
+    }
+    
+    /// Field the items are sorted by
+    pub fn sort(&self) -> String {
+        self.sort.clone().unwrap_or_else(default_sort)
+    }
+    
+    /// Sort direction, asc or desc
+    pub fn direction(&self) -> String {
+        self.direction.clone().unwrap_or_else(default_sort_direction)
+    }
+    
+    /// Collections to paginate, comma-separated
+    pub fn collection(&self) -> String {
+        self.collection.clone().unwrap_or_else(default_collection)
+    }
+    
+    /// Whether items should be sorted in descending order
+    pub fn is_descending(&self) -> bool {
+        match self.sort_reverse {
+            Some(reverse) => reverse,
+            None => self.direction().eq_ignore_ascii_case("desc"),
+        }
+    }
+}
+
 /// SEO-related metadata
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct SeoMetadata {
@@ -372,7 +474,7 @@ impl FrontMatter {
             let mut pagination_obj = Object::new();
             
             pagination_obj.insert("enabled".into(), Value::scalar(pagination.enabled));
-            pagination_obj.insert("per_page".into(), Value::scalar(pagination.per_page.to_string()));
+            pagination_obj.insert("per_page".into(), Value::scalar(pagination.per_page().to_string()));
             pagination_obj.insert("page".into(), Value::scalar(pagination.page.to_string()));
             pagination_obj.insert("total_pages".into(), Value::scalar(pagination.total_pages.to_string()));
             pagination_obj.insert("total_items".into(), Value::scalar(pagination.total_items.to_string()));
@@ -381,9 +483,9 @@ impl FrontMatter {
                 pagination_obj.insert("path".into(), Value::scalar(path.clone()));
             }
             
-            pagination_obj.insert("sort".into(), Value::scalar(pagination.sort.clone()));
-            pagination_obj.insert("direction".into(), Value::scalar(pagination.direction.clone()));
-            pagination_obj.insert("collection".into(), Value::scalar(pagination.collection.clone()));
+            pagination_obj.insert("sort".into(), Value::scalar(pagination.sort()));
+            pagination_obj.insert("direction".into(), Value::scalar(pagination.direction()));
+            pagination_obj.insert("collection".into(), Value::scalar(pagination.collection()));
             
             if let Some(category) = &pagination.category {
                 pagination_obj.insert("category".into(), Value::scalar(category.clone()));
@@ -450,6 +552,7 @@ impl FrontMatter {
     
     /// Convert a datetime to a liquid object with Jekyll-compatible date parts
     fn datetime_to_liquid_object(&self, dt: DateTime<Utc>) -> Object {
+        let dt = crate::utils::date::local(dt);
         let mut obj = Object::new();
         
         // Add date components
@@ -589,46 +692,21 @@ impl FrontMatter {
     
     /// Get parsed date if available
     pub fn get_date(&self) -> Option<chrono::DateTime<chrono::Utc>> {
-        if let Some(date_str) = &self.date {
-            // Try ISO 8601 format (YYYY-MM-DD)
-            if let Ok(dt) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-                let naive_dt = dt.and_hms_opt(0, 0, 0)?;
-                return Some(Utc.from_utc_datetime(&naive_dt));
-            }
-            
-            // Try Jekyll date format (YYYY-MM-DD HH:MM:SS)
-            if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S") {
-                return Some(Utc.from_utc_datetime(&dt));
-            }
-            
-            // Try RFC3339 format
-            if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(date_str) {
-                return Some(dt.with_timezone(&Utc));
-            }
-        }
-        None
+        self.date.as_deref().and_then(crate::utils::date::parse_date)
     }
     
     /// Get modified date if available
     pub fn get_modified_date(&self) -> Option<chrono::DateTime<chrono::Utc>> {
-        if let Some(date_str) = &self.modified_date {
-            // Try ISO 8601 format (YYYY-MM-DD)
-            if let Ok(dt) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-                let naive_dt = dt.and_hms_opt(0, 0, 0)?;
-                return Some(Utc.from_utc_datetime(&naive_dt));
-            }
-            
-            // Try Jekyll date format (YYYY-MM-DD HH:MM:SS)
-            if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S") {
-                return Some(Utc.from_utc_datetime(&dt));
-            }
-            
-            // Try RFC3339 format
-            if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(date_str) {
-                return Some(dt.with_timezone(&Utc));
-            }
-        }
-        None
+        self.modified_date.as_deref().and_then(crate::utils::date::parse_date)
+    }
+    
+    /// Get the last modification date, from `modified_date` or `last_modified_at`
+    pub fn get_last_modified(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.get_modified_date().or_else(|| {
+            self.custom.get("last_modified_at")
+                .and_then(|value| value.as_str())
+                .and_then(crate::utils::date::parse_date)
+        })
     }
     
     /// Get categories as a single string
@@ -1,5 +1,5 @@
 pub mod front_matter;
 pub mod deserializers;
 
-pub use front_matter::FrontMatter;
+pub use front_matter::{FrontMatter, Pagination};
  
\ No newline at end of file
@@ -51,10 +51,21 @@ pub fn extract_front_matter(content: &str) -> BoxResult<(FrontMatter, String)> {
                 
                 Ok((front_matter, content))
             },
-            Err(e) => Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("Error parsing front matter: {}", e)
-            ))),
+            Err(e) => {
+                // Report the location as a line of the file, not of the YAML block
+                let message = match e.location() {
+                    Some(location) => {
+                        let leading_lines = content[3..].len() - content[3..].trim_start().len();
+                        let line_offset = content[3..3 + leading_lines].matches('\n').count();
+                        let reason = e.to_string();
+                        let reason = reason.split(" at line ").next().unwrap_or(&reason).to_string();
+                        format!("Error parsing front matter at line {} column {}: {}",
+                                location.line() + line_offset, location.column(), reason)
+                    },
+                    None => format!("Error parsing front matter: {}", e),
+                };
+                Err(Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, message)))
+            },
         }
     } else {
         Err(Box::new(std::io::Error::new(
@@ -0,0 +1,42 @@
+// Rustyll - A blazing fast, Jekyll-compatible static site generator written in Rust
+//! Rustyll builds Jekyll-compatible static sites.
+//!
+//! A [`Site`] is built from a [`Config`] in phases: `read` loads layouts,
+//! includes, data, pages and collections, `render` renders them in memory and
+//! `write` writes them to the destination. `process` runs all three:
+//!
+//! ```no_run
+//! let mut site = rustyll::Site::load(".")?;
+//! let outcome = site.process()?;
+//! println!("Built {} pages with {} problems", outcome.stats.pages_count, outcome.diagnostics.len());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+// Core modules
+mod builder;     // Site building and processing
+mod server;      // Development server
+mod config;      // Configuration handling
+mod markdown;    // Markdown processing
+mod directory;   // Directory and file operations
+mod collections; // Content collections
+mod front_matter; // Front matter parsing and handling
+mod liquid;      // Liquid templating
+pub mod cli;     // Command-line interface
+mod utils;       // Utility functions
+mod layout;      // Layout handling
+mod report;      // Site reporting and analytics
+mod migrate;     // Migration tools
+mod plugins;     // Plugin system (extensibility)
+#[cfg(test)]
+mod test_support; // Temporary sites for tests
+
+// Public API
+pub use builder::site::{Site, BuildStats, BuildOutcome, BuildReport, RenderedOutput, RenderRecord};
+pub use builder::page::{Page, Paginator};
+pub use builder::diagnostics::{Diagnostic, Severity, Category};
+pub use builder::profiler::{ProfileEntry, TemplateKind};
+pub use builder::types::BoxResult;
+pub use collections::Collection;
+pub use collections::document::model::Document;
+pub use config::{Config, load_config};
+pub use front_matter::FrontMatter;
@@ -0,0 +1,34 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+// Files read by include tags while the current thread renders a template.
+// Rendering a page happens entirely on one rayon worker, so a thread-local
+// recorder is enough to attribute includes to the page that used them.
+thread_local! {
+    static RECORDED: RefCell<Option<Vec<PathBuf>>> = const { RefCell::new(None) };
+}
+
+/// Start recording the files read by tags on the current thread
+pub fn start_recording() {
+    RECORDED.with(|recorded| {
+        *recorded.borrow_mut() = Some(Vec::new());
+    });
+}
+
+/// Record that a file was read while rendering
+///
+/// Does nothing unless recording was started on the current thread.
+pub fn record(path: &Path) {
+    RECORDED.with(|recorded| {
+        if let Some(paths) = recorded.borrow_mut().as_mut() {
+            if !paths.iter().any(|p| p == path) {
+                paths.push(path.to_path_buf());
+            }
+        }
+    });
+}
+
+/// Stop recording and return the files read since `start_recording`
+pub fn finish_recording() -> Vec<PathBuf> {
+    RECORDED.with(|recorded| recorded.borrow_mut().take().unwrap_or_default())
+}
@@ -0,0 +1,383 @@
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::builder::profiler::TemplateKind;
+use super::strict::{self, Segment};
+
+// Templates being rendered on the current thread, outermost first. A page is
+// rendered on a single rayon worker, like the include recording in
+// `dependencies`, so its layouts and includes stack up here.
+thread_local! {
+    static STACK: RefCell<Vec<(TemplateKind, PathBuf)>> = const { RefCell::new(Vec::new()) };
+
+    // An error described in an include, for the templates around it to pass on
+    static DESCRIBED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Lines shown before and after the failing line
+const CONTEXT_LINES: usize = 2;
+
+/// A template on the stack, removed when dropped
+pub struct Frame(());
+
+impl Drop for Frame {
+    fn drop(&mut self) {
+        STACK.with(|stack| {
+            stack.borrow_mut().pop();
+        });
+    }
+}
+
+/// Note that a template is rendered on the current thread until the frame is dropped
+pub fn enter(kind: TemplateKind, path: &Path) -> Frame {
+    STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        if stack.is_empty() {
+            DESCRIBED.with(|described| described.borrow_mut().take());
+        }
+        stack.push((kind, path.to_path_buf()));
+    });
+    Frame(())
+}
+
+/// The templates being rendered, innermost first, e.g.
+/// `_includes/card.html, included from _layouts/post.html`
+pub fn current_templates() -> Option<String> {
+    STACK.with(|stack| {
+        let stack = stack.borrow();
+        let mut text = display_path(&stack.last()?.1);
+        for line in stack_lines(&stack) {
+            text.push_str(", ");
+            text.push_str(&line);
+        }
+        Some(text)
+    })
+}
+
+/// How each template on the stack was reached, innermost first
+fn stack_lines(stack: &[(TemplateKind, PathBuf)]) -> Vec<String> {
+    stack.windows(2).rev()
+        .map(|pair| match pair[1].0 {
+            TemplateKind::Layout => format!("as the layout of {}", display_path(&pair[0].1)),
+            _ => format!("included from {}", display_path(&pair[0].1)),
+        })
+        .collect()
+}
+
+/// A path relative to the working directory, as the user wrote it
+fn display_path(path: &Path) -> String {
+    let relative = std::env::current_dir().ok()
+        .and_then(|dir| path.strip_prefix(dir).ok())
+        .unwrap_or(path);
+    relative.strip_prefix(".").unwrap_or(relative).display().to_string()
+}
+
+/// The parts of a Liquid error message
+#[derive(Debug, Default)]
+struct Parts {
+    message: String,
+    /// Line and column of a parse error, in the parsed text
+    position: Option<(usize, usize)>,
+    context: Vec<(String, String)>,
+    /// The tags the error passed through, innermost first
+    traces: Vec<String>,
+}
+
+impl Parts {
+    fn parse(text: &str) -> Self {
+        let mut parts = Parts::default();
+        let mut lines = text.lines();
+        let first = lines.next().unwrap_or("");
+        let first = first.strip_prefix("liquid:").unwrap_or(first).trim();
+
+        match first.strip_prefix("-->") {
+            Some(position) => {
+                let mut numbers = position.trim().split(':').filter_map(|n| n.parse().ok());
+                parts.position = numbers.next().zip(numbers.next());
+            }
+            None => parts.message = first.to_string(),
+        }
+
+        for line in lines {
+            let trimmed = line.trim();
+            if let Some(trace) = line.strip_prefix("from: ") {
+                parts.traces.push(trace.trim().to_string());
+            } else if let Some(message) = trimmed.strip_prefix("= ") {
+                // The message of a parse error, after pest's excerpt
+                if !parts.message.is_empty() {
+                    parts.message.push(' ');
+                }
+                parts.message.push_str(message);
+            } else if let Some((key, value)) = trimmed.split_once('=') {
+                if line.starts_with("    ") && !key.contains('|') {
+                    parts.context.push((key.to_string(), value.to_string()));
+                }
+            }
+        }
+
+        parts
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.context.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// The message with its context, leaving out the lists of what is available
+    fn summary(&self) -> String {
+        let context: Vec<String> = self.context.iter()
+            .filter(|(key, _)| !key.starts_with("available"))
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        match (self.message.is_empty(), context.is_empty()) {
+            (true, _) => "Liquid error".to_string(),
+            (false, true) => self.message.clone(),
+            (false, false) => format!("{} ({})", self.message, context.join(", ")),
+        }
+    }
+}
+
+/// The offset of a line and column in a text, if the text has that line
+fn line_column_offset(text: &str, line: usize, column: usize) -> Option<usize> {
+    let start = text.split_inclusive('\n').take(line.saturating_sub(1)).map(str::len).sum::<usize>();
+    let line_text = text.lines().nth(line.saturating_sub(1))?;
+    Some(start + line_text.chars().take(column.saturating_sub(1)).map(char::len_utf8).sum::<usize>())
+}
+
+/// The offset in `source` of a parse error position in `parsed`
+fn map_position(line: usize, column: usize, source: &str, parsed: &str) -> Option<usize> {
+    let offset = line_column_offset(parsed, line, column)?;
+    if parsed == source {
+        return Some(offset);
+    }
+
+    // Markdown and preprocessing change the text, so find the same markup in
+    // the source, or the one in the same place when smart quotes changed it
+    let words = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let source_markups = strict::markups(source);
+    let parsed_markups = strict::markups(parsed);
+    let found = parsed_markups.iter().enumerate()
+        .find(|(_, markup)| markup.offset <= offset && offset <= markup.offset + markup.inner.len())
+        .and_then(|(index, markup)| {
+            let inner = words(markup.inner);
+            source_markups.iter()
+                .find(|candidate| words(candidate.inner) == inner)
+                .or_else(|| source_markups.get(index).filter(|_| source_markups.len() == parsed_markups.len()))
+                .map(|candidate| candidate.offset + (offset - markup.offset).min(candidate.inner.len()))
+        });
+
+    // Otherwise the parser's line and column, which Markdown mostly keeps
+    found.or_else(|| line_column_offset(source, line, column))
+}
+
+/// Find where an error happened in the source
+fn locate(parts: &Parts, source: &str, parsed: &str) -> Option<usize> {
+    let markups = strict::markups(source);
+
+    // Unclosed blocks are reported at the end, the last opening tag is more useful
+    let unclosed = Regex::new(r"Unclosed block\. \{% end(\w+) %\}").unwrap();
+    if let Some(caps) = unclosed.captures(&parts.message) {
+        let opening = markups.iter().rev().find(|markup| !markup.output && markup.tag().0 == &caps[1]);
+        if let Some(markup) = opening {
+            return Some(markup.offset + markup.inner.len() - markup.inner.trim_start().len());
+        }
+    }
+    if let Some((line, column)) = parts.position {
+        return map_position(line, column, source, parsed);
+    }
+
+    let references = || markups.iter().flat_map(|markup| {
+        let mut references = Vec::new();
+        strict::scan_references(markup.inner, markup.offset, &mut references);
+        references
+    });
+
+    if let Some(name) = parts.get("requested variable") {
+        if let Some(reference) = references().find(|reference| reference.root == name) {
+            return Some(reference.offset);
+        }
+    }
+    if let (Some(variable), Some(index)) = (parts.get("variable"), parts.get("requested index")) {
+        // The variable is shown as a path, its last name is what the index follows
+        let parent = Regex::new(r"[\w-]+").unwrap().find_iter(variable).last().map_or("", |m| m.as_str());
+        let found = references().find(|reference| {
+            let names: Vec<String> = std::iter::once(reference.root.clone())
+                .chain(reference.segments.iter().map(|segment| match segment {
+                    Segment::Key(key) => key.clone(),
+                    Segment::Index(index) => index.to_string(),
+                    Segment::Dynamic => String::new(),
+                }))
+                .collect();
+            names.windows(2).any(|pair| pair[0] == parent && pair[1] == index)
+        });
+        if let Some(reference) = found {
+            return Some(reference.offset);
+        }
+    }
+    // Filters that fail on their arguments are named with them
+    let filter = parts.get("requested filter")
+        .or_else(|| parts.get("filter").and_then(|filter| filter.split([':', ' ']).next()));
+    if let Some(name) = filter {
+        for markup in &markups {
+            for (start, filter) in strict::split_filters(markup.inner).into_iter().skip(1) {
+                if strict::filter_name(filter).0 == name {
+                    return Some(markup.offset + start + filter.len() - filter.trim_start().len());
+                }
+            }
+        }
+    }
+
+    // Tags are traced with their markup, the first words find them
+    let trace = parts.traces.first()?;
+    let words: Vec<String> = trace.trim_start_matches(['{', '%', '-']).split_whitespace()
+        .take(2)
+        .map(|word| regex::escape(word.trim_end_matches(['%', '}', '-'])))
+        .collect();
+    let pattern = Regex::new(&format!(r"^\s*{}", words.join(r"\s+"))).ok()?;
+    markups.iter()
+        .find(|markup| !markup.output && pattern.is_match(markup.inner))
+        .map(|markup| markup.offset + markup.inner.len() - markup.inner.trim_start().len())
+}
+
+/// The line at an offset with the lines around it, and a caret under the offset
+pub(super) fn excerpt(source: &str, offset: usize, line_offset: usize) -> String {
+    let before = &source[..offset.min(source.len())];
+    let index = before.matches('\n').count();
+    let column_text = before.rsplit('\n').next().unwrap_or("");
+
+    let lines: Vec<&str> = source.lines().collect();
+    let first = index.saturating_sub(CONTEXT_LINES);
+    let last = (index + CONTEXT_LINES).min(lines.len().saturating_sub(1));
+    let width = (last + 1 + line_offset).to_string().len();
+
+    let mut text = String::new();
+    for (i, line) in lines.iter().enumerate().take(last + 1).skip(first) {
+        text.push_str(&format!("{:>width$} | {}\n", i + 1 + line_offset, line, width = width));
+        if i == index {
+            // Keep tabs so the caret lines up
+            let padding: String = column_text.chars().map(|c| if c == '\t' { '\t' } else { ' ' }).collect();
+            text.push_str(&format!("{:>width$} | {}^\n", "", padding, width = width));
+        }
+    }
+    text
+}
+
+/// Whether the current template is an include
+fn in_include() -> bool {
+    STACK.with(|stack| stack.borrow().last().is_some_and(|(kind, _)| *kind == TemplateKind::Include))
+}
+
+/// Keep a description made in an include, for the templates around it to pass on
+fn pass_on(description: String) -> String {
+    if in_include() {
+        DESCRIBED.with(|described| *described.borrow_mut() = Some(description.clone()));
+    }
+    description
+}
+
+/// Where a problem is in a template, e.g. ` at line 3, column 5`
+///
+/// Layouts and includes are named with the position, since diagnostics take
+/// a bare line and column to be in the page.
+pub(super) fn location(file: &Path, line: usize, column: usize) -> String {
+    let nested = STACK.with(|stack| stack.borrow().len() > 1);
+    if nested {
+        format!(" in {}:{}:{}", display_path(file), line, column)
+    } else {
+        format!(" at line {}, column {}", line, column)
+    }
+}
+
+/// Finish the description of a problem with the includes and layouts that led to it
+pub(super) fn finish(description: &str) -> String {
+    let lines = STACK.with(|stack| stack_lines(&stack.borrow()));
+    let mut description = description.trim_end().to_string();
+    for line in lines {
+        description.push('\n');
+        description.push_str(&line);
+    }
+    pass_on(description)
+}
+
+/// Describe a Liquid error in a template
+///
+/// `source` is the template as written in `file` and `parsed` the text
+/// given to the parser. The description has the failing line with a few
+/// lines around it, and the includes and layouts that led to the template.
+/// Errors already described by an include are passed on as they are.
+pub fn describe(error: &dyn Display, source: &str, parsed: &str, file: &Path) -> String {
+    if let Some(described) = DESCRIBED.with(|described| described.borrow_mut().take()) {
+        return pass_on(described);
+    }
+
+    let parts = Parts::parse(&error.to_string());
+    let mut description = parts.summary().trim_end_matches('.').to_string();
+    let line_offset = strict::front_matter_lines(file);
+    match locate(&parts, source, parsed) {
+        Some(offset) => {
+            let (line, column) = strict::position(source, offset);
+            description.push_str(&location(file, line + line_offset, column));
+            description.push('\n');
+            description.push_str(&excerpt(source, offset, line_offset));
+        }
+        None if STACK.with(|stack| stack.borrow().len() > 1) => {
+            description.push_str(&format!(" in {}", display_path(file)));
+        }
+        None => {}
+    }
+
+    finish(&description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(template: &str, globals: &liquid::Object) -> liquid::Error {
+        let parser = liquid::ParserBuilder::with_stdlib().build().unwrap();
+        match parser.parse(template) {
+            Ok(template) => template.render(globals).unwrap_err(),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn test_describe_errors() {
+        let globals = liquid::object!({ "page": { "title": "Hello" } });
+        let source = "<h1>\n  {{ page.title }}\n  {{ page.tittle | upcase }}\n</h1>\n";
+        let described = describe(&error(source, &globals), source, source, Path::new("index.html"));
+        assert_eq!(described, "Unknown index (variable=page, requested index=tittle) at line 3, column 6\n\
+                               1 | <h1>\n\
+                               2 |   {{ page.title }}\n\
+                               3 |   {{ page.tittle | upcase }}\n  \
+                               |      ^\n\
+                               4 | </h1>");
+
+        let source = "a\n{{ 'x' | nofilter }}";
+        let described = describe(&error(source, &globals), source, source, Path::new("index.html"));
+        assert!(described.starts_with("Unknown filter (requested filter=nofilter) at line 2, column 10\n"));
+
+        let source = "a\nb {{ x | }}";
+        let _page = enter(TemplateKind::Page, Path::new("about.md"));
+        let _layout = enter(TemplateKind::Layout, Path::new("_layouts/default.html"));
+        let described = describe(&error(source, &globals), source, source, Path::new("_layouts/default.html"));
+        assert!(described.starts_with("expected Identifier in _layouts/default.html:2:10\n"));
+        assert!(described.ends_with("\nas the layout of about.md"));
+    }
+
+    #[test]
+    fn test_describe_markdown_errors() {
+        let config = crate::config::Config::default();
+        let renderer = crate::markdown::MarkdownRenderer::new(&config);
+        let globals = liquid::Object::new();
+
+        // Smart quotes change the markup, it is found by its place instead
+        let source = "Some *text*\n\nSay {{ \"hi\" | }}\n";
+        let parsed = html_escape::decode_html_entities(&renderer.render(source)).to_string();
+        let described = describe(&error(&parsed, &globals), source, &parsed, Path::new("about.md"));
+        assert!(described.starts_with("expected Value at line 3, column 8\n"), "{}", described);
+        assert!(described.contains("3 | Say {{ \"hi\" | }}\n"));
+    }
+}
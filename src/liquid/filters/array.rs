@@ -0,0 +1,163 @@
+//! Helpers shared by the Jekyll array filters
+
+use std::collections::HashMap;
+
+use liquid_core::runtime::Expression;
+use liquid_core::{Object, Result as LiquidResult, Runtime, Value, ValueView};
+
+/// The items of an array, or the values of a hash like in Jekyll
+pub(super) fn items(input: &dyn ValueView) -> Option<Vec<&dyn ValueView>> {
+    if let Some(array) = input.as_array() {
+        Some(array.values().collect())
+    } else {
+        input.as_object().map(|object| object.values().collect())
+    }
+}
+
+/// Look up a property of an item, following dots into nested objects
+///
+/// Missing and nil properties are both `None`.
+pub(super) fn property<'v>(item: &'v dyn ValueView, path: &str) -> Option<&'v dyn ValueView> {
+    path.split('.')
+        .try_fold(item, |value, key| match value.as_object() {
+            Some(object) => object.get(key),
+            None => value.as_array().and_then(|array| array.get(key.parse().ok()?)),
+        })
+        .filter(|value| !value.is_nil())
+}
+
+/// Evaluate the variable name and expression of the `_exp` filters
+pub(super) fn exp_arguments(variable: &Expression, expression: &Expression, runtime: &dyn Runtime) -> LiquidResult<(String, String)> {
+    Ok((
+        variable.evaluate(runtime)?.to_kstr().to_string(),
+        expression.evaluate(runtime)?.to_kstr().to_string(),
+    ))
+}
+
+pub(super) fn to_array(items: impl Iterator<Item = Value>) -> Value {
+    Value::Array(items.collect())
+}
+
+/// Build Jekyll's `[{name, items, size}]` groups, in order of first appearance
+pub(super) fn grouped(groups: Vec<(Value, Vec<Value>)>) -> Value {
+    to_array(groups.into_iter().map(|(name, items)| {
+        let mut group = Object::new();
+        group.insert("name".into(), name);
+        group.insert("size".into(), Value::scalar(items.len() as i64));
+        group.insert("items".into(), Value::Array(items));
+        Value::Object(group)
+    }))
+}
+
+/// Add an item to the group named by `name`
+pub(super) fn add_to_group(groups: &mut Vec<(Value, Vec<Value>)>, index: &mut HashMap<String, usize>, name: Value, item: Value) {
+    let key = name.to_kstr().to_string();
+    let i = *index.entry(key).or_insert_with(|| {
+        groups.push((name, Vec::new()));
+        groups.len() - 1
+    });
+    groups[i].1.push(item);
+}
+
+/// Evaluate an optional count argument, one by default
+pub(super) fn count_argument(count: &Option<Expression>, runtime: &dyn Runtime) -> LiquidResult<usize> {
+    match count {
+        Some(count) => {
+            let count = count.evaluate(runtime)?;
+            Ok(count.as_scalar().and_then(|count| count.to_integer()).unwrap_or(1).max(0) as usize)
+        }
+        None => Ok(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use liquid_core::{Object, Value};
+
+    use crate::config::Config;
+    use crate::liquid::create_jekyll_parser;
+    use super::super::expression::Condition;
+
+    fn render(template: &str) -> String {
+        let config = Config::default();
+        let parser = create_jekyll_parser(&config, HashMap::new()).unwrap();
+
+        let posts: Vec<Value> = [
+            ("One", "2023-05-01", "en", Some(3), vec!["rust", "web"]),
+            ("Two", "2024-02-10", "fr", None, vec!["web"]),
+            ("Three", "2024-08-20", "en", Some(1), vec![]),
+        ].into_iter().map(|(title, date, lang, weight, tags)| {
+            let mut post = Object::new();
+            post.insert("title".into(), Value::scalar(title));
+            post.insert("date".into(), Value::scalar(date));
+            if let Some(weight) = weight {
+                post.insert("weight".into(), Value::scalar(weight as i64));
+            }
+            post.insert("tags".into(), Value::Array(tags.into_iter().map(Value::scalar).collect()));
+            let mut data = Object::new();
+            data.insert("lang".into(), Value::scalar(lang));
+            post.insert("data".into(), Value::Object(data));
+            Value::Object(post)
+        }).collect();
+
+        let mut globals = Object::new();
+        globals.insert("posts".into(), Value::Array(posts));
+        globals.insert("lang".into(), Value::scalar("en"));
+        parser.parse(template).unwrap().render(&globals).unwrap()
+    }
+
+    #[test]
+    fn test_where_and_find() {
+        assert_eq!(render(r#"{{ posts | where: "data.lang", lang | map: "title" | join: "," }}"#), "One,Three");
+        assert_eq!(render(r#"{{ posts | where: "tags", "web" | map: "title" | join: "," }}"#), "One,Two");
+        assert_eq!(render(r#"{{ posts | where: "weight", nil | map: "title" | join: "," }}"#), "Two");
+        assert_eq!(render(r#"{% assign post = posts | find: "title", "Two" %}{{ post.date }}"#), "2024-02-10");
+    }
+
+    #[test]
+    fn test_where_exp_and_find_exp() {
+        let titles = |expression: &str| render(&format!(
+            r#"{{{{ posts | where_exp: "post", '{}' | map: "title" | join: "," }}}}"#, expression));
+
+        assert_eq!(titles(r#"post.tags contains "rust""#), "One");
+        assert_eq!(titles(r#"post.data.lang == lang and post.weight"#), "One,Three");
+        assert_eq!(titles(r#"post.weight < 2 or post.tags.size == 1"#), "Two,Three");
+        assert_eq!(titles(r#"post.data["lang"] != "en""#), "Two");
+        assert_eq!(titles("post.tags == empty"), "Three");
+        assert_eq!(render(r#"{% assign post = posts | find_exp: "post", "post.weight > 2" %}{{ post.title }}"#), "One");
+        assert!(Condition::parse("post.title ==").is_err());
+        assert!(Condition::parse("post.title == \"a").is_err());
+    }
+
+    #[test]
+    fn test_group_by() {
+        let groups = r#"{% for group in groups %}{{ group.name }}:{{ group.size }}:{{ group.items | map: "title" | join: "+" }};{% endfor %}"#;
+
+        let template = format!(r#"{{% assign groups = posts | group_by: "data.lang" %}}{}"#, groups);
+        assert_eq!(render(&template), "en:2:One+Three;fr:1:Two;");
+
+        let template = format!(r#"{{% assign groups = posts | group_by_exp: "post", "post.date | slice: 0, 4" %}}{}"#, groups);
+        assert_eq!(render(&template), "2023:1:One;2024:2:Two+Three;");
+
+        // Filter chains are rendered where the filter is used, so they see its variables
+        let template = format!(r#"{{% assign groups = posts | group_by_exp: "post", "post.data.lang | append: lang" %}}{}"#, groups);
+        assert_eq!(render(&template), "enen:2:One+Three;fren:1:Two;");
+    }
+
+    #[test]
+    fn test_sort_nils() {
+        assert_eq!(render(r#"{{ posts | sort: "weight" | map: "title" | join: "," }}"#), "Two,Three,One");
+        assert_eq!(render(r#"{{ posts | sort: "weight", "last" | map: "title" | join: "," }}"#), "Three,One,Two");
+        assert_eq!(render(r#"{{ "10,9,100" | split: "," | sort | join: "," }}"#), "9,10,100");
+    }
+
+    #[test]
+    fn test_push_pop_shift_unshift() {
+        let list = r#"{% assign list = "b,c,d" | split: "," %}"#;
+        assert_eq!(render(&format!(r#"{}{{{{ list | push: "e" | unshift: "a" | join: "" }}}}"#, list)), "abcde");
+        assert_eq!(render(&format!(r#"{}{{{{ list | pop | join: "" }}}}-{{{{ list | shift: 2 | join: "" }}}}"#, list)), "bc-d");
+        assert_eq!(render(&format!(r#"{}{{{{ list | sample: 5 | size }}}}{{{{ list | sample | size }}}}"#, list)), "31");
+    }
+}
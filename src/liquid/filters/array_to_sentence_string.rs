@@ -0,0 +1,71 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::string::{input_string, optional_string};
+
+/// ArrayToSentenceString filter implementation
+#[derive(Debug)]
+pub struct ArrayToSentenceStringFilter {
+    connector: Option<Expression>,
+}
+
+impl fmt::Display for ArrayToSentenceStringFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "array_to_sentence_string")
+    }
+}
+
+impl liquid_core::Filter for ArrayToSentenceStringFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let connector = optional_string(&self.connector, runtime)?.unwrap_or_else(|| "and".to_string());
+        let items: Vec<String> = match input.as_array() {
+            Some(array) => array.values().map(|item| item.to_kstr().to_string()).collect(),
+            None => vec![input_string(input)],
+        };
+
+        let sentence = match items.as_slice() {
+            [] => String::new(),
+            [item] => item.clone(),
+            [first, second] => format!("{} {} {}", first, connector, second),
+            [rest @ .., last] => format!("{}, {} {}", rest.join(", "), connector, last),
+        };
+        Ok(Value::scalar(sentence))
+    }
+}
+
+/// Parse filter factory for array_to_sentence_string
+#[derive(Debug, Clone)]
+pub struct ArrayToSentenceStringFilterParser;
+
+impl FilterReflection for ArrayToSentenceStringFilterParser {
+    fn name(&self) -> &str {
+        "array_to_sentence_string"
+    }
+
+    fn description(&self) -> &str {
+        "Joins an array into a sentence, e.g. \"a, b, and c\""
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for ArrayToSentenceStringFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "array_to_sentence_string", 0, 1)?.into_iter();
+        Ok(Box::new(ArrayToSentenceStringFilter { connector: arguments.next() }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
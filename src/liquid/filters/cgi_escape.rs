@@ -0,0 +1,58 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::string::{input_string, percent_encode};
+
+/// CgiEscape filter implementation
+#[derive(Debug)]
+pub struct CgiEscapeFilter;
+
+impl fmt::Display for CgiEscapeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cgi_escape")
+    }
+}
+
+impl liquid_core::Filter for CgiEscapeFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> LiquidResult<Value> {
+        // Like Ruby's CGI.escape, spaces become plus signs
+        let escaped = percent_encode(&input_string(input), |b| b.is_ascii_alphanumeric() || b"_.-~ ".contains(&b));
+        Ok(Value::scalar(escaped.replace(' ', "+")))
+    }
+}
+
+/// Parse filter factory for cgi_escape
+#[derive(Debug, Clone)]
+pub struct CgiEscapeFilterParser;
+
+impl FilterReflection for CgiEscapeFilterParser {
+    fn name(&self) -> &str {
+        "cgi_escape"
+    }
+
+    fn description(&self) -> &str {
+        "Escapes text for use in a URL query string"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for CgiEscapeFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        arguments(args, "cgi_escape", 0, 0)?;
+        Ok(Box::new(CgiEscapeFilter))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
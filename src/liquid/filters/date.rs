@@ -1,13 +1,94 @@
 use std::fmt;
-use liquid_core::{Runtime, ValueView, Value, Result as LiquidResult};
-use liquid_core::parser::{FilterArguments, ParseFilter, ParameterReflection};
-use liquid_core::{FilterReflection};
-use chrono::{NaiveDateTime, Utc, TimeZone, DateTime};
 
-/// Date filter implementation
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use liquid_core::model::{try_find, ScalarCow};
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{Error, FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use crate::utils::date::{local, parse_date, strftime, DateNames};
+use super::arguments;
+
+/// Parse a date from a filter input, accepting what Liquid's `date` does
+///
+/// Besides date strings this takes `now`, `today` and Unix timestamps.
+fn parse_input(input: &dyn ValueView) -> Option<DateTime<Utc>> {
+    if let Some(timestamp) = input.as_scalar().and_then(|scalar| scalar.to_integer()) {
+        return Utc.timestamp_opt(timestamp, 0).single();
+    }
+
+    let text = input.to_kstr();
+    let text = text.trim();
+    if text == "now" || text == "today" {
+        Some(Utc::now())
+    } else if !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit()) {
+        Utc.timestamp_opt(text.parse().ok()?, 0).single()
+    } else {
+        parse_date(text)
+    }
+}
+
+/// The date of a filter input in the site's timezone, like Jekyll's `time`
+///
+/// Nil and empty input give `None`, which filters pass through unchanged.
+pub(super) fn input_date(input: &dyn ValueView) -> LiquidResult<Option<DateTime<Tz>>> {
+    if input.is_nil() || input.to_kstr().trim().is_empty() {
+        return Ok(None);
+    }
+    match parse_input(input) {
+        Some(date) => Ok(Some(local(date))),
+        None => Err(Error::with_msg(format!("Invalid Date: '{}' is not a valid datetime.", input.to_kstr()))),
+    }
+}
+
+/// Month and day names for the current language, from `site.data.locales`
+///
+/// The language is the page's `lang`, or else the site's `lang` or
+/// `locale`. Locale files use the layout of Rails' i18n files, with names
+/// under `date` or at the top level, optionally nested under the language.
+pub(super) fn locale_names(runtime: &dyn Runtime) -> Option<DateNames> {
+    let lang = [["page", "lang"], ["site", "lang"], ["site", "locale"]].iter()
+        .filter_map(|path| runtime.try_get(&[ScalarCow::new(path[0]), ScalarCow::new(path[1])]))
+        .map(|lang| lang.to_kstr().to_string())
+        .find(|lang| !lang.is_empty())?;
+
+    let locale = runtime.try_get(&[ScalarCow::new("site"), ScalarCow::new("data"), ScalarCow::new("locales"), ScalarCow::new(lang.as_str())])?;
+    let locale = match try_find(locale.as_view(), &[ScalarCow::new(lang.as_str())]) {
+        Some(nested) if nested.is_object() => nested,
+        _ => locale,
+    };
+    let names = match try_find(locale.as_view(), &[ScalarCow::new("date")]) {
+        Some(section) if section.is_object() => section,
+        _ => locale,
+    };
+
+    let list = |key: &str| -> Vec<String> {
+        let Some(values) = try_find(names.as_view(), &[ScalarCow::new(key)]) else {
+            return Vec::new();
+        };
+        let Some(values) = values.as_array() else {
+            return Vec::new();
+        };
+        // Rails lists months from index 1, with nil in front
+        let skip = usize::from(values.size() == 13 && values.first().is_some_and(|first| first.is_nil()));
+        values.values().skip(skip).map(|value| value.to_kstr().to_string()).collect()
+    };
+
+    let names = DateNames {
+        months: list("month_names"),
+        abbr_months: list("abbr_month_names"),
+        days: list("day_names"),
+        abbr_days: list("abbr_day_names"),
+    };
+    let found = !(names.months.is_empty() && names.abbr_months.is_empty() && names.days.is_empty() && names.abbr_days.is_empty());
+    found.then_some(names)
+}
+
+/// Liquid's `date` filter, in the site's timezone and language
 #[derive(Debug, Clone)]
 pub struct DateFilter {
-    format: String,
+    format: Option<Expression>,
 }
 
 impl fmt::Display for DateFilter {
@@ -17,66 +98,21 @@ impl fmt::Display for DateFilter {
 }
 
 impl liquid_core::Filter for DateFilter {
-    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> LiquidResult<Value> {
-        let date_str = input.to_kstr().to_string();
-
-        // Try to parse the date in various formats
-        let date = if date_str == "now" || date_str == "today" {
-            // Special case for "now" and "today"
-            Some(Utc::now())
-        } else if let Ok(timestamp) = date_str.parse::<i64>() {
-            // Unix timestamp
-            Some(Utc.timestamp_opt(timestamp, 0).single().unwrap_or_else(Utc::now))
-        } else {
-            // Try various date string formats
-            parse_date_string(&date_str)
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let format = match &self.format {
+            Some(format) => format.evaluate(runtime)?.to_kstr().to_string(),
+            None => String::new(),
         };
 
-        if let Some(dt) = date {
-            // Format the date according to the provided format string
-            let formatted = format_date(&dt, &self.format);
-            Ok(Value::scalar(formatted))
-        } else {
-            // If can't parse, return the original string
-            Ok(Value::scalar(date_str))
-        }
-    }
-}
-
-fn parse_date_string(date_str: &str) -> Option<DateTime<Utc>> {
-    // Try RFC3339 format first (used by Jekyll/Liquid)
-    if let Ok(dt) = DateTime::parse_from_rfc3339(date_str) {
-        return Some(dt.with_timezone(&Utc));
-    }
-
-    // Try ISO 8601 format (YYYY-MM-DD HH:MM:SS)
-    if let Ok(dt) = NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S") {
-        return Some(Utc.from_utc_datetime(&dt));
-    }
+        // Like Liquid, input is passed through without a format or a date
+        let date = match parse_input(input) {
+            Some(date) if !format.is_empty() => date,
+            _ => return Ok(input.to_value()),
+        };
 
-    // Try date only (YYYY-MM-DD)
-    if let Ok(dt) = NaiveDateTime::parse_from_str(&format!("{} 00:00:00", date_str), "%Y-%m-%d %H:%M:%S") {
-        return Some(Utc.from_utc_datetime(&dt));
+        let names = locale_names(runtime);
+        Ok(Value::scalar(strftime(&local(date), &format, names.as_ref())))
     }
-
-    None
-}
-
-fn format_date(dt: &DateTime<Utc>, format: &str) -> String {
-    // Convert strftime-like format to chrono format
-    // This handles Jekyll/Liquid date formatting strings
-    let chrono_format = convert_jekyll_to_chrono_format(format);
-    dt.format(&chrono_format).to_string()
-}
-
-fn convert_jekyll_to_chrono_format(format: &str) -> String {
-    // Jekyll uses strftime-style formatting
-    // This is already compatible with chrono's format strings
-    // But we need to handle a few special cases
-    format
-        .replace("%s", "%s") // Unix timestamp (already compatible)
-        .replace("%Q", "%3f") // Milliseconds
-        .to_string()
 }
 
 /// Parse filter factory for date
@@ -89,7 +125,7 @@ impl FilterReflection for DateFilterParser {
     }
 
     fn description(&self) -> &str {
-        "Formats a date according to the specified format string"
+        "Formats a date with a strftime format string"
     }
 
     fn positional_parameters(&self) -> &'static [ParameterReflection] {
@@ -102,15 +138,54 @@ impl FilterReflection for DateFilterParser {
 }
 
 impl ParseFilter for DateFilterParser {
-    fn parse(&self, _args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
-        // For now, we'll use a default format
-        // TODO: Parse the format string from arguments when the API is clearer
-        Ok(Box::new(DateFilter {
-            format: "%B %d, %Y".to_string()  // Default Jekyll format
-        }))
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let format = arguments(args, "date", 0, 1)?.pop();
+        Ok(Box::new(DateFilter { format }))
     }
 
     fn reflection(&self) -> &dyn FilterReflection {
         self
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use liquid::{object, ParserBuilder};
+
+    use crate::liquid::filters::register_filters;
+    use crate::config::Config;
+
+    fn render(template: &str, globals: &liquid::Object) -> String {
+        let parser = register_filters(ParserBuilder::with_stdlib(), &Config::default()).build().unwrap();
+        parser.parse(template).unwrap().render(globals).unwrap()
+    }
+
+    #[test]
+    fn test_date_filters() {
+        let globals = object!({ "date": "2008-11-07 13:07:54 -0800", "empty": "" });
+
+        assert_eq!(render("{{ date | date: '%Y/%m/%d %H:%M' }}", &globals), "2008/11/07 21:07");
+        assert_eq!(render("{{ date | date_to_string }}|{{ date | date_to_string: 'ordinal', 'US' }}", &globals),
+                   "07 Nov 2008|Nov 7th, 2008");
+        assert_eq!(render("{{ date | date_to_long_string: 'ordinal' }}", &globals), "7th November 2008");
+        assert_eq!(render("{{ date | date_to_rfc822 }}", &globals), "Fri, 07 Nov 2008 21:07:54 +0000");
+        assert_eq!(render("{{ date | date_to_xmlschema }}", &globals), "2008-11-07T21:07:54+00:00");
+        assert_eq!(render("{{ empty | date_to_string }}|{{ 'soon' | date: '%Y' }}", &globals), "|soon");
+    }
+
+    #[test]
+    fn test_localized_names() {
+        let globals = object!({
+            "date": "2024-02-03",
+            "page": { "lang": "fr" },
+            "site": { "data": { "locales": { "fr": { "fr": { "date": {
+                "month_names": [nil, "janvier", "février", "mars", "avril", "mai", "juin",
+                                "juillet", "août", "septembre", "octobre", "novembre", "décembre"],
+                "day_names": ["dimanche", "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi"],
+            } } } } } },
+        });
+
+        assert_eq!(render("{{ date | date: '%A %-d %B %Y' }}", &globals), "samedi 3 février 2024");
+        assert_eq!(render("{{ date | date_to_long_string }}", &globals), "03 février 2024");
+    }
+}
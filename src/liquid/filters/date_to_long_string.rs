@@ -0,0 +1,59 @@
+use std::fmt;
+use liquid_core::{Runtime, ValueView, Value, Result as LiquidResult};
+use liquid_core::parser::{FilterArguments, ParseFilter, ParameterReflection};
+use liquid_core::runtime::Expression;
+use liquid_core::FilterReflection;
+
+use super::arguments;
+use super::date_to_string::stringify_date;
+
+/// DateToLongString filter implementation
+#[derive(Debug, Clone)]
+pub struct DateToLongStringFilter {
+    args: Vec<Expression>,
+}
+
+impl fmt::Display for DateToLongStringFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "date_to_long_string")
+    }
+}
+
+impl liquid_core::Filter for DateToLongStringFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        stringify_date(input, "%B", &self.args, runtime)
+    }
+}
+
+/// Parse filter factory for date_to_long_string
+#[derive(Debug, Clone)]
+pub struct DateToLongStringFilterParser;
+
+impl FilterReflection for DateToLongStringFilterParser {
+    fn name(&self) -> &str {
+        "date_to_long_string"
+    }
+
+    fn description(&self) -> &str {
+        "Formats a date according to Jekyll's date_to_long_string format (%d %B %Y)"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for DateToLongStringFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let args = arguments(args, "date_to_long_string", 0, 2)?;
+        Ok(Box::new(DateToLongStringFilter { args }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
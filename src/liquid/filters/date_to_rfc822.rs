@@ -0,0 +1,61 @@
+use std::fmt;
+use liquid_core::{Runtime, ValueView, Value, Result as LiquidResult};
+use liquid_core::parser::{FilterArguments, ParseFilter, ParameterReflection};
+use liquid_core::FilterReflection;
+
+use crate::utils::date::strftime;
+use super::arguments;
+use super::date::input_date;
+
+/// DateToRfc822 filter implementation
+#[derive(Debug, Clone)]
+pub struct DateToRfc822Filter;
+
+impl fmt::Display for DateToRfc822Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "date_to_rfc822")
+    }
+}
+
+impl liquid_core::Filter for DateToRfc822Filter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> LiquidResult<Value> {
+        // RFC 822 names are always English
+        match input_date(input)? {
+            Some(date) => Ok(Value::scalar(strftime(&date, "%a, %d %b %Y %H:%M:%S %z", None))),
+            None => Ok(input.to_value()),
+        }
+    }
+}
+
+/// Parse filter factory for date_to_rfc822
+#[derive(Debug, Clone)]
+pub struct DateToRfc822FilterParser;
+
+impl FilterReflection for DateToRfc822FilterParser {
+    fn name(&self) -> &str {
+        "date_to_rfc822"
+    }
+
+    fn description(&self) -> &str {
+        "Formats a date for RSS feeds as defined by RFC 822"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for DateToRfc822FilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        arguments(args, "date_to_rfc822", 0, 0)?;
+        Ok(Box::new(DateToRfc822Filter))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
@@ -1,12 +1,56 @@
 use std::fmt;
 use liquid_core::{Runtime, ValueView, Value, Result as LiquidResult};
 use liquid_core::parser::{FilterArguments, ParseFilter, ParameterReflection};
+use liquid_core::runtime::Expression;
 use liquid_core::{FilterReflection};
-use chrono::{TimeZone, Utc, NaiveDateTime};
+use chrono::Datelike;
+
+use crate::utils::date::{ordinal_suffix, strftime};
+use super::arguments;
+use super::date::{input_date, locale_names};
+
+/// Format a date like Jekyll's `date_to_string` and `date_to_long_string`
+///
+/// `month` is the strftime directive for the month name. With the
+/// `"ordinal"` type the day gets an English suffix, and the `"US"` style
+/// puts the month first.
+pub(super) fn stringify_date(
+    input: &dyn ValueView,
+    month: &str,
+    args: &[Expression],
+    runtime: &dyn Runtime,
+) -> LiquidResult<Value> {
+    let date = match input_date(input)? {
+        Some(date) => date,
+        None => return Ok(input.to_value()),
+    };
+
+    let mut options = Vec::with_capacity(args.len());
+    for arg in args {
+        options.push(arg.evaluate(runtime)?.to_kstr().to_string());
+    }
+    let (kind, style) = (options.first().map(String::as_str), options.get(1).map(String::as_str));
+
+    let format = if kind == Some("ordinal") {
+        let day = format!("{}{}", date.day(), ordinal_suffix(date.day()));
+        if style == Some("US") {
+            format!("{} {}, %Y", month, day)
+        } else {
+            format!("{} {} %Y", day, month)
+        }
+    } else {
+        format!("%d {} %Y", month)
+    };
+
+    let names = locale_names(runtime);
+    Ok(Value::scalar(strftime(&date, &format, names.as_ref())))
+}
 
 /// DateToString filter implementation
 #[derive(Debug, Clone)]
-pub struct DateToStringFilter;
+pub struct DateToStringFilter {
+    args: Vec<Expression>,
+}
 
 impl fmt::Display for DateToStringFilter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -15,31 +59,8 @@ impl fmt::Display for DateToStringFilter {
 }
 
 impl liquid_core::Filter for DateToStringFilter {
-    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> LiquidResult<Value> {
-        let date_str = input.to_kstr().to_string();
-        
-        // Try to parse the date in various formats
-        let date = if date_str.len() >= 10 {
-            // Try ISO 8601 format (YYYY-MM-DD)
-            let iso_date = NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%d");
-            if let Ok(date) = iso_date {
-                Some(date)
-            } else {
-                // Try Jekyll date format (YYYY-MM-DD HH:MM:SS)
-                let jekyll_date = NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%d %H:%M:%S");
-                jekyll_date.ok()
-            }
-        } else {
-            None
-        };
-        
-        if let Some(dt) = date {
-            let formatted = Utc.from_utc_datetime(&dt).format("%d %b %Y").to_string();
-            Ok(Value::scalar(formatted))
-        } else {
-            // If can't parse, return the original string
-            Ok(Value::scalar(date_str))
-        }
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        stringify_date(input, "%b", &self.args, runtime)
     }
 }
 
@@ -66,11 +87,12 @@ impl FilterReflection for DateToStringFilterParser {
 }
 
 impl ParseFilter for DateToStringFilterParser {
-    fn parse(&self, _args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
-        Ok(Box::new(DateToStringFilter))
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let args = arguments(args, "date_to_string", 0, 2)?;
+        Ok(Box::new(DateToStringFilter { args }))
     }
     
     fn reflection(&self) -> &dyn FilterReflection {
         self
     }
-} 
\ No newline at end of file
+} 
@@ -2,7 +2,8 @@ use std::fmt;
 use liquid_core::{Runtime, ValueView, Value, Result as LiquidResult};
 use liquid_core::parser::{FilterArguments, ParseFilter};
 use liquid_core::FilterReflection;
-use chrono::{DateTime, Utc, NaiveDateTime, NaiveDate};
+use crate::utils::date::strftime;
+use super::date::input_date;
 
 /// DateToXmlSchema filter implementation
 #[derive(Debug, Clone)]
@@ -16,25 +17,11 @@ impl fmt::Display for DateToXmlSchemaFilter {
 
 impl liquid_core::Filter for DateToXmlSchemaFilter {
     fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> LiquidResult<Value> {
-        let date_str = input.to_kstr().to_string();
-
-        // Try various date formats
-        let date = if let Ok(dt) = DateTime::parse_from_rfc3339(&date_str) {
-            dt.with_timezone(&Utc)
-        } else if let Ok(dt) = DateTime::parse_from_rfc2822(&date_str) {
-            dt.with_timezone(&Utc)
-        } else if let Ok(dt) = NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%d %H:%M:%S") {
-            dt.and_utc()
-        } else if let Ok(dt) = NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
-            // For date only, create a datetime at midnight
-            dt.and_hms_opt(0, 0, 0).unwrap().and_utc()
-        } else {
-            // Default to current time if we can't parse
-            Utc::now()
-        };
-
-        // Format as XML Schema (ISO 8601)
-        Ok(Value::scalar(date.to_rfc3339()))
+        // Format as XML Schema (ISO 8601) in the site's timezone
+        match input_date(input)? {
+            Some(date) => Ok(Value::scalar(strftime(&date, "%Y-%m-%dT%H:%M:%S%:z", None))),
+            None => Ok(input.to_value()),
+        }
     }
 }
 
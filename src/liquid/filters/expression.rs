@@ -0,0 +1,289 @@
+use std::fmt;
+
+use liquid_core::model::{try_find, State, ValueCow, ValueViewCmp};
+use liquid_core::parser::parse_variable;
+use liquid_core::runtime::Variable;
+use liquid_core::{Runtime, Value, ValueView};
+
+/// Characters that make up comparison operators
+const OPERATOR_CHARS: &str = "=!<>";
+
+/// A token of an expression
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'s> {
+    /// A quoted string, without the quotes
+    Quoted(&'s str),
+    /// A comparison operator such as `==`
+    Operator(&'s str),
+    /// A keyword, number or variable
+    Word(&'s str),
+}
+
+impl fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Quoted(text) => write!(f, "\"{}\"", text),
+            Token::Operator(text) | Token::Word(text) => write!(f, "'{}'", text),
+        }
+    }
+}
+
+/// Split an expression into tokens
+fn tokenize(text: &str) -> Result<Vec<Token<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let end = loop {
+                match chars.next() {
+                    Some((i, ch)) if ch == c => break i,
+                    Some(_) => {}
+                    None => return Err(format!("Unterminated string in expression \"{}\"", text)),
+                }
+            };
+            tokens.push(Token::Quoted(&text[start + 1..end]));
+        } else if OPERATOR_CHARS.contains(c) {
+            let mut end = start;
+            while let Some(&(i, ch)) = chars.peek() {
+                if !OPERATOR_CHARS.contains(ch) {
+                    break;
+                }
+                end = i + ch.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Operator(&text[start..end]));
+        } else {
+            // Variables may index with quoted keys, e.g. post["my key"]
+            let mut end = text.len();
+            let mut depth = 0usize;
+            let mut quote = None;
+            while let Some(&(i, ch)) = chars.peek() {
+                match quote {
+                    Some(q) if ch == q => quote = None,
+                    Some(_) => {}
+                    None => match ch {
+                        '"' | '\'' if depth > 0 => quote = Some(ch),
+                        '[' => depth += 1,
+                        ']' => depth = depth.saturating_sub(1),
+                        _ if depth == 0 && (ch.is_whitespace() || OPERATOR_CHARS.contains(ch)) => {
+                            end = i;
+                            break;
+                        }
+                        _ => {}
+                    },
+                }
+                chars.next();
+            }
+            tokens.push(Token::Word(&text[start..end]));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// A literal or a variable in an expression
+#[derive(Debug, Clone)]
+pub enum Operand {
+    Literal(Value),
+    Variable(Variable),
+}
+
+impl Operand {
+    /// Parse a single literal or variable, e.g. `post.tags[0]` or `"draft"`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let tokens = tokenize(text)?;
+        match tokens.as_slice() {
+            [token] => Self::from_token(Some(*token), text),
+            [] => Err("Expected a value but the expression is empty".to_string()),
+            [_, token, ..] => Err(format!("Unexpected {} in expression \"{}\"", token, text)),
+        }
+    }
+
+    fn from_token(token: Option<Token<'_>>, text: &str) -> Result<Self, String> {
+        let word = match token {
+            Some(Token::Quoted(value)) => return Ok(Operand::Literal(Value::scalar(value.to_string()))),
+            Some(Token::Word(word)) => word,
+            Some(token) => return Err(format!("Expected a value but found {} in expression \"{}\"", token, text)),
+            None => return Err(format!("Expected a value at the end of expression \"{}\"", text)),
+        };
+
+        let literal = match word {
+            "true" => Value::scalar(true),
+            "false" => Value::scalar(false),
+            "nil" | "null" => Value::Nil,
+            "empty" => Value::State(State::Empty),
+            "blank" => Value::State(State::Blank),
+            _ => {
+                if let Ok(number) = word.parse::<i64>() {
+                    Value::scalar(number)
+                } else if let Some(number) = word.parse::<f64>().ok().filter(|_| word.starts_with(|c: char| c.is_ascii_digit() || c == '-')) {
+                    Value::scalar(number)
+                } else {
+                    return parse_variable(word)
+                        .map(Operand::Variable)
+                        .map_err(|_| format!("Invalid variable '{}' in expression \"{}\"", word, text));
+                }
+            }
+        };
+
+        Ok(Operand::Literal(literal))
+    }
+
+    /// Look up the operand, with `variable` bound to `item`
+    ///
+    /// Other variables are looked up in the template's scope.
+    pub fn evaluate<'a>(&'a self, variable: &str, item: &'a dyn ValueView, runtime: &'a dyn Runtime) -> Option<ValueCow<'a>> {
+        match self {
+            Operand::Literal(value) => Some(ValueCow::Borrowed(value)),
+            Operand::Variable(var) => {
+                let path = var.try_evaluate(runtime)?;
+                let (root, rest) = path.split_first()?;
+                if root.to_kstr().as_str() == variable {
+                    try_find(item, rest)
+                } else {
+                    runtime.try_get(&path)
+                }
+            }
+        }
+    }
+}
+
+/// Comparison operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Equals,
+    NotEquals,
+    Less,
+    Greater,
+    LessOrEqual,
+    GreaterOrEqual,
+    Contains,
+}
+
+impl Operator {
+    fn from_token(token: Token<'_>) -> Option<Self> {
+        match token {
+            Token::Operator("==") => Some(Operator::Equals),
+            Token::Operator("!=") | Token::Operator("<>") => Some(Operator::NotEquals),
+            Token::Operator("<") => Some(Operator::Less),
+            Token::Operator(">") => Some(Operator::Greater),
+            Token::Operator("<=") => Some(Operator::LessOrEqual),
+            Token::Operator(">=") => Some(Operator::GreaterOrEqual),
+            Token::Word("contains") => Some(Operator::Contains),
+            _ => None,
+        }
+    }
+
+    fn compare(self, left: &dyn ValueView, right: &dyn ValueView) -> bool {
+        let (l, r) = (ValueViewCmp::new(left), ValueViewCmp::new(right));
+        match self {
+            Operator::Equals => l == r,
+            Operator::NotEquals => l != r,
+            Operator::Less => l < r,
+            Operator::Greater => l > r,
+            Operator::LessOrEqual => l <= r,
+            Operator::GreaterOrEqual => l >= r,
+            Operator::Contains => contains(left, right),
+        }
+    }
+}
+
+/// Liquid's `contains`: substrings, array elements and object keys
+fn contains(left: &dyn ValueView, right: &dyn ValueView) -> bool {
+    if let Some(array) = left.as_array() {
+        array.values().any(|value| ValueViewCmp::new(value) == ValueViewCmp::new(right))
+    } else if let Some(object) = left.as_object() {
+        object.contains_key(right.to_kstr().as_str())
+    } else if left.is_scalar() {
+        left.to_kstr().contains(right.to_kstr().as_str())
+    } else {
+        false
+    }
+}
+
+/// How a condition is combined with the next one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Logic {
+    And,
+    Or,
+}
+
+/// A condition in Jekyll's expression grammar, as used by `where_exp` and `find_exp`
+///
+/// Conditions are comparisons such as `post.layout == "post"`, or a single
+/// value that is tested for truthiness. Like in Liquid's `if` tag, they can
+/// be chained with `and` and `or`, which have no precedence and are
+/// evaluated from right to left.
+#[derive(Debug, Clone)]
+pub struct Condition {
+    left: Operand,
+    comparison: Option<(Operator, Operand)>,
+    next: Option<(Logic, Box<Condition>)>,
+}
+
+impl Condition {
+    /// Parse a condition, e.g. `item.year > 2020 and item.tags contains "rust"`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let tokens = tokenize(text)?;
+        let mut tokens = tokens.into_iter().peekable();
+        let condition = Self::parse_tokens(&mut tokens, text)?;
+
+        match tokens.next() {
+            None => Ok(condition),
+            Some(token) => Err(format!("Unexpected {} in expression \"{}\"", token, text)),
+        }
+    }
+
+    fn parse_tokens<'s>(tokens: &mut std::iter::Peekable<std::vec::IntoIter<Token<'s>>>, text: &str) -> Result<Self, String> {
+        let left = Operand::from_token(tokens.next(), text)?;
+
+        let comparison = match tokens.peek().and_then(|token| Operator::from_token(*token)) {
+            Some(operator) => {
+                tokens.next();
+                Some((operator, Operand::from_token(tokens.next(), text)?))
+            }
+            None => None,
+        };
+
+        let logic = match tokens.peek() {
+            Some(Token::Word("and")) => Some(Logic::And),
+            Some(Token::Word("or")) => Some(Logic::Or),
+            _ => None,
+        };
+        let next = match logic {
+            Some(logic) => {
+                tokens.next();
+                Some((logic, Box::new(Self::parse_tokens(tokens, text)?)))
+            }
+            None => None,
+        };
+
+        Ok(Self { left, comparison, next })
+    }
+
+    /// Evaluate the condition for one item, bound to `variable`
+    pub fn evaluate(&self, variable: &str, item: &dyn ValueView, runtime: &dyn Runtime) -> bool {
+        let nil = Value::Nil;
+        let left = self.left.evaluate(variable, item, runtime);
+        let left = left.as_ref().map_or(&nil as &dyn ValueView, |value| value.as_view());
+
+        let result = match &self.comparison {
+            Some((operator, right)) => {
+                let right = right.evaluate(variable, item, runtime);
+                let right = right.as_ref().map_or(&nil as &dyn ValueView, |value| value.as_view());
+                operator.compare(left, right)
+            }
+            None => left.query_state(State::Truthy),
+        };
+
+        match &self.next {
+            Some((Logic::And, next)) => result && next.evaluate(variable, item, runtime),
+            Some((Logic::Or, next)) => result || next.evaluate(variable, item, runtime),
+            None => result,
+        }
+    }
+}
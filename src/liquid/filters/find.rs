@@ -0,0 +1,70 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::array::items;
+use super::r#where::WhereFilter;
+
+/// Find filter implementation, the first item `where` would select
+#[derive(Debug)]
+pub struct FindFilter {
+    inner: WhereFilter,
+}
+
+impl fmt::Display for FindFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "find")
+    }
+}
+
+impl liquid_core::Filter for FindFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        match items(input) {
+            Some(items) if !self.inner.ignores_target(runtime)? => {
+                let found = self.inner.matching(items, runtime)?.into_iter().next();
+                Ok(found.map_or(Value::Nil, |item| item.to_value()))
+            }
+            _ => Ok(input.to_value()),
+        }
+    }
+}
+
+/// Parse filter factory for find
+#[derive(Debug, Clone)]
+pub struct FindFilterParser;
+
+impl FilterReflection for FindFilterParser {
+    fn name(&self) -> &str {
+        "find"
+    }
+
+    fn description(&self) -> &str {
+        "Returns the first item whose property matches a value"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for FindFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "find", 2, 2)?.into_iter();
+        Ok(Box::new(FindFilter {
+            inner: WhereFilter {
+                property: arguments.next().unwrap(),
+                target: arguments.next(),
+            },
+        }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::array::items;
+use super::where_exp::WhereExpFilter;
+
+/// FindExp filter implementation, the first item `where_exp` would select
+#[derive(Debug)]
+pub struct FindExpFilter {
+    inner: WhereExpFilter,
+}
+
+impl fmt::Display for FindExpFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "find_exp")
+    }
+}
+
+impl liquid_core::Filter for FindExpFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        match items(input) {
+            Some(items) => {
+                let found = self.inner.matching(items, runtime)?.into_iter().next();
+                Ok(found.map_or(Value::Nil, |item| item.to_value()))
+            }
+            None => Ok(input.to_value()),
+        }
+    }
+}
+
+/// Parse filter factory for find_exp
+#[derive(Debug, Clone)]
+pub struct FindExpFilterParser;
+
+impl FilterReflection for FindExpFilterParser {
+    fn name(&self) -> &str {
+        "find_exp"
+    }
+
+    fn description(&self) -> &str {
+        "Returns the first item for which an expression is true"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for FindExpFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "find_exp", 2, 2)?.into_iter();
+        Ok(Box::new(FindExpFilter {
+            inner: WhereExpFilter {
+                variable: arguments.next().unwrap(),
+                expression: arguments.next().unwrap(),
+            },
+        }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::array::{add_to_group, grouped, items, property};
+
+/// GroupBy filter implementation
+#[derive(Debug)]
+pub struct GroupByFilter {
+    property: Expression,
+}
+
+impl fmt::Display for GroupByFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "group_by")
+    }
+}
+
+impl liquid_core::Filter for GroupByFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let items = match items(input) {
+            Some(items) => items,
+            None => return Ok(input.to_value()),
+        };
+        let path = self.property.evaluate(runtime)?.to_kstr().to_string();
+
+        let mut groups = Vec::new();
+        let mut index = HashMap::new();
+        for item in items {
+            let name = property(item, &path).map_or_else(String::new, |value| value.to_kstr().to_string());
+            add_to_group(&mut groups, &mut index, Value::scalar(name), item.to_value());
+        }
+
+        Ok(grouped(groups))
+    }
+}
+
+/// Parse filter factory for group_by
+#[derive(Debug, Clone)]
+pub struct GroupByFilterParser;
+
+impl FilterReflection for GroupByFilterParser {
+    fn name(&self) -> &str {
+        "group_by"
+    }
+
+    fn description(&self) -> &str {
+        "Groups items by the value of a property"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for GroupByFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "group_by", 1, 1)?.into_iter();
+        Ok(Box::new(GroupByFilter {
+            property: arguments.next().unwrap(),
+        }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+use liquid_core::parser::{FilterArguments, Language, ParameterReflection, ParseFilter};
+use liquid_core::partials::{EagerCompiler, InMemorySource, PartialCompiler, PartialSource};
+use liquid_core::runtime::{Expression, PartialStore, Renderable, StackFrame, Template};
+use liquid_core::{Error, FilterReflection, Object, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::array::{add_to_group, exp_arguments, grouped, items};
+use super::expression::Operand;
+
+/// Name the value of a `group_by_exp` expression is bound to when applying its filters
+const GROUP_VALUE: &str = "group_value";
+
+/// Split a `group_by_exp` expression into its value and its filters, e.g.
+/// `post.date | date: "%Y"` into `post.date` and `| date: "%Y"`
+fn split_filters(text: &str) -> (&str, Option<&str>) {
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '|' => return (text[..i].trim(), Some(&text[i..])),
+            None => {}
+        }
+    }
+    (text.trim(), None)
+}
+
+/// The filter chains of `group_by_exp` expressions, parsed with the language
+/// of the parser the filter is registered with
+///
+/// The language is only held weakly, as it owns the filter.
+#[derive(Default)]
+pub struct FilterChains {
+    language: OnceLock<Weak<Language>>,
+    templates: Mutex<HashMap<String, Arc<Template>>>,
+}
+
+impl fmt::Debug for FilterChains {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FilterChains")
+    }
+}
+
+impl FilterChains {
+    fn get(&self, filters: &str) -> LiquidResult<Arc<Template>> {
+        let mut templates = self.templates.lock().unwrap();
+        if let Some(template) = templates.get(filters) {
+            return Ok(template.clone());
+        }
+
+        let language = self.language.get().and_then(Weak::upgrade)
+            .ok_or_else(|| Error::with_msg("group_by_exp can only apply filters while its parser is alive"))?;
+        let text = format!("{{{{ {} {} }}}}", GROUP_VALUE, filters);
+        let template = Arc::new(Template::new(liquid_core::parser::parse(&text, &language)?));
+        templates.insert(filters.to_string(), template.clone());
+        Ok(template)
+    }
+}
+
+/// Partials compiler handing the parser's language to `group_by_exp`
+///
+/// Liquid only shares the language of a parser with its partials, so this
+/// is how filter chains get parsed with the filters of the same parser.
+/// There are no partials, as includes are handled by our own tags.
+pub struct LanguagePartials {
+    chains: Arc<FilterChains>,
+    partials: EagerCompiler<InMemorySource>,
+}
+
+impl LanguagePartials {
+    pub fn new(chains: Arc<FilterChains>) -> Self {
+        LanguagePartials { chains, partials: EagerCompiler::default() }
+    }
+}
+
+impl PartialCompiler for LanguagePartials {
+    fn compile(self, language: Arc<Language>) -> LiquidResult<Box<dyn PartialStore + Send + Sync>> {
+        let _ = self.chains.language.set(Arc::downgrade(&language));
+        self.partials.compile(language)
+    }
+
+    fn source(&self) -> &dyn PartialSource {
+        self.partials.source()
+    }
+}
+
+/// GroupByExp filter implementation
+#[derive(Debug)]
+pub struct GroupByExpFilter {
+    variable: Expression,
+    expression: Expression,
+    chains: Arc<FilterChains>,
+}
+
+impl fmt::Display for GroupByExpFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "group_by_exp")
+    }
+}
+
+impl liquid_core::Filter for GroupByExpFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let items = match items(input) {
+            Some(items) => items,
+            None => return Ok(input.to_value()),
+        };
+        let (variable, expression) = exp_arguments(&self.variable, &self.expression, runtime)?;
+        let (value, filters) = split_filters(&expression);
+        let value = Operand::parse(value).map_err(Error::with_msg)?;
+        let template = match filters {
+            Some(filters) => Some(self.chains.get(filters)?),
+            None => None,
+        };
+
+        let mut groups = Vec::new();
+        let mut index = HashMap::new();
+        for item in items {
+            let name = value.evaluate(&variable, item, runtime).map_or(Value::Nil, |name| name.to_value());
+            let name = match &template {
+                Some(template) => {
+                    let mut scope = Object::new();
+                    scope.insert(GROUP_VALUE.into(), name);
+                    Value::scalar(template.render(&StackFrame::new(runtime, scope))?)
+                }
+                None => name,
+            };
+            add_to_group(&mut groups, &mut index, name, item.to_value());
+        }
+
+        Ok(grouped(groups))
+    }
+}
+
+/// Parse filter factory for group_by_exp
+#[derive(Debug, Clone)]
+pub struct GroupByExpFilterParser {
+    pub chains: Arc<FilterChains>,
+}
+
+impl FilterReflection for GroupByExpFilterParser {
+    fn name(&self) -> &str {
+        "group_by_exp"
+    }
+
+    fn description(&self) -> &str {
+        "Groups items by the value of an expression"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for GroupByExpFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "group_by_exp", 2, 2)?.into_iter();
+        Ok(Box::new(GroupByExpFilter {
+            variable: arguments.next().unwrap(),
+            expression: arguments.next().unwrap(),
+            chains: self.chains.clone(),
+        }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
@@ -0,0 +1,126 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::string::{format_float, xml_escape};
+
+/// Describe a value the way Ruby's `inspect` does
+fn write_inspect(value: &dyn ValueView, text: &mut String) {
+    if let Some(array) = value.as_array() {
+        text.push('[');
+        for (i, item) in array.values().enumerate() {
+            if i > 0 {
+                text.push_str(", ");
+            }
+            write_inspect(item, text);
+        }
+        text.push(']');
+    } else if let Some(object) = value.as_object() {
+        text.push('{');
+        for (i, (key, item)) in object.iter().enumerate() {
+            if i > 0 {
+                text.push_str(", ");
+            }
+            text.push_str(&inspect_string(key.as_str()));
+            text.push_str("=>");
+            write_inspect(item, text);
+        }
+        text.push('}');
+    } else if let Some(scalar) = value.as_scalar() {
+        match value.type_name() {
+            "string" => text.push_str(&inspect_string(&scalar.to_kstr())),
+            "fractional number" => text.push_str(&scalar.to_float().map(format_float).unwrap_or_default()),
+            _ => text.push_str(&scalar.to_kstr()),
+        }
+    } else {
+        text.push_str("nil");
+    }
+}
+
+fn inspect_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            '#' if matches!(chars.peek(), Some('{') | Some('$') | Some('@')) => quoted.push_str("\\#"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Inspect filter implementation
+#[derive(Debug)]
+pub struct InspectFilter;
+
+impl fmt::Display for InspectFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "inspect")
+    }
+}
+
+impl liquid_core::Filter for InspectFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let mut text = String::new();
+        write_inspect(input, &mut text);
+        Ok(Value::scalar(xml_escape(&text)))
+    }
+}
+
+/// Parse filter factory for inspect
+#[derive(Debug, Clone)]
+pub struct InspectFilterParser;
+
+impl FilterReflection for InspectFilterParser {
+    fn name(&self) -> &str {
+        "inspect"
+    }
+
+    fn description(&self) -> &str {
+        "Describes a value for debugging, escaped for HTML"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for InspectFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        arguments(args, "inspect", 0, 0)?;
+        Ok(Box::new(InspectFilter))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspect() {
+        let mut object = liquid_core::Object::new();
+        object.insert("title".into(), Value::scalar("A \"quoted\" title"));
+        let value = Value::Array(vec![Value::Object(object), Value::scalar(1.0), Value::scalar(2i64), Value::Nil]);
+
+        let mut text = String::new();
+        write_inspect(&value, &mut text);
+        assert_eq!(text, r#"[{"title"=>"A \"quoted\" title"}, 1.0, 2, nil]"#);
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::string::format_float;
+
+/// Serialize a value as compact JSON like Ruby's `to_json`
+fn write_json(value: &dyn ValueView, json: &mut String) {
+    if let Some(array) = value.as_array() {
+        json.push('[');
+        for (i, item) in array.values().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write_json(item, json);
+        }
+        json.push(']');
+    } else if let Some(object) = value.as_object() {
+        json.push('{');
+        for (i, (key, item)) in object.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            json.push_str(&serde_json::to_string(key.as_str()).unwrap_or_default());
+            json.push(':');
+            write_json(item, json);
+        }
+        json.push('}');
+    } else if let Some(scalar) = value.as_scalar() {
+        match value.type_name() {
+            "whole number" | "boolean" => json.push_str(&scalar.to_kstr()),
+            "fractional number" => match scalar.to_float().filter(|number| number.is_finite()) {
+                Some(number) => json.push_str(&format_float(number)),
+                None => json.push_str("null"),
+            },
+            _ => json.push_str(&serde_json::to_string(scalar.to_kstr().as_str()).unwrap_or_default()),
+        }
+    } else {
+        json.push_str("null");
+    }
+}
+
+/// Jsonify filter implementation
+#[derive(Debug)]
+pub struct JsonifyFilter;
+
+impl fmt::Display for JsonifyFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "jsonify")
+    }
+}
+
+impl liquid_core::Filter for JsonifyFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let mut json = String::new();
+        write_json(input, &mut json);
+        Ok(Value::scalar(json))
+    }
+}
+
+/// Parse filter factory for jsonify
+#[derive(Debug, Clone)]
+pub struct JsonifyFilterParser;
+
+impl FilterReflection for JsonifyFilterParser {
+    fn name(&self) -> &str {
+        "jsonify"
+    }
+
+    fn description(&self) -> &str {
+        "Converts a value into JSON"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for JsonifyFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        arguments(args, "jsonify", 0, 0)?;
+        Ok(Box::new(JsonifyFilter))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsonify() {
+        let mut object = liquid_core::Object::new();
+        object.insert("title".into(), Value::scalar("A \"quoted\" title"));
+        let value = Value::Array(vec![Value::Object(object), Value::scalar(1.0), Value::scalar(2i64), Value::Nil]);
+
+        let mut json = String::new();
+        write_json(&value, &mut json);
+        assert_eq!(json, r#"[{"title":"A \"quoted\" title"},1.0,2,null]"#);
+    }
+}
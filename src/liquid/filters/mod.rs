@@ -2,15 +2,52 @@ mod markdownify;
 mod relative_url;
 mod absolute_url;
 mod date_to_string;
+mod date_to_long_string;
+mod date_to_rfc822;
 mod date_to_xmlschema;
 mod date;
 mod number_with_delimiter;
+mod array;
+mod expression;
+mod r#where;
+mod where_exp;
+mod find;
+mod find_exp;
+mod group_by;
+mod group_by_exp;
+mod sort;
+mod sample;
+mod push;
+mod pop;
+mod shift;
+mod unshift;
+mod string;
+mod slugify;
+mod jsonify;
+mod xml_escape;
+mod cgi_escape;
+mod uri_escape;
+mod number_of_words;
+mod array_to_sentence_string;
+mod normalize_whitespace;
+mod smartify;
+mod inspect;
+
+use std::sync::Arc;
 
 use liquid::ParserBuilder;
+use liquid_core::runtime::Expression;
+use liquid_core::parser::FilterArguments;
+use liquid_core::{Error, Result as LiquidResult};
 use crate::config::Config;
 
+pub use group_by_exp::LanguagePartials;
+
 /// Register custom filters for use in Liquid templates
-pub fn register_filters(parser_builder: ParserBuilder, config: &Config) -> ParserBuilder {
+///
+/// This also sets the partials compiler, which is how `group_by_exp` gets
+/// hold of the language of the parser it is registered with.
+pub fn register_filters(parser_builder: ParserBuilder, config: &Config) -> ParserBuilder<LanguagePartials> {
     // Add markdownify filter
     let parser_builder = parser_builder
         .filter(markdownify::MarkdownifyFilterParser);
@@ -32,6 +69,14 @@ pub fn register_filters(parser_builder: ParserBuilder, config: &Config) -> Parse
     let parser_builder = parser_builder
         .filter(date_to_string::DateToStringFilterParser);
 
+    // Add date_to_long_string filter
+    let parser_builder = parser_builder
+        .filter(date_to_long_string::DateToLongStringFilterParser);
+
+    // Add date_to_rfc822 filter
+    let parser_builder = parser_builder
+        .filter(date_to_rfc822::DateToRfc822FilterParser);
+
     // Add date_to_xmlschema filter
     let parser_builder = parser_builder
         .filter(date_to_xmlschema::DateToXmlSchemaFilterParser);
@@ -44,7 +89,45 @@ pub fn register_filters(parser_builder: ParserBuilder, config: &Config) -> Parse
     let parser_builder = parser_builder
         .filter(number_with_delimiter::NumberWithDelimiterFilterParser);
 
-    parser_builder
+    // Add Jekyll array filters, replacing Liquid's where and sort
+    let chains = Arc::new(group_by_exp::FilterChains::default());
+    let parser_builder = parser_builder
+        .filter(r#where::WhereFilterParser)
+        .filter(where_exp::WhereExpFilterParser)
+        .filter(find::FindFilterParser)
+        .filter(find_exp::FindExpFilterParser)
+        .filter(group_by::GroupByFilterParser)
+        .filter(group_by_exp::GroupByExpFilterParser { chains: chains.clone() })
+        .filter(sort::SortFilterParser)
+        .filter(sample::SampleFilterParser)
+        .filter(push::PushFilterParser)
+        .filter(pop::PopFilterParser)
+        .filter(shift::ShiftFilterParser)
+        .filter(unshift::UnshiftFilterParser);
+
+    // Add Jekyll string and utility filters
+    let parser_builder = parser_builder
+        .filter(slugify::SlugifyFilterParser)
+        .filter(jsonify::JsonifyFilterParser)
+        .filter(xml_escape::XmlEscapeFilterParser)
+        .filter(cgi_escape::CgiEscapeFilterParser)
+        .filter(uri_escape::UriEscapeFilterParser)
+        .filter(number_of_words::NumberOfWordsFilterParser)
+        .filter(array_to_sentence_string::ArrayToSentenceStringFilterParser)
+        .filter(normalize_whitespace::NormalizeWhitespaceFilterParser)
+        .filter(smartify::SmartifyFilterParser)
+        .filter(inspect::InspectFilterParser);
+
+    // Let group_by_exp parse its filter chains with the parser being built
+    parser_builder.partials(LanguagePartials::new(chains))
 }
 
- 
\ No newline at end of file
+/// Collect the positional arguments of a filter, checking their number
+fn arguments(args: FilterArguments, filter: &str, min: usize, max: usize) -> LiquidResult<Vec<Expression>> {
+    let arguments: Vec<Expression> = args.positional.collect();
+    if arguments.len() < min || arguments.len() > max {
+        let expected = if min == max { min.to_string() } else { format!("{} to {}", min, max) };
+        return Err(Error::with_msg(format!("{} expects {} arguments, got {}", filter, expected, arguments.len())));
+    }
+    Ok(arguments)
+}
@@ -0,0 +1,63 @@
+use std::fmt;
+
+use lazy_static::lazy_static;
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+use regex::Regex;
+
+use super::arguments;
+use super::string::input_string;
+
+lazy_static! {
+    static ref WHITESPACE_REGEX: Regex = Regex::new(r"\s+").unwrap();
+}
+
+/// NormalizeWhitespace filter implementation
+#[derive(Debug)]
+pub struct NormalizeWhitespaceFilter;
+
+impl fmt::Display for NormalizeWhitespaceFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "normalize_whitespace")
+    }
+}
+
+impl liquid_core::Filter for NormalizeWhitespaceFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let text = input_string(input);
+        Ok(Value::scalar(WHITESPACE_REGEX.replace_all(&text, " ").trim().to_string()))
+    }
+}
+
+/// Parse filter factory for normalize_whitespace
+#[derive(Debug, Clone)]
+pub struct NormalizeWhitespaceFilterParser;
+
+impl FilterReflection for NormalizeWhitespaceFilterParser {
+    fn name(&self) -> &str {
+        "normalize_whitespace"
+    }
+
+    fn description(&self) -> &str {
+        "Replaces each run of whitespace with a single space"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for NormalizeWhitespaceFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        arguments(args, "normalize_whitespace", 0, 0)?;
+        Ok(Box::new(NormalizeWhitespaceFilter))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
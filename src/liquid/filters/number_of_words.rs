@@ -0,0 +1,96 @@
+use std::fmt;
+
+use lazy_static::lazy_static;
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+use regex::Regex;
+
+use super::arguments;
+use super::string::{input_string, optional_string};
+
+lazy_static! {
+    // Characters of Chinese, Japanese and Korean text, which Jekyll counts as one word each
+    static ref CJK_REGEX: Regex = Regex::new(r"[\p{Han}\p{Katakana}\p{Hiragana}\p{Hangul}]").unwrap();
+    static ref NON_CJK_WORD_REGEX: Regex = Regex::new(r"[^\p{Han}\p{Katakana}\p{Hiragana}\p{Hangul}\s]+").unwrap();
+}
+
+/// Count words like Jekyll's `number_of_words`
+///
+/// In `cjk` mode every Chinese, Japanese and Korean character counts as a
+/// word, and `auto` does so only when the text contains such characters.
+fn number_of_words(text: &str, mode: Option<&str>) -> usize {
+    let cjk_words = || CJK_REGEX.find_iter(text).count() + NON_CJK_WORD_REGEX.find_iter(text).count();
+    match mode {
+        Some("cjk") => cjk_words(),
+        Some("auto") if CJK_REGEX.is_match(text) => cjk_words(),
+        _ => text.split_whitespace().count(),
+    }
+}
+
+/// NumberOfWords filter implementation
+#[derive(Debug)]
+pub struct NumberOfWordsFilter {
+    mode: Option<Expression>,
+}
+
+impl fmt::Display for NumberOfWordsFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "number_of_words")
+    }
+}
+
+impl liquid_core::Filter for NumberOfWordsFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let mode = optional_string(&self.mode, runtime)?;
+        let count = number_of_words(&input_string(input), mode.as_deref());
+        Ok(Value::scalar(count as i64))
+    }
+}
+
+/// Parse filter factory for number_of_words
+#[derive(Debug, Clone)]
+pub struct NumberOfWordsFilterParser;
+
+impl FilterReflection for NumberOfWordsFilterParser {
+    fn name(&self) -> &str {
+        "number_of_words"
+    }
+
+    fn description(&self) -> &str {
+        "Counts the words in a string"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for NumberOfWordsFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "number_of_words", 0, 1)?.into_iter();
+        Ok(Box::new(NumberOfWordsFilter { mode: arguments.next() }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_of_words() {
+        assert_eq!(number_of_words("Hello  world, again", None), 3);
+        assert_eq!(number_of_words("你好世界 hello", None), 2);
+        assert_eq!(number_of_words("你好世界 hello", Some("cjk")), 5);
+        assert_eq!(number_of_words("你好世界 hello", Some("auto")), 5);
+        assert_eq!(number_of_words("hello world", Some("auto")), 2);
+    }
+}
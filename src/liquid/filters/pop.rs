@@ -0,0 +1,67 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::array::count_argument;
+
+/// Pop filter implementation, removing items from the end of a copy of an array
+#[derive(Debug)]
+pub struct PopFilter {
+    count: Option<Expression>,
+}
+
+impl fmt::Display for PopFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pop")
+    }
+}
+
+impl liquid_core::Filter for PopFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let array = match input.as_array() {
+            Some(array) => array,
+            None => return Ok(input.to_value()),
+        };
+        let count = count_argument(&self.count, runtime)?;
+
+        let mut items: Vec<Value> = array.values().map(|value| value.to_value()).collect();
+        items.truncate(items.len().saturating_sub(count));
+        Ok(Value::Array(items))
+    }
+}
+
+/// Parse filter factory for pop
+#[derive(Debug, Clone)]
+pub struct PopFilterParser;
+
+impl FilterReflection for PopFilterParser {
+    fn name(&self) -> &str {
+        "pop"
+    }
+
+    fn description(&self) -> &str {
+        "Removes items from the end of an array"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for PopFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "pop", 0, 1)?.into_iter();
+        Ok(Box::new(PopFilter { count: arguments.next() }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+
+/// Push filter implementation, adding an item to the end of a copy of an array
+#[derive(Debug)]
+pub struct PushFilter {
+    item: Expression,
+}
+
+impl fmt::Display for PushFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "push")
+    }
+}
+
+impl liquid_core::Filter for PushFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let array = match input.as_array() {
+            Some(array) => array,
+            None => return Ok(input.to_value()),
+        };
+
+        let mut items: Vec<Value> = array.values().map(|value| value.to_value()).collect();
+        items.push(self.item.evaluate(runtime)?.to_value());
+        Ok(Value::Array(items))
+    }
+}
+
+/// Parse filter factory for push
+#[derive(Debug, Clone)]
+pub struct PushFilterParser;
+
+impl FilterReflection for PushFilterParser {
+    fn name(&self) -> &str {
+        "push"
+    }
+
+    fn description(&self) -> &str {
+        "Adds an item to the end of an array"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for PushFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "push", 1, 1)?.into_iter();
+        Ok(Box::new(PushFilter { item: arguments.next().unwrap() }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
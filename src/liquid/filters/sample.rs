@@ -0,0 +1,90 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::array::{count_argument, to_array};
+
+/// Pick `count` distinct random indices below `len`
+fn random_indices(len: usize, count: usize) -> Vec<usize> {
+    // A fresh hasher state is randomly seeded, which is enough for picking items
+    let mut state = RandomState::new().build_hasher().finish() | 1;
+    let mut indices: Vec<usize> = (0..len).collect();
+    let count = count.min(len);
+
+    for i in 0..count {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let j = i + (state % (len - i) as u64) as usize;
+        indices.swap(i, j);
+    }
+    indices.truncate(count);
+    indices
+}
+
+/// Sample filter implementation
+#[derive(Debug)]
+pub struct SampleFilter {
+    count: Option<Expression>,
+}
+
+impl fmt::Display for SampleFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sample")
+    }
+}
+
+impl liquid_core::Filter for SampleFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let array = match input.as_array() {
+            Some(array) => array,
+            None => return Ok(input.to_value()),
+        };
+        let items: Vec<&dyn ValueView> = array.values().collect();
+
+        // A single item is returned as it is, several as an array
+        if self.count.is_none() {
+            return Ok(random_indices(items.len(), 1).first().map_or(Value::Nil, |&i| items[i].to_value()));
+        }
+        let count = count_argument(&self.count, runtime)?;
+        Ok(to_array(random_indices(items.len(), count).into_iter().map(|i| items[i].to_value())))
+    }
+}
+
+/// Parse filter factory for sample
+#[derive(Debug, Clone)]
+pub struct SampleFilterParser;
+
+impl FilterReflection for SampleFilterParser {
+    fn name(&self) -> &str {
+        "sample"
+    }
+
+    fn description(&self) -> &str {
+        "Picks random items from an array"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for SampleFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "sample", 0, 1)?.into_iter();
+        Ok(Box::new(SampleFilter { count: arguments.next() }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
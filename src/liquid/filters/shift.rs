@@ -0,0 +1,66 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::array::count_argument;
+
+/// Shift filter implementation, removing items from the start of a copy of an array
+#[derive(Debug)]
+pub struct ShiftFilter {
+    count: Option<Expression>,
+}
+
+impl fmt::Display for ShiftFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "shift")
+    }
+}
+
+impl liquid_core::Filter for ShiftFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let array = match input.as_array() {
+            Some(array) => array,
+            None => return Ok(input.to_value()),
+        };
+        let count = count_argument(&self.count, runtime)?;
+
+        let items: Vec<Value> = array.values().skip(count).map(|value| value.to_value()).collect();
+        Ok(Value::Array(items))
+    }
+}
+
+/// Parse filter factory for shift
+#[derive(Debug, Clone)]
+pub struct ShiftFilterParser;
+
+impl FilterReflection for ShiftFilterParser {
+    fn name(&self) -> &str {
+        "shift"
+    }
+
+    fn description(&self) -> &str {
+        "Removes items from the start of an array"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for ShiftFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "shift", 0, 1)?.into_iter();
+        Ok(Box::new(ShiftFilter { count: arguments.next() }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use crate::config::permalink::{slugify_with_mode, SlugifyMode};
+use super::arguments;
+use super::string::optional_string;
+
+/// Slugify filter implementation
+#[derive(Debug)]
+pub struct SlugifyFilter {
+    mode: Option<Expression>,
+}
+
+impl fmt::Display for SlugifyFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "slugify")
+    }
+}
+
+impl liquid_core::Filter for SlugifyFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        if input.is_nil() {
+            return Ok(Value::Nil);
+        }
+        let text = input.to_kstr();
+        let mode = optional_string(&self.mode, runtime)?;
+
+        // Like Jekyll, unknown modes only lowercase the input
+        let slug = match mode.as_deref().map_or(Some(SlugifyMode::Default), SlugifyMode::from_name) {
+            Some(mode) => slugify_with_mode(&text, mode),
+            None => text.to_lowercase(),
+        };
+        Ok(Value::scalar(slug))
+    }
+}
+
+/// Parse filter factory for slugify
+#[derive(Debug, Clone)]
+pub struct SlugifyFilterParser;
+
+impl FilterReflection for SlugifyFilterParser {
+    fn name(&self) -> &str {
+        "slugify"
+    }
+
+    fn description(&self) -> &str {
+        "Converts a string into a lowercase URL slug"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for SlugifyFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "slugify", 0, 1)?.into_iter();
+        Ok(Box::new(SlugifyFilter { mode: arguments.next() }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify_modes() {
+        let text = "The _config.yml file?!  Café";
+        assert_eq!(slugify_with_mode(text, SlugifyMode::Default), "the-config-yml-file-café");
+        assert_eq!(slugify_with_mode(text, SlugifyMode::Raw), "the-_config.yml-file?!-café");
+        assert_eq!(slugify_with_mode(text, SlugifyMode::Pretty), "the-_config.yml-file-!-café");
+        assert_eq!(slugify_with_mode(text, SlugifyMode::Ascii), "the-config-yml-file-caf");
+        assert_eq!(slugify_with_mode(text, SlugifyMode::Latin), "the-config-yml-file-cafe");
+        assert_eq!(slugify_with_mode("Ünïcödé 日本", SlugifyMode::Latin), "unicode");
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::string::input_string;
+
+/// Whether a quote after `previous` opens a quotation
+fn opens_quote(previous: Option<char>) -> bool {
+    previous.is_none_or(|c| c.is_whitespace() || "([{<-\u{2013}\u{2014}".contains(c))
+}
+
+/// Convert quotes, dashes and ellipses to their typographic HTML entities,
+/// leaving HTML tags alone, like Jekyll's SmartyPants converter
+fn smartify(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut smart = String::with_capacity(text.len());
+    let mut in_tag = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let previous = i.checked_sub(1).map(|j| chars[j]);
+        let next = chars.get(i + 1).copied();
+        let repeated = |n: usize| chars[i..].iter().take(n).filter(|&&other| other == c).count() == n;
+
+        let (entity, width) = if in_tag {
+            in_tag = c != '>';
+            (None, 1)
+        } else {
+            match c {
+                '<' if next.is_some_and(|n| n.is_ascii_alphabetic() || n == '/' || n == '!') => {
+                    in_tag = true;
+                    (None, 1)
+                }
+                '-' if repeated(3) => (Some("&mdash;"), 3),
+                '-' if repeated(2) => (Some("&ndash;"), 2),
+                '.' if repeated(3) => (Some("&hellip;"), 3),
+                '<' if repeated(2) => (Some("&laquo;"), 2),
+                '>' if repeated(2) => (Some("&raquo;"), 2),
+                '"' if opens_quote(previous) => (Some("&ldquo;"), 1),
+                '"' => (Some("&rdquo;"), 1),
+                // Abbreviated years such as '80s take an apostrophe
+                '\'' if opens_quote(previous) && !next.is_some_and(|n| n.is_ascii_digit()) => (Some("&lsquo;"), 1),
+                '\'' => (Some("&rsquo;"), 1),
+                _ => (None, 1),
+            }
+        };
+
+        match entity {
+            Some(entity) => smart.push_str(entity),
+            None => smart.push(c),
+        }
+        i += width;
+    }
+
+    smart
+}
+
+/// Smartify filter implementation
+#[derive(Debug)]
+pub struct SmartifyFilter;
+
+impl fmt::Display for SmartifyFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "smartify")
+    }
+}
+
+impl liquid_core::Filter for SmartifyFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> LiquidResult<Value> {
+        Ok(Value::scalar(smartify(&input_string(input))))
+    }
+}
+
+/// Parse filter factory for smartify
+#[derive(Debug, Clone)]
+pub struct SmartifyFilterParser;
+
+impl FilterReflection for SmartifyFilterParser {
+    fn name(&self) -> &str {
+        "smartify"
+    }
+
+    fn description(&self) -> &str {
+        "Converts quotes, dashes and ellipses to typographic characters"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for SmartifyFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        arguments(args, "smartify", 0, 0)?;
+        Ok(Box::new(SmartifyFilter))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smartify() {
+        assert_eq!(smartify(r#"He said "it's 'fine'" -- in the '80s... --- <a href="x">ok</a>"#),
+                   "He said &ldquo;it&rsquo;s &lsquo;fine&rsquo;&rdquo; &ndash; in the &rsquo;80s&hellip; &mdash; <a href=\"x\">ok</a>");
+    }
+}
@@ -0,0 +1,126 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use liquid_core::model::ValueViewCmp;
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{Error, FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::array::{items, property, to_array};
+
+/// Compare values like Jekyll's `sort`, treating numeric strings as numbers
+fn compare_values(a: &dyn ValueView, b: &dyn ValueView) -> Ordering {
+    if let (Some(a), Some(b)) = (sort_number(a), sort_number(b)) {
+        return a.total_cmp(&b);
+    }
+
+    ValueViewCmp::new(a)
+        .partial_cmp(&ValueViewCmp::new(b))
+        .unwrap_or_else(|| a.to_kstr().cmp(&b.to_kstr()))
+}
+
+fn sort_number(value: &dyn ValueView) -> Option<f64> {
+    let scalar = value.as_scalar()?;
+    if value.type_name() == "string" {
+        let text = scalar.to_kstr();
+        let (whole, fraction) = text.split_once('.').unwrap_or((text.as_str(), "0"));
+        let digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        if !digits(whole) || !digits(fraction) {
+            return None;
+        }
+    }
+    scalar.to_float()
+}
+
+/// Sort filter implementation
+#[derive(Debug)]
+pub struct SortFilter {
+    property: Option<Expression>,
+    nils: Option<Expression>,
+}
+
+impl fmt::Display for SortFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sort")
+    }
+}
+
+impl liquid_core::Filter for SortFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        if input.is_nil() {
+            return Err(Error::with_msg("Cannot sort a null object."));
+        }
+        let mut items = match items(input) {
+            Some(items) => items,
+            None => return Ok(input.to_value()),
+        };
+
+        let path = match &self.property {
+            Some(property) => property.evaluate(runtime)?.to_kstr().to_string(),
+            None => {
+                items.sort_by(|a, b| compare_values(*a, *b));
+                return Ok(to_array(items.into_iter().map(|item| item.to_value())));
+            }
+        };
+        let nils = match &self.nils {
+            Some(nils) => nils.evaluate(runtime)?.to_kstr().to_string(),
+            None => "first".to_string(),
+        };
+        // Where items without the property go relative to the others
+        let nil_order = match nils.as_str() {
+            "first" => Ordering::Less,
+            "last" => Ordering::Greater,
+            _ => return Err(Error::with_msg(format!(
+                "Invalid nils order: '{}' is not a valid nils order. It must be 'first' or 'last'.", nils))),
+        };
+
+        let mut keyed: Vec<(Option<&dyn ValueView>, &dyn ValueView)> = items.into_iter()
+            .map(|item| (property(item, &path), item))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) => compare_values(*a, *b),
+            (None, Some(_)) => nil_order,
+            (Some(_), None) => nil_order.reverse(),
+            (None, None) => Ordering::Equal,
+        });
+
+        Ok(to_array(keyed.into_iter().map(|(_, item)| item.to_value())))
+    }
+}
+
+/// Parse filter factory for sort
+#[derive(Debug, Clone)]
+pub struct SortFilterParser;
+
+impl FilterReflection for SortFilterParser {
+    fn name(&self) -> &str {
+        "sort"
+    }
+
+    fn description(&self) -> &str {
+        "Sorts an array, optionally by a property with nils first or last"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for SortFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "sort", 0, 2)?.into_iter();
+        Ok(Box::new(SortFilter {
+            property: arguments.next(),
+            nils: arguments.next(),
+        }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
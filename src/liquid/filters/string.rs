@@ -0,0 +1,61 @@
+//! Helpers shared by the Jekyll string and utility filters
+
+use liquid_core::runtime::Expression;
+use liquid_core::{Result as LiquidResult, Runtime, ValueView};
+
+/// The input as a string, with nil as an empty string
+pub(super) fn input_string(input: &dyn ValueView) -> String {
+    if input.is_nil() {
+        String::new()
+    } else {
+        input.to_kstr().to_string()
+    }
+}
+
+/// Evaluate an optional argument as a string
+pub(super) fn optional_string(argument: &Option<Expression>, runtime: &dyn Runtime) -> LiquidResult<Option<String>> {
+    match argument {
+        Some(argument) => {
+            let value = argument.evaluate(runtime)?;
+            Ok((!value.is_nil()).then(|| value.to_kstr().to_string()))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Escape a string for use in XML and HTML, including attribute values
+pub(super) fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Percent-encode the bytes of a string that `keep` does not accept
+pub(super) fn percent_encode(text: &str, keep: impl Fn(u8) -> bool) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if keep(byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Format a float like Ruby, which always shows a decimal part
+pub(super) fn format_float(number: f64) -> String {
+    if number.is_finite() && number.fract() == 0.0 && number.abs() < 1e16 {
+        format!("{:.1}", number)
+    } else {
+        number.to_string()
+    }
+}
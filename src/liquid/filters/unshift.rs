@@ -0,0 +1,65 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+
+/// Unshift filter implementation, adding an item to the start of a copy of an array
+#[derive(Debug)]
+pub struct UnshiftFilter {
+    item: Expression,
+}
+
+impl fmt::Display for UnshiftFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unshift")
+    }
+}
+
+impl liquid_core::Filter for UnshiftFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        let array = match input.as_array() {
+            Some(array) => array,
+            None => return Ok(input.to_value()),
+        };
+
+        let mut items: Vec<Value> = array.values().map(|value| value.to_value()).collect();
+        items.insert(0, self.item.evaluate(runtime)?.to_value());
+        Ok(Value::Array(items))
+    }
+}
+
+/// Parse filter factory for unshift
+#[derive(Debug, Clone)]
+pub struct UnshiftFilterParser;
+
+impl FilterReflection for UnshiftFilterParser {
+    fn name(&self) -> &str {
+        "unshift"
+    }
+
+    fn description(&self) -> &str {
+        "Adds an item to the start of an array"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for UnshiftFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "unshift", 1, 1)?.into_iter();
+        Ok(Box::new(UnshiftFilter { item: arguments.next().unwrap() }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
@@ -0,0 +1,58 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::string::{input_string, percent_encode};
+
+/// UriEscape filter implementation
+#[derive(Debug)]
+pub struct UriEscapeFilter;
+
+impl fmt::Display for UriEscapeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "uri_escape")
+    }
+}
+
+impl liquid_core::Filter for UriEscapeFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> LiquidResult<Value> {
+        // Reserved characters and existing escapes are kept, like Addressable's normalize_component
+        let escaped = percent_encode(&input_string(input), |b| b.is_ascii_alphanumeric() || b"-._~:/?#[]@!$&'()*+,;=%".contains(&b));
+        Ok(Value::scalar(escaped))
+    }
+}
+
+/// Parse filter factory for uri_escape
+#[derive(Debug, Clone)]
+pub struct UriEscapeFilterParser;
+
+impl FilterReflection for UriEscapeFilterParser {
+    fn name(&self) -> &str {
+        "uri_escape"
+    }
+
+    fn description(&self) -> &str {
+        "Percent-encodes characters that are not allowed in a URI"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for UriEscapeFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        arguments(args, "uri_escape", 0, 0)?;
+        Ok(Box::new(UriEscapeFilter))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
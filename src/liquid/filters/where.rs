@@ -0,0 +1,125 @@
+use std::fmt;
+
+use liquid_core::model::State;
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::array::{items, to_array};
+
+/// Check a property against the target of `where` and `find` like Jekyll
+///
+/// Values are compared as strings. Array properties match if any of their
+/// elements does, `nil` matches missing properties, and `empty` or `blank`
+/// match missing and empty properties.
+fn matches_target(property: Option<&dyn ValueView>, target: &dyn ValueView) -> bool {
+    if target.is_nil() {
+        return property.is_none();
+    }
+    let property = match property {
+        Some(property) => property,
+        None => return target.is_state(),
+    };
+    if target.is_state() {
+        return property.query_state(State::Empty);
+    }
+
+    let target = target.to_kstr();
+    match property.as_array() {
+        Some(array) => array.values().any(|value| value.to_kstr() == target),
+        None => property.to_kstr() == target,
+    }
+}
+
+/// Where filter implementation
+#[derive(Debug)]
+pub struct WhereFilter {
+    pub(super) property: Expression,
+    pub(super) target: Option<Expression>,
+}
+
+impl WhereFilter {
+    pub(super) fn matching<'v>(&self, items: Vec<&'v dyn ValueView>, runtime: &dyn Runtime) -> LiquidResult<Vec<&'v dyn ValueView>> {
+        let property = self.property.evaluate(runtime)?.to_kstr().to_string();
+        let target = match &self.target {
+            Some(target) => Some(target.evaluate(runtime)?),
+            None => None,
+        };
+
+        Ok(items.into_iter()
+            .filter(|item| {
+                let value = super::array::property(*item, &property);
+                match &target {
+                    Some(target) => matches_target(value, target.as_view()),
+                    // Without a target, like Liquid's `where`, match truthy properties
+                    None => value.is_some_and(|value| value.query_state(State::Truthy)),
+                }
+            })
+            .collect())
+    }
+
+    /// Jekyll leaves the input as it is when the target is an array or hash
+    pub(super) fn ignores_target(&self, runtime: &dyn Runtime) -> LiquidResult<bool> {
+        match &self.target {
+            Some(target) => {
+                let target = target.evaluate(runtime)?;
+                Ok(target.is_array() || target.is_object())
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+impl fmt::Display for WhereFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "where")
+    }
+}
+
+impl liquid_core::Filter for WhereFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        match items(input) {
+            Some(items) if !self.ignores_target(runtime)? => {
+                Ok(to_array(self.matching(items, runtime)?.into_iter().map(|item| item.to_value())))
+            }
+            _ => Ok(input.to_value()),
+        }
+    }
+}
+
+/// Parse filter factory for where
+#[derive(Debug, Clone)]
+pub struct WhereFilterParser;
+
+impl FilterReflection for WhereFilterParser {
+    fn name(&self) -> &str {
+        "where"
+    }
+
+    fn description(&self) -> &str {
+        "Selects the items whose property matches a value"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for WhereFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "where", 1, 2)?.into_iter();
+        Ok(Box::new(WhereFilter {
+            property: arguments.next().unwrap(),
+            target: arguments.next(),
+        }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::runtime::Expression;
+use liquid_core::{Error, FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::array::{exp_arguments, items, to_array};
+use super::expression::Condition;
+
+/// WhereExp filter implementation
+#[derive(Debug)]
+pub struct WhereExpFilter {
+    pub(super) variable: Expression,
+    pub(super) expression: Expression,
+}
+
+impl WhereExpFilter {
+    pub(super) fn matching<'v>(&self, items: Vec<&'v dyn ValueView>, runtime: &dyn Runtime) -> LiquidResult<Vec<&'v dyn ValueView>> {
+        let (variable, expression) = exp_arguments(&self.variable, &self.expression, runtime)?;
+        let condition = Condition::parse(&expression).map_err(Error::with_msg)?;
+
+        Ok(items.into_iter()
+            .filter(|item| condition.evaluate(&variable, *item, runtime))
+            .collect())
+    }
+}
+
+impl fmt::Display for WhereExpFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "where_exp")
+    }
+}
+
+impl liquid_core::Filter for WhereExpFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> LiquidResult<Value> {
+        match items(input) {
+            Some(items) => Ok(to_array(self.matching(items, runtime)?.into_iter().map(|item| item.to_value()))),
+            None => Ok(input.to_value()),
+        }
+    }
+}
+
+/// Parse filter factory for where_exp
+#[derive(Debug, Clone)]
+pub struct WhereExpFilterParser;
+
+impl FilterReflection for WhereExpFilterParser {
+    fn name(&self) -> &str {
+        "where_exp"
+    }
+
+    fn description(&self) -> &str {
+        "Selects the items for which an expression is true"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for WhereExpFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        let mut arguments = arguments(args, "where_exp", 2, 2)?.into_iter();
+        Ok(Box::new(WhereExpFilter {
+            variable: arguments.next().unwrap(),
+            expression: arguments.next().unwrap(),
+        }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
@@ -0,0 +1,56 @@
+use std::fmt;
+
+use liquid_core::parser::{FilterArguments, ParameterReflection, ParseFilter};
+use liquid_core::{FilterReflection, Result as LiquidResult, Runtime, Value, ValueView};
+
+use super::arguments;
+use super::string::{input_string, xml_escape};
+
+/// XmlEscape filter implementation
+#[derive(Debug)]
+pub struct XmlEscapeFilter;
+
+impl fmt::Display for XmlEscapeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "xml_escape")
+    }
+}
+
+impl liquid_core::Filter for XmlEscapeFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> LiquidResult<Value> {
+        Ok(Value::scalar(xml_escape(&input_string(input))))
+    }
+}
+
+/// Parse filter factory for xml_escape
+#[derive(Debug, Clone)]
+pub struct XmlEscapeFilterParser;
+
+impl FilterReflection for XmlEscapeFilterParser {
+    fn name(&self) -> &str {
+        "xml_escape"
+    }
+
+    fn description(&self) -> &str {
+        "Escapes text for use in XML"
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for XmlEscapeFilterParser {
+    fn parse(&self, args: FilterArguments) -> LiquidResult<Box<dyn liquid_core::Filter>> {
+        arguments(args, "xml_escape", 0, 0)?;
+        Ok(Box::new(XmlEscapeFilter))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
@@ -1,10 +1,15 @@
 mod filters;
 mod tags;
 pub mod preprocess;
+pub mod dependencies;
+pub mod errors;
+pub mod strict;
+
+pub use tags::{IncludeCache, LinkTag};
 
 use std::collections::HashMap;
 use std::error::Error;
-use liquid::{Parser, ParserBuilder, Object, ValueView};
+use liquid::{Parser, ParserBuilder, Object};
 use liquid::model::Value;
 use crate::config::Config;
 use log;
@@ -75,41 +80,20 @@ fn detect_github_repository(config: &Config) -> (String, String, String) {
     (repo_name, owner_name, project_name)
 }
 
-/// Parse content with Liquid
-pub fn parse_liquid(content: &str, parser: &liquid::Parser, globals: &Object) -> BoxResult<String> {
+/// Parse and render content from `path` with Liquid
+///
+/// Errors name the template and show the failing line, with the includes
+/// and layouts that led to it.
+pub fn parse_liquid(content: &str, path: &Path, parser: &liquid::Parser, globals: &Object) -> BoxResult<String> {
     // First preprocess to fix include tags with slashes
     let preprocessed_content = preprocess::preprocess_liquid(content);
     
     // Decode HTML entities before parsing
     let decoded_content = html_escape::decode_html_entities(&preprocessed_content).to_string();
     
-    match parser.parse(&decoded_content) {
-        Ok(template) => match template.render(globals) {
-            Ok(result) => Ok(result),
-            Err(e) => {
-                // Log available globals for debugging
-                log::debug!("Error rendering Liquid template: {}", e);
-                log::debug!("Available globals:");
-                for (key, value) in globals {
-                    log::debug!("- {}: {:?}", key, value);
-                    
-                    // If this is an object, just log that it's an object without trying to iterate
-                    if value.is_object() {
-                        log::debug!("  - {}.* is an Object", key);
-                    }
-                }
-                
-                Err(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!("Error rendering Liquid template: {}", e)
-                )))
-            },
-        },
-        Err(e) => Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("Error parsing Liquid template: {}", e)
-        ))),
-    }
+    parser.parse(&decoded_content)
+        .and_then(|template| template.render(globals))
+        .map_err(|e| errors::describe(&e, content, &decoded_content, path).into())
 }
 
 /// Create a Jekyll-compatible Liquid parser with custom filters/tags
@@ -120,14 +104,11 @@ pub fn create_jekyll_parser(
     // Note: We don't use the includes map directly anymore since we're using custom tags
     // for handling includes in a Jekyll-compatible way
     
-    // Create the parser builder and register custom extensions
-    let mut parser_builder = ParserBuilder::with_stdlib();
-    
-    // Register custom filters
-    parser_builder = filters::register_filters(parser_builder, config);
+    // Create the parser builder and register custom filters
+    let parser_builder = filters::register_filters(ParserBuilder::with_stdlib(), config);
     
     // Register custom tags
-    parser_builder = tags::register_tags(parser_builder, config);
+    let parser_builder = tags::register_tags(parser_builder, config);
     
     // Build the parser
     let parser = parser_builder.build()?;
@@ -143,7 +124,7 @@ pub fn create_site_object(config: &Config) -> Object {
     // Add dynamic values that aren't in the config
     
     // Add time
-    let now = chrono::Utc::now();
+    let now = crate::utils::date::local(chrono::Utc::now());
     site.insert("time".into(), Value::scalar(now.to_rfc3339()));
     
     // Add Jekyll version variable
@@ -189,6 +170,9 @@ pub fn create_site_object(config: &Config) -> Object {
         ]
     };
     site.insert("plugins".into(), Value::Array(plugins));
+    
+    // Set per post while rendering posts, empty everywhere else
+    site.insert("related_posts".into(), Value::Array(Vec::new()));
 
     // Debug output all available site variables
     log::debug!("Site variables available in templates:");
@@ -215,10 +199,5 @@ pub fn create_globals(config: &Config, site_data: Option<&Object>) -> Object {
     // Add default include object with pre-defined variables for Jekyll compatibility
     globals.insert("include".into(), Value::Object(tags::utils::create_default_include_globals()));
     
-    log::debug!("Global variables:");
-    for (key, value) in &globals {
-        log::debug!("- {}: {:?}", key, value);
-    }
-    
     globals
 } 
\ No newline at end of file
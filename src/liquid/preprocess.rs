@@ -5,7 +5,7 @@ use log::{info, debug, error};
 pub fn preprocess_liquid(content: &str) -> String {
     // Create regex to find include tags with paths containing slashes
     // This regex handles paths like "components/rankings/criteria-methodology.html" and "content/devops/introducao.md"
-    let include_regex = Regex::new(r#"(\{%\s*-?\s*include\s+)([a-zA-Z0-9_./-]+(?:/[a-zA-Z0-9_./-]+)*)(\s+.*?-?\s*%\}|\s*-?\s*%\})"#)
+    let include_regex = Regex::new(r#"(\{%\s*-?\s*include(?:_cached)?\s+)([a-zA-Z0-9_./-]+(?:/[a-zA-Z0-9_./-]+)*)(\s+.*?-?\s*%\}|\s*-?\s*%\})"#)
         .unwrap_or_else(|e| {
             error!("Failed to compile include regex: {}", e);
             Regex::new(r"a^").unwrap() // This will never match anything
@@ -45,7 +45,7 @@ pub fn preprocess_liquid(content: &str) -> String {
     }).to_string();
 
     // Handle the specific pattern: {% capture var %}{% include path/with/slash.ext %}{% endcapture %}
-    let capture_inline_include_regex = Regex::new(r#"(\{%\s*capture\s+[^%]+%\}\s*\{%\s*include\s+)([a-zA-Z0-9_./-]+/[a-zA-Z0-9_./-]+)(\s*%\}\s*\{%\s*endcapture\s*%\})"#)
+    let capture_inline_include_regex = Regex::new(r#"(\{%\s*capture\s+[^%]+%\}\s*\{%\s*include(?:_cached)?\s+)([a-zA-Z0-9_./-]+/[a-zA-Z0-9_./-]+)(\s*%\}\s*\{%\s*endcapture\s*%\})"#)
         .unwrap_or_else(|e| {
             error!("Failed to compile capture inline include regex: {}", e);
             Regex::new(r"a^").unwrap() // This will never match anything
@@ -70,7 +70,7 @@ pub fn preprocess_liquid(content: &str) -> String {
 
     // Handle any remaining include statements that weren't caught by the above patterns
     // This is a more general pattern that catches include statements with slashes anywhere
-    let general_include_regex = Regex::new(r#"(\{%\s*-?\s*include\s+)([a-zA-Z0-9_./-]+/[a-zA-Z0-9_./-]+)(\s+.*?%\}|%\})"#)
+    let general_include_regex = Regex::new(r#"(\{%\s*-?\s*include(?:_cached)?\s+)([a-zA-Z0-9_./-]+/[a-zA-Z0-9_./-]+)(\s+.*?%\}|%\})"#)
         .unwrap_or_else(|e| {
             error!("Failed to compile general include regex: {}", e);
             Regex::new(r"a^").unwrap() // This will never match anything
@@ -94,7 +94,7 @@ pub fn preprocess_liquid(content: &str) -> String {
     }).to_string();
     
     // Also handle trim modifiers at the end of include paths
-    let trim_regex = Regex::new(r#"(\{%\s*include\s+(?:"[^"]+"|'[^']+'|[^\s"']+))(-\s*%\})"#)
+    let trim_regex = Regex::new(r#"(\{%\s*include(?:_cached)?\s+(?:"[^"]+"|'[^']+'|[^\s"']+))(-\s*%\})"#)
         .unwrap_or_else(|e| {
             error!("Failed to compile trim regex: {}", e);
             Regex::new(r"a^").unwrap() // This will never match anything
@@ -111,6 +111,7 @@ pub fn preprocess_liquid(content: &str) -> String {
     }).to_string();
     
     // Apply each preprocessor function in sequence
+    let content = quote_post_url_names(&content);
     let content = normalize_hyphenated_variables(&content);
     let content = convert_include_equals_to_colons(&content);
     let content = protect_date_filter_formats(&content);
@@ -121,13 +122,28 @@ pub fn preprocess_liquid(content: &str) -> String {
     content
 }
 
+/// Quote the post names of post_url tags, which aren't valid Liquid
+/// For example: {% post_url 2024-01-01-hello %} -> {% post_url "2024-01-01-hello" %}
+pub fn quote_post_url_names(content: &str) -> String {
+    lazy_static::lazy_static! {
+        static ref POST_URL_RE: Regex = Regex::new(
+            r#"(\{%-?\s*post_url\s+)([^\s"'%-][^\s%]*)(\s*-?%\})"#
+        ).unwrap();
+    }
+
+    POST_URL_RE.replace_all(content, |caps: &regex::Captures| {
+        debug!("Quoting post_url name '{}'", &caps[2]);
+        format!("{}\"{}\"{}", &caps[1], &caps[2], &caps[3])
+    }).to_string()
+}
+
 /// Convert equals signs to colons in include tag parameters
 /// For example: {% include file.html param=value %} -> {% include file.html param: value %}
 fn convert_include_equals_to_colons(content: &str) -> String {
     lazy_static::lazy_static! {
         // This regex finds include tags with parameters using equals signs
         static ref INCLUDE_PARAMS_RE: Regex = Regex::new(
-            r#"(\{%\s*include\s+(?:"[^"]+"|'[^']+'|[^\s"']+)(?:\s+[a-zA-Z0-9_-]+))=([^%}]+%\})"#
+            r#"(\{%\s*include(?:_cached)?\s+(?:"[^"]+"|'[^']+'|[^\s"']+)(?:\s+[a-zA-Z0-9_-]+))=([^%}]+%\})"#
         ).unwrap();
     }
     
@@ -0,0 +1,828 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+use liquid::model::Value;
+use liquid::{Object, Parser};
+use regex::Regex;
+
+use crate::builder::diagnostics::{self, Category, Diagnostic, Severity};
+use crate::builder::render_state;
+use crate::config::Config;
+use super::errors;
+
+lazy_static! {
+    // The end of blocks whose contents are not Liquid
+    static ref END_RAW: Regex = Regex::new(r"\{%-?\s*endraw\s*-?%\}").unwrap();
+    static ref END_COMMENT: Regex = Regex::new(r"\{%-?\s*endcomment\s*-?%\}").unwrap();
+}
+
+/// Words in expressions that are not variables
+const KEYWORDS: &[&str] = &[
+    "and", "or", "contains", "in", "true", "false", "nil", "null", "empty", "blank", "reversed",
+];
+
+/// Liquid's `error_mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorMode {
+    /// Problems are not reported
+    #[default]
+    Lax,
+    /// Problems are reported as warnings and rendering goes on
+    Warn,
+    /// Problems are reported as errors and fail the template
+    Strict,
+}
+
+impl ErrorMode {
+    /// Parse a mode from its name in the config
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "lax" => Some(ErrorMode::Lax),
+            "warn" => Some(ErrorMode::Warn),
+            "strict" => Some(ErrorMode::Strict),
+            _ => None,
+        }
+    }
+}
+
+/// How undefined variables and unknown filters are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StrictMode {
+    /// The severity of undefined variables, if they are reported
+    pub variables: Option<Severity>,
+    /// The severity of unknown filters, if they are reported
+    pub filters: Option<Severity>,
+}
+
+impl StrictMode {
+    /// Read the mode from the `liquid` config
+    ///
+    /// Like Jekyll, undefined variables are only reported, as errors, with
+    /// `strict_variables`. The `warn` and `strict` error modes report unknown
+    /// filters as warnings or errors, and `strict_filters` makes them errors
+    /// in any mode.
+    pub fn from_config(config: &Config) -> Self {
+        let liquid = config.liquid.clone().unwrap_or_default();
+        let filters = match ErrorMode::from_name(&liquid.error_mode).unwrap_or_default() {
+            ErrorMode::Lax => None,
+            ErrorMode::Warn => Some(Severity::Warning),
+            ErrorMode::Strict => Some(Severity::Error),
+        };
+
+        Self {
+            variables: liquid.strict_variables.then_some(Severity::Error),
+            filters: if liquid.strict_filters { Some(Severity::Error) } else { filters },
+        }
+    }
+
+    /// Whether nothing is reported
+    pub fn is_lax(&self) -> bool {
+        self.variables.is_none() && self.filters.is_none()
+    }
+}
+
+/// What the checks of one site found so far, shared by its rendering threads
+#[derive(Debug, Clone, Default)]
+pub struct StrictState {
+    /// Whether filter names are known to the site's parser
+    filters: Arc<Mutex<HashMap<String, bool>>>,
+    /// Warnings already reported, since layouts and includes are rendered for many pages
+    reported: Arc<Mutex<HashSet<(PathBuf, usize, String)>>>,
+}
+
+/// A part of a variable after its name
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Segment {
+    /// `.key` or `["key"]`
+    Key(String),
+    /// `[0]`
+    Index(i64),
+    /// An index that is only known when rendering, e.g. `[page.lang]`
+    Dynamic,
+}
+
+/// A variable used in a template, e.g. `page.tags[0]`
+#[derive(Debug, Clone)]
+pub(super) struct Reference {
+    pub(super) text: String,
+    pub(super) offset: usize,
+    pub(super) root: String,
+    pub(super) segments: Vec<Segment>,
+}
+
+/// A step from the globals to a value
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+/// A `{{ }}` or `{% %}` in a template, without delimiters and whitespace control
+pub(super) struct Markup<'s> {
+    pub(super) output: bool,
+    pub(super) inner: &'s str,
+    pub(super) offset: usize,
+}
+
+impl<'s> Markup<'s> {
+    /// The tag name and the rest of a tag, with the offset of the rest
+    pub(super) fn tag(&self) -> (&'s str, &'s str, usize) {
+        let trimmed = self.inner.trim_start();
+        let start = self.offset + self.inner.len() - trimmed.len();
+        let name_end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        (&trimmed[..name_end], &trimmed[name_end..], start + name_end)
+    }
+
+    /// The expression and filters of an output, `assign` or `echo`, with its offset
+    fn filtered_expression(&self) -> Option<(&'s str, usize)> {
+        if self.output {
+            return Some((self.inner, self.offset));
+        }
+        match self.tag() {
+            ("assign", rest, offset) => rest.find('=').map(|i| (&rest[i + 1..], offset + i + 1)),
+            ("echo", rest, offset) => Some((rest, offset)),
+            _ => None,
+        }
+    }
+}
+
+/// Find the Liquid markup in a template, skipping `raw` and `comment` blocks
+pub(super) fn markups(source: &str) -> Vec<Markup<'_>> {
+    let mut markups = Vec::new();
+    let mut pos = 0;
+
+    while let Some(start) = source[pos..].find('{').map(|i| pos + i) {
+        let rest = &source[start..];
+        let (output, close) = if rest.starts_with("{{") {
+            (true, "}}")
+        } else if rest.starts_with("{%") {
+            (false, "%}")
+        } else {
+            pos = start + 1;
+            continue;
+        };
+        let Some(end) = rest[2..].find(close).map(|i| start + 2 + i) else {
+            break;
+        };
+        pos = end + 2;
+
+        let mut inner_start = start + 2;
+        let mut inner_end = end;
+        if source[inner_start..inner_end].starts_with('-') {
+            inner_start += 1;
+        }
+        if inner_end > inner_start && source[inner_start..inner_end].ends_with('-') {
+            inner_end -= 1;
+        }
+        let markup = Markup { output, inner: &source[inner_start..inner_end], offset: inner_start };
+
+        if !output {
+            let end_tag = match markup.tag().0 {
+                "raw" => Some(&*END_RAW),
+                "comment" => Some(&*END_COMMENT),
+                _ => None,
+            };
+            if let Some(end_tag) = end_tag {
+                pos = end_tag.find(&source[pos..]).map_or(source.len(), |m| pos + m.end());
+                continue;
+            }
+        }
+        markups.push(markup);
+    }
+
+    markups
+}
+
+/// The index after a bracketed index starting at the beginning of `text`
+fn bracket_end(text: &str) -> usize {
+    let mut depth = 0;
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '[' => depth += 1,
+                ']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return i + 1;
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+    text.len()
+}
+
+/// The end of a variable starting at `start`, e.g. `site.data["menu"].items`
+fn path_end(expr: &str, start: usize) -> usize {
+    let mut chars = expr[start..].char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let more = match c {
+            '[' => {
+                let end = start + i + bracket_end(&expr[start + i..]);
+                while chars.peek().is_some_and(|(j, _)| start + j < end) {
+                    chars.next();
+                }
+                true
+            }
+            '.' => chars.peek().is_some_and(|(_, next)| next.is_ascii_alphabetic() || *next == '_'),
+            c => c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '?',
+        };
+        if !more {
+            return start + i;
+        }
+    }
+    expr.len()
+}
+
+/// Split a variable into its name and segments, collecting variables used as indexes
+fn parse_reference(text: &str, offset: usize, references: &mut Vec<Reference>) -> Reference {
+    let root_end = text.find(['.', '[']).unwrap_or(text.len());
+    let mut segments = Vec::new();
+    let mut pos = root_end;
+
+    while pos < text.len() {
+        let rest = &text[pos..];
+        if let Some(after) = rest.strip_prefix('.') {
+            let len = after.find(['.', '[']).unwrap_or(after.len());
+            segments.push(Segment::Key(after[..len].to_string()));
+            pos += 1 + len;
+        } else {
+            let end = bracket_end(rest);
+            let inner = rest[1..end.saturating_sub(1).max(1)].trim();
+            let quoted = inner.len() >= 2 && (inner.starts_with('"') && inner.ends_with('"') || inner.starts_with('\'') && inner.ends_with('\''));
+            if quoted {
+                segments.push(Segment::Key(inner[1..inner.len() - 1].to_string()));
+            } else if let Ok(index) = inner.parse::<i64>() {
+                segments.push(Segment::Index(index));
+            } else {
+                segments.push(Segment::Dynamic);
+                scan_references(&rest[1..end.saturating_sub(1).max(1)], offset + pos + 1, references);
+            }
+            pos += end.max(1);
+        }
+    }
+
+    Reference { text: text.to_string(), offset, root: text[..root_end].to_string(), segments }
+}
+
+/// Collect the variables used in an expression, skipping literals and keywords
+pub(super) fn scan_references(expr: &str, offset: usize, references: &mut Vec<Reference>) {
+    let bytes = expr.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c == b'"' || c == b'\'' {
+            i = expr[i + 1..].find(c as char).map_or(bytes.len(), |end| i + end + 2);
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)) {
+                i += 1;
+            }
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            let end = path_end(expr, i);
+            let text = &expr[i..end];
+            // Names of keyword arguments, e.g. `limit: 3`
+            let argument_name = expr[end..].trim_start().starts_with(':');
+            if !argument_name && !KEYWORDS.contains(&text) {
+                let reference = parse_reference(text, offset + i, references);
+                references.push(reference);
+            }
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Split an expression at the pipes between filters, outside of strings
+pub(super) fn split_filters(expr: &str) -> Vec<(usize, &str)> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in expr.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '|' => {
+                parts.push((start, &expr[start..i]));
+                start = i + 1;
+            }
+            None => {}
+        }
+    }
+    parts.push((start, &expr[start..]));
+    parts
+}
+
+/// The name of a filter and the offset of its arguments, e.g. `date` in ` date: "%Y"`
+pub(super) fn filter_name(filter: &str) -> (&str, usize) {
+    let trimmed = filter.trim_start();
+    let start = filter.len() - trimmed.len();
+    let end = trimmed.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(trimmed.len());
+    (&trimmed[..end], start + end)
+}
+
+/// Whether the parser knows a filter
+fn is_filter(parser: &Parser, name: &str, state: &StrictState) -> bool {
+    if let Some(known) = state.filters.lock().unwrap().get(name) {
+        return *known;
+    }
+    let known = match parser.parse(&format!("{{{{ nil | {} }}}}", name)) {
+        Ok(_) => true,
+        // Filters that need arguments fail for other reasons
+        Err(e) => !e.to_string().contains("Unknown filter"),
+    };
+    state.filters.lock().unwrap().insert(name.to_string(), known);
+    known
+}
+
+/// Follow variable segments from a value, returning the path to the first missing key
+///
+/// Like Liquid, indexing nil and scalars is not an error, nor are `size`,
+/// `first` and `last`.
+fn find_missing(value: &Value, segments: &[Segment], mut path: Vec<Step>) -> Option<Vec<Step>> {
+    let (segment, rest) = segments.split_first()?;
+    match (value, segment) {
+        (Value::Object(object), Segment::Key(key)) => match object.get(key.as_str()) {
+            Some(child) => {
+                path.push(Step::Key(key.clone()));
+                find_missing(child, rest, path)
+            }
+            None if key == "size" => None,
+            None => Some(missing_keys(path, segments)),
+        },
+        (Value::Array(array), segment) => {
+            let index = match segment {
+                Segment::Key(key) if key == "first" => 0,
+                Segment::Key(key) if key == "last" => array.len().checked_sub(1)?,
+                Segment::Index(index) if *index < 0 => array.len().checked_sub(index.unsigned_abs() as usize)?,
+                Segment::Index(index) => *index as usize,
+                _ => return None,
+            };
+            let item = array.get(index)?;
+            path.push(Step::Index(index));
+            find_missing(item, rest, path)
+        }
+        _ => None,
+    }
+}
+
+/// The path to a missing key, followed by the keys used on it
+fn missing_keys(mut path: Vec<Step>, segments: &[Segment]) -> Vec<Step> {
+    path.extend(segments.iter().map_while(|segment| match segment {
+        Segment::Key(key) => Some(Step::Key(key.clone())),
+        _ => None,
+    }));
+    path
+}
+
+/// Look up a value in the globals
+fn find_value<'v>(globals: &'v Object, path: &[Step]) -> Option<&'v Value> {
+    let (Step::Key(root), rest) = path.split_first()? else {
+        return None;
+    };
+    let mut value = globals.get(root.as_str())?;
+    for step in rest {
+        value = match (value, step) {
+            (Value::Object(object), Step::Key(key)) => object.get(key.as_str())?,
+            (Value::Array(array), Step::Index(index)) => array.get(*index)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// Set a missing variable to nil, so rendering doesn't fail on it
+///
+/// Objects missing along the way are created, since Liquid fails on
+/// indexing nil.
+fn set_nil(globals: &mut Object, path: &[Step]) {
+    let Some((Step::Key(root), rest)) = path.split_first() else {
+        return;
+    };
+    let mut value = globals.entry(root.clone()).or_insert(Value::Nil);
+    for step in rest {
+        if matches!((&value, step), (Value::Nil, Step::Key(_))) {
+            *value = Value::Object(Object::new());
+        }
+        value = match (value, step) {
+            (Value::Object(object), Step::Key(key)) => object.entry(key.clone()).or_insert(Value::Nil),
+            (Value::Array(array), Step::Index(index)) => match array.get_mut(*index) {
+                Some(item) => item,
+                None => return,
+            },
+            _ => return,
+        };
+    }
+}
+
+/// Variables assigned in a template, and where loop variables come from
+#[derive(Debug, Default)]
+struct Bindings {
+    locals: HashSet<String>,
+    loops: HashMap<String, Option<Reference>>,
+}
+
+impl Bindings {
+    fn collect(markups: &[Markup<'_>]) -> Self {
+        let mut bindings = Bindings::default();
+        bindings.locals.extend(["forloop", "tablerowloop"].map(String::from));
+
+        for markup in markups.iter().filter(|markup| !markup.output) {
+            let (name, rest, offset) = markup.tag();
+            let first = rest.split(|c: char| c.is_whitespace() || c == '=').find(|word| !word.is_empty()).unwrap_or("");
+            match name {
+                "assign" | "capture" | "increment" | "decrement" => {
+                    bindings.locals.insert(first.to_string());
+                }
+                "for" | "tablerow" => {
+                    // Items can be checked if the loop goes over a global
+                    let source = rest.find(" in ").and_then(|i| {
+                        let mut references = Vec::new();
+                        let source = &rest[i + 4..];
+                        let start = source.len() - source.trim_start().len();
+                        scan_references(&source[..start + path_end(source, start) - start], offset + i + 4, &mut references);
+                        references.pop()
+                    });
+                    let source = source.filter(|source| source.segments.iter().all(|segment| *segment != Segment::Dynamic));
+                    match bindings.loops.get(first) {
+                        // The same name looping over different things can't be checked
+                        Some(existing) if existing.as_ref().map(|r| &r.text) != source.as_ref().map(|r| &r.text) => {
+                            bindings.loops.insert(first.to_string(), None);
+                        }
+                        _ => {
+                            bindings.loops.insert(first.to_string(), source);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Loops over assigned or other loop variables can't be checked either
+        let unchecked: Vec<String> = bindings.loops.iter()
+            .filter(|(_, source)| source.as_ref().is_some_and(|source| {
+                bindings.locals.contains(&source.root) || bindings.loops.contains_key(&source.root)
+            }))
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in unchecked {
+            bindings.loops.insert(name, None);
+        }
+
+        bindings
+    }
+
+    /// Paths to the missing keys of a variable, one per loop item for loop variables
+    fn missing(&self, reference: &Reference, globals: &Object) -> Vec<Vec<Step>> {
+        if self.locals.contains(&reference.root) {
+            return Vec::new();
+        }
+        if let Some(source) = self.loops.get(&reference.root) {
+            let Some(source) = source else {
+                return Vec::new();
+            };
+            let Some(path) = self.global_path(source, globals) else {
+                return Vec::new();
+            };
+            let Some(Value::Array(items)) = find_value(globals, &path) else {
+                return Vec::new();
+            };
+            return items.iter().enumerate()
+                .filter_map(|(i, item)| {
+                    let mut item_path = path.clone();
+                    item_path.push(Step::Index(i));
+                    find_missing(item, &reference.segments, item_path)
+                })
+                .collect();
+        }
+
+        match globals.get(reference.root.as_str()) {
+            Some(value) => find_missing(value, &reference.segments, vec![Step::Key(reference.root.clone())])
+                .into_iter()
+                .collect(),
+            None => vec![missing_keys(vec![Step::Key(reference.root.clone())], &reference.segments)],
+        }
+    }
+
+    /// The path of a loop's source in the globals, if it exists
+    fn global_path(&self, source: &Reference, globals: &Object) -> Option<Vec<Step>> {
+        let root = globals.get(source.root.as_str())?;
+        if find_missing(root, &source.segments, Vec::new()).is_some() {
+            return None;
+        }
+        let mut path = vec![Step::Key(source.root.clone())];
+        let mut value = root;
+        for segment in &source.segments {
+            let (step, next) = match (value, segment) {
+                (Value::Object(object), Segment::Key(key)) => (Step::Key(key.clone()), object.get(key.as_str())?),
+                (Value::Array(array), Segment::Index(index)) if *index >= 0 => (Step::Index(*index as usize), array.get(*index as usize)?),
+                _ => return None,
+            };
+            path.push(step);
+            value = next;
+        }
+        Some(path)
+    }
+}
+
+/// An undefined variable or unknown filter
+#[derive(Debug, Clone, PartialEq)]
+struct Problem {
+    severity: Severity,
+    offset: usize,
+    message: String,
+}
+
+/// The result of checking a template
+#[derive(Debug, Clone, Default)]
+pub struct Checked {
+    unknown_filters: HashSet<String>,
+}
+
+impl Checked {
+    /// Remove unknown filters from a template, like Liquid's lax mode ignores them
+    pub fn apply<'t>(&self, template: &'t str) -> Cow<'t, str> {
+        if self.unknown_filters.is_empty() {
+            return Cow::Borrowed(template);
+        }
+
+        let mut removed = Vec::new();
+        for markup in markups(template) {
+            let Some((expr, offset)) = markup.filtered_expression() else {
+                continue;
+            };
+            for (start, filter) in split_filters(expr).into_iter().skip(1) {
+                if self.unknown_filters.contains(filter_name(filter).0) {
+                    // Remove the pipe too
+                    removed.push((offset + start - 1, offset + start + filter.len()));
+                }
+            }
+        }
+
+        let mut result = String::with_capacity(template.len());
+        let mut pos = 0;
+        for (start, end) in removed {
+            result.push_str(&template[pos..start]);
+            pos = end;
+        }
+        result.push_str(&template[pos..]);
+        Cow::Owned(result)
+    }
+}
+
+/// Whether a variable is tested by a condition, or is a key under one that is
+fn is_guarded(reference: &Reference, guarded: &HashSet<String>) -> bool {
+    guarded.iter().any(|text| {
+        reference.text.strip_prefix(text.as_str()).is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+    })
+}
+
+/// Find the problems in a template, and the variables to set to nil
+///
+/// Variables tested by `if` and `unless`, and values with a `default`, may
+/// be missing, as templates only use them when they are set.
+fn find_problems(source: &str, globals: &Object, parser: &Parser, mode: StrictMode, state: &StrictState) -> (Vec<Problem>, Vec<Vec<Step>>, HashSet<String>) {
+    let markups = markups(source);
+    let bindings = Bindings::collect(&markups);
+    let mut references = Vec::new();
+    let mut conditions = Vec::new();
+    let mut problems = Vec::new();
+    let mut unknown_filters = HashSet::new();
+
+    for markup in &markups {
+        if let Some((expr, offset)) = markup.filtered_expression() {
+            let parts = split_filters(expr);
+            let has_default = parts.iter().skip(1).any(|(_, filter)| filter_name(filter).0 == "default");
+            let (start, value) = parts[0];
+            if !has_default {
+                scan_references(value, offset + start, &mut references);
+            }
+            for (start, filter) in parts.into_iter().skip(1) {
+                let (name, args) = filter_name(filter);
+                if let Some(severity) = mode.filters {
+                    if !name.is_empty() && !is_filter(parser, name, state) {
+                        let name_offset = offset + start + filter.len() - filter.trim_start().len();
+                        problems.push(Problem { severity, offset: name_offset, message: format!("Unknown filter '{}'", name) });
+                        unknown_filters.insert(name.to_string());
+                    }
+                }
+                scan_references(&filter[args..], offset + start + args, &mut references);
+            }
+            continue;
+        }
+
+        let (name, rest, offset) = markup.tag();
+        match name {
+            "if" | "elsif" | "unless" => scan_references(rest, offset, &mut conditions),
+            "case" | "when" | "cycle" => scan_references(rest, offset, &mut references),
+            "for" | "tablerow" => {
+                if let Some(i) = rest.find(" in ") {
+                    scan_references(&rest[i + 4..], offset + i + 4, &mut references);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Missing variables are set to nil like Liquid's lax lookups, even when
+    // they are not reported. Conditions go first, and keys under a missing
+    // condition are left alone so it stays false.
+    let mut missing: Vec<Vec<Step>> = conditions.iter().flat_map(|reference| bindings.missing(reference, globals)).collect();
+    let guarded: HashSet<String> = conditions.into_iter().map(|reference| reference.text).collect();
+    for reference in references {
+        let paths: Vec<Vec<Step>> = bindings.missing(&reference, globals).into_iter()
+            .filter(|path| !missing.iter().any(|repaired| path.starts_with(repaired)))
+            .collect();
+        if paths.is_empty() {
+            continue;
+        }
+        if let Some(severity) = mode.variables.filter(|_| !is_guarded(&reference, &guarded)) {
+            problems.push(Problem { severity, offset: reference.offset, message: format!("Undefined variable '{}'", reference.text) });
+        }
+        missing.extend(paths);
+    }
+
+    problems.sort_by_key(|problem| problem.offset);
+    (problems, missing, unknown_filters)
+}
+
+/// The number of lines before the body of a file with front matter
+pub(super) fn front_matter_lines(file: &Path) -> usize {
+    let Ok(text) = fs::read_to_string(file) else {
+        return 0;
+    };
+    let mut lines = text.lines();
+    if lines.next().map(str::trim_end) != Some("---") {
+        return 0;
+    }
+    lines.position(|line| matches!(line.trim_end(), "---" | "..."))
+        .map_or(0, |i| i + 2)
+}
+
+/// The line and column of an offset, both 1-indexed
+pub(super) fn position(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |line| line.chars().count()) + 1;
+    (line, column)
+}
+
+/// Check a template for undefined variables and unknown filters before rendering it
+///
+/// `source` is the template as written in `file`, for line numbers.
+/// Warnings are reported right away, and missing variables are set to nil
+/// in `globals` so the template still renders. Errors are returned
+/// together, to fail the template.
+pub fn check(source: &str, file: &Path, globals: &mut Object, parser: &Parser, mode: StrictMode) -> Result<Checked, String> {
+    if mode.is_lax() {
+        return Ok(Checked::default());
+    }
+
+    // Outside of a site build, nothing is shared between templates
+    let state = render_state::with(|state| state.map(|state| state.strict().clone())).unwrap_or_default();
+    let (problems, missing, unknown_filters) = find_problems(source, globals, parser, mode, &state);
+
+    let line_offset = if problems.is_empty() { 0 } else { front_matter_lines(file) };
+    let mut errors = Vec::new();
+    for problem in problems {
+        let (line, column) = position(source, problem.offset);
+        let line = line + line_offset;
+        match problem.severity {
+            Severity::Error => errors.push(format!("{}{}\n{}", problem.message, errors::location(file, line, column),
+                                                   errors::excerpt(source, problem.offset, line_offset))),
+            Severity::Warning => {
+                let first = state.reported.lock().unwrap().insert((file.to_path_buf(), problem.offset, problem.message.clone()));
+                if first {
+                    let mut diagnostic = Diagnostic::new(Severity::Warning, Category::Liquid, file, problem.message);
+                    diagnostic.line = Some(line);
+                    diagnostic.column = Some(column);
+                    diagnostics::report(diagnostic);
+                }
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors::finish(&errors.concat()));
+    }
+    for path in missing {
+        set_nil(globals, &path);
+    }
+    Ok(Checked { unknown_filters })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use liquid::object;
+    use crate::builder::site::Site;
+    use crate::config::LiquidConfig;
+    use crate::test_support::TestSite;
+
+    fn mode(severity: Severity) -> StrictMode {
+        StrictMode { variables: Some(severity), filters: Some(severity) }
+    }
+
+    fn globals() -> Object {
+        object!({
+            "page": { "title": "Hello", "tags": ["a", "b"] },
+            "site": { "posts": [{ "title": "One" }, { "title": "Two", "subtitle": "2" }] },
+        })
+    }
+
+    #[test]
+    fn test_find_problems() {
+        let parser = liquid::ParserBuilder::with_stdlib().build().unwrap();
+        let source = "{{ page.title | upcase }}{{ page.tittle }}\n\
+                      {% assign first = page.tags.first | nofilter: page.tags.size %}{{ first }}\n\
+                      {% for post in site.posts limit: 1 %}{{ post.title }}{{ post.subtitle }}{{ forloop.index }}{% endfor %}\n\
+                      {% raw %}{{ nope }}{% endraw %}{% case page.kind %}{% endcase %}\n\
+                      {% if page.subtitle and page.author %}{{ page.subtitle }}{{ page.author.name }}{% endif %}\
+                      {% unless site.url %}{{ site.url }}{% endunless %}{{ page.lead | default: page.title }}{{ page.subtitles }}";
+
+        let (problems, missing, unknown) = find_problems(source, &globals(), &parser, mode(Severity::Warning), &StrictState::default());
+        let messages: Vec<&str> = problems.iter().map(|problem| problem.message.as_str()).collect();
+        assert_eq!(messages, ["Undefined variable 'page.tittle'", "Unknown filter 'nofilter'",
+                              "Undefined variable 'post.subtitle'", "Undefined variable 'page.kind'",
+                              "Undefined variable 'page.subtitles'"]);
+        assert_eq!(position(source, problems[2].offset), (3, 57));
+        assert_eq!(missing.len(), 7);
+        assert!(unknown.contains("nofilter"));
+    }
+
+    #[test]
+    fn test_check_repairs_warnings() {
+        let parser = liquid::ParserBuilder::with_stdlib().build().unwrap();
+        let source = "{{ page.tittle }}|{{ page.title | nofilter: 1 | upcase }}|{% for post in site.posts %}{{ post.subtitle }},{% endfor %}";
+        let mut globals = globals();
+
+        let checked = check(source, Path::new("test.html"), &mut globals, &parser, mode(Severity::Warning)).unwrap();
+        let template = parser.parse(&checked.apply(source)).unwrap();
+        assert_eq!(template.render(&globals).unwrap(), "|HELLO|,2,");
+
+        let error = check(source, Path::new("test.html"), &mut self::globals(), &parser, mode(Severity::Error)).unwrap_err();
+        assert!(error.starts_with("Undefined variable 'page.tittle' at line 1, column 4\n"));
+    }
+
+    #[test]
+    fn test_mode_from_config() {
+        let config = |liquid: LiquidConfig| Config { liquid: Some(liquid), ..Config::default() };
+        let warn = StrictMode::from_config(&config(LiquidConfig::default()));
+        assert_eq!(warn, StrictMode { variables: None, filters: Some(Severity::Warning) });
+
+        let strict = StrictMode::from_config(&config(LiquidConfig { strict_variables: true, ..LiquidConfig::default() }));
+        assert_eq!(strict, StrictMode { variables: Some(Severity::Error), filters: Some(Severity::Warning) });
+    }
+
+    #[test]
+    fn test_default_mode_site() {
+        let site = TestSite::new("strict-default");
+        site.write("_layouts/default.html", "{{ layout.foo }}{{ site.url }}{{ jekyll.environment }}{{ paginator.page }}{{ content }}")
+            .write("index.html", "---\nlayout: default\n---\n{% if page.subtitle %}{{ page.subtitle }}{% endif %}{{ page.date }}")
+            .write("about.html", "---\nlayout: default\n---\n{% if page.author %}By {{ page.author.name }}{% endif %}About");
+
+        // Like Jekyll, variables that are not set are only reported with strict_variables
+        let outcome = site.build(site.config());
+        assert!(outcome.diagnostics.is_empty(), "{:?}", outcome.diagnostics);
+
+        // Conditions on missing variables stay false
+        let about = site.read("about.html");
+        assert!(about.ends_with("About") && !about.contains("By"), "{}", about);
+    }
+
+    #[test]
+    fn test_warnings_per_site() {
+        let sites: Vec<TestSite> = ["first", "second"].iter().map(|name| {
+            let site = TestSite::new(&format!("strict-{}", name));
+            site.write("_layouts/default.html", "{{ content | nofilter }}")
+                .write("a.html", "---\nlayout: default\n---\nA")
+                .write("b.html", "---\nlayout: default\n---\nB");
+            site
+        }).collect();
+
+        // The second site is read while the first one renders, and each site
+        // reports its layout's warning once
+        let mut first = Site::new(sites[0].config());
+        let mut second = Site::new(sites[1].config());
+        first.read().unwrap();
+        second.read().unwrap();
+        first.render().unwrap();
+        second.render().unwrap();
+        for site in [first, second] {
+            let diagnostics = site.outcome().diagnostics;
+            let messages: Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+            assert_eq!(messages, ["Unknown filter 'nofilter'"]);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use liquid_core::{Runtime, Error, ParseTag, Renderable, TagReflection, TagTokenIter};
+use crate::config::Config;
+
+/// jekyll-feed's feed_meta tag, linking to the site's feeds
+#[derive(Debug, Clone)]
+pub struct FeedMetaTag {
+    config: Config,
+}
+
+impl FeedMetaTag {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+    
+    /// A `<link rel="alternate">` element for each format of the posts feed
+    fn links(&self) -> String {
+        let feed_config = match self.config.feed_config() {
+            Some(feed_config) => feed_config,
+            None => return String::new(),
+        };
+        
+        let title = self.config.site_data.title.as_deref().unwrap_or(&self.config.title);
+        feed_config.format_paths(&feed_config.posts_path()).into_iter()
+            .map(|(_, mime_type, path)| format!(
+                "<link type=\"{}\" rel=\"alternate\" href=\"{}\" title=\"{}\" />",
+                mime_type,
+                html_escape::encode_double_quoted_attribute(&self.config.absolute_url(&path)),
+                html_escape::encode_double_quoted_attribute(title)
+            ))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+struct FeedMetaTagReflection;
+
+impl TagReflection for FeedMetaTagReflection {
+    fn tag(&self) -> &str {
+        "feed_meta"
+    }
+    
+    fn description(&self) -> &str {
+        "Outputs link elements for the site's feeds"
+    }
+}
+
+impl ParseTag for FeedMetaTag {
+    fn reflection(&self) -> &dyn TagReflection {
+        &FeedMetaTagReflection
+    }
+    
+    fn parse(&self, _arguments: TagTokenIter, _options: &liquid_core::parser::Language) -> Result<Box<dyn Renderable>, Error> {
+        Ok(Box::new(FeedMetaTagRenderer { links: self.links() }))
+    }
+}
+
+/// Renderer for the feed_meta tag
+#[derive(Debug)]
+struct FeedMetaTagRenderer {
+    links: String,
+}
+
+impl Renderable for FeedMetaTagRenderer {
+    fn render(&self, _runtime: &dyn Runtime) -> Result<String, Error> {
+        Ok(self.links.clone())
+    }
+    
+    fn render_to(&self, writer: &mut dyn std::io::Write, runtime: &dyn Runtime) -> Result<(), Error> {
+        let s = self.render(runtime)?;
+        writer.write_all(s.as_bytes()).map_err(|e| Error::with_msg(format!("Failed to write to output: {}", e)))?;
+        Ok(())
+    }
+}
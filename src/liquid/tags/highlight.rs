@@ -3,6 +3,7 @@ use regex::Regex;
 use std::io::Write;
 use log::{warn, debug, error};
 use crate::config::Config;
+use crate::liquid::errors;
 use std::collections::HashMap;
 use syntect::highlighting::{ThemeSet, Theme};
 use syntect::parsing::{SyntaxSet, SyntaxReference};
@@ -215,7 +216,10 @@ impl HighlightBlock {
         let syntax = match Self::get_syntax_for_language(lang) {
             Some(s) => s,
             None => {
-                warn!("Unknown language '{}', falling back to plain text", lang);
+                match errors::current_templates() {
+                    Some(templates) => warn!("Unknown language '{}' in {}, falling back to plain text", lang, templates),
+                    None => warn!("Unknown language '{}', falling back to plain text", lang),
+                }
                 SYNTAX_SET.find_syntax_plain_text()
             }
         };
@@ -331,7 +335,9 @@ impl ParseBlock for HighlightBlock {
         let options = self.parse_options(&options_str);
 
         // Get the content inside the block
-        let content_str = content.escape_liquid(false)?;
+        // Errors are traced with the tag, to find it in the template
+        let content_str = content.escape_liquid(false)
+            .map_err(|e| e.trace(format!("{{% highlight {} %}}", args_str)))?;
 
         debug!("Highlight block: lang={}, options={:?}", lang, options);
 
@@ -367,7 +373,8 @@ impl Renderable for HighlightBlockRenderer {
     fn render_to(&self, writer: &mut dyn Write, runtime: &dyn Runtime) -> Result<(), Error> {
         let s = self.render(runtime)?;
         writer.write_all(s.as_bytes())
-            .map_err(|e| Error::with_msg(format!("Failed to write to output: {}", e)))?;
+            .map_err(|e| Error::with_msg(format!("Failed to write to output: {}", e))
+                .trace(format!("{{% highlight {} %}}", self.lang)))?;
         Ok(())
     }
 }
\ No newline at end of file
@@ -3,11 +3,13 @@ use crate::config::Config;
 use std::path::PathBuf;
 use std::fs;
 use std::collections::HashMap;
-use log::{info, error};
+use log::{info, debug, error};
 use regex;
 use super::utils::create_default_include_globals;
 use html_escape;
-use crate::liquid::filters;
+use crate::liquid::{errors, filters};
+use crate::liquid::strict::{self, StrictMode};
+use crate::builder::profiler::{self, TemplateKind};
 
 /// Jekyll-compatible include tag
 #[derive(Debug, Clone)]
@@ -20,30 +22,39 @@ impl IncludeTag {
         Self { config }
     }
     
-    fn read_include_file(&self, name: &str) -> Result<String, Error> {
+    /// The path of an include file, trying `.html` for names without an extension
+    fn include_path(&self, name: &str) -> PathBuf {
         let includes_dir = self.config.source.join(&self.config.includes_dir);
         
         // First try with the exact name
-        let mut file_path = includes_dir.join(name);
+        let file_path = includes_dir.join(name);
         
         // If it doesn't exist, try with .html extension
         if !file_path.exists() && !name.contains('.') {
-            file_path = includes_dir.join(format!("{}.html", name));
+            includes_dir.join(format!("{}.html", name))
+        } else {
+            file_path
         }
+    }
+    
+    fn read_include_file(&self, name: &str) -> Result<(String, PathBuf), Error> {
+        let includes_dir = self.config.source.join(&self.config.includes_dir);
+        let file_path = self.include_path(name);
         
         info!("Looking for include file at: {}", file_path.display());
         
         // Check if this is a binary file that we should skip
         if crate::builder::processor::is_binary_file(&file_path) {
             log::info!("Skipping binary file in include: {}", file_path.display());
-            return Ok(format!("<!-- Binary file skipped: {} -->", name));
+            return Ok((format!("<!-- Binary file skipped: {} -->", name), file_path));
         }
         
         // Try to read the file
         match fs::read_to_string(&file_path) {
             Ok(content) => {
                 info!("Successfully read include file: {}", file_path.display());
-                Ok(content)
+                crate::liquid::dependencies::record(&file_path);
+                Ok((content, file_path))
             },
             Err(e) => {
                 error!("Failed to read include file '{}': {}", file_path.display(), e);
@@ -56,7 +67,8 @@ impl IncludeTag {
                         match fs::read_to_string(&html_path) {
                             Ok(content) => {
                                 info!("Successfully read alternative include file: {}", html_path.display());
-                                return Ok(content);
+                                crate::liquid::dependencies::record(&html_path);
+                                return Ok((content, html_path));
                             },
                             Err(e2) => {
                                 error!("Failed to read alternative include file '{}': {}", html_path.display(), e2);
@@ -136,7 +148,14 @@ impl ParseTag for IncludeTag {
         &IncludeTagReflection
     }
     
-    fn parse(&self, mut arguments: TagTokenIter, _options: &liquid_core::parser::Language) -> Result<Box<dyn Renderable>, Error> {
+    fn parse(&self, arguments: TagTokenIter, _options: &liquid_core::parser::Language) -> Result<Box<dyn Renderable>, Error> {
+        Ok(Box::new(self.parse_renderer(arguments)?))
+    }
+}
+
+impl IncludeTag {
+    /// Parse the file name and parameters of an include
+    pub(super) fn parse_renderer(&self, mut arguments: TagTokenIter) -> Result<IncludeTagRenderer, Error> {
         // Collect all arguments as raw tokens first to handle paths with slashes correctly
         let mut all_tokens = Vec::new();
         while let Some(token) = arguments.next() {
@@ -171,6 +190,9 @@ impl ParseTag for IncludeTag {
             if token_index < all_tokens.len() && all_tokens[token_index] == "/" {
                 filename_parts.push("/".to_string());
                 token_index += 1;
+            } else {
+                // Parameters follow the filename
+                break;
             }
         }
 
@@ -183,7 +205,7 @@ impl ParseTag for IncludeTag {
         info!("Include tag remaining tokens: {:?}", all_remaining_tokens);
         
         // Reset arguments iterator
-        let mut arguments = all_remaining_tokens.iter();
+        let arguments = all_remaining_tokens.iter();
         
         // Handle "-" trim directive at the end of the filename
         if first_arg_str.ends_with('-') {
@@ -205,30 +227,32 @@ impl ParseTag for IncludeTag {
         let mut params = HashMap::new();
         let mut current_key = String::new();
         
-        // Check for the "with" keyword to process parameters
-        if let Some(next_token) = arguments.next() {
-            if next_token == "with" {
-                // Process key=value pairs after "with"
-                while let Some(param) = arguments.next() {
-                    let param_str = param.to_string();
-                    
-                    if param_str.contains('=') {
-                        // This is a key=value pair
-                        let parts: Vec<&str> = param_str.splitn(2, '=').collect();
-                        if parts.len() == 2 {
-                            let key = parts[0].trim().to_string();
-                            let value = parts[1].trim().to_string();
-                            params.insert(key, value);
-                        }
-                    } else if current_key.is_empty() {
-                        // This is a key
-                        current_key = param_str;
-                    } else {
-                        // This is a value for the previous key
-                        params.insert(current_key, param_str);
-                        current_key = String::new();
-                    }
+        // Process key=value pairs, which may follow a "with" keyword
+        let mut arguments = arguments.peekable();
+        if arguments.peek().is_some_and(|token| *token == "with") {
+            arguments.next();
+        }
+        for param in arguments {
+            let param_str = param.to_string();
+            
+            if param_str == "=" || param_str == ":" {
+                // Separator between a key and its value
+                continue;
+            } else if current_key.is_empty() && param_str.contains('=') {
+                // This is a key=value pair
+                let parts: Vec<&str> = param_str.splitn(2, '=').collect();
+                if parts.len() == 2 {
+                    let key = parts[0].trim().to_string();
+                    let value = parts[1].trim().to_string();
+                    params.insert(key, value);
                 }
+            } else if current_key.is_empty() {
+                // This is a key
+                current_key = param_str;
+            } else {
+                // This is a value for the previous key
+                params.insert(current_key, param_str);
+                current_key = String::new();
             }
         }
         
@@ -236,30 +260,27 @@ impl ParseTag for IncludeTag {
         info!("Include tag parsed: filename='{}', is_variable={}, params={:?}", 
               filename, is_variable, params);
         
-        Ok(Box::new(IncludeTagRenderer {
+        Ok(IncludeTagRenderer {
             config: self.config.clone(),
             filename,
             is_variable,
             params,
-        }))
+        })
     }
 }
 
 /// Renderer for the include tag
 #[derive(Debug)]
-struct IncludeTagRenderer {
+pub(super) struct IncludeTagRenderer {
     config: Config,
     filename: String,
     is_variable: bool,
     params: HashMap<String, String>,
 }
 
-impl Renderable for IncludeTagRenderer {
-    fn render(&self, runtime: &dyn Runtime) -> Result<String, Error> {
-        info!("Rendering include tag with filename: {}", self.filename);
-        info!("Parameters: {:?}", self.params);
-        
-        // Evaluate the filename
+impl IncludeTagRenderer {
+    /// The name of the included file, looking it up if it is a variable
+    pub(super) fn filename(&self, runtime: &dyn Runtime) -> Result<String, Error> {
         let filename = if self.is_variable {
             let var_name = self.filename.trim_start_matches("{{").trim_end_matches("}}").trim();
             // For variable resolution, we need to parse it as a path
@@ -270,10 +291,69 @@ impl Renderable for IncludeTagRenderer {
         } else {
             self.filename.clone()
         };
+        Ok(filename)
+    }
+
+    /// Evaluate the include's parameters, sorted by name
+    pub(super) fn parameters(&self, runtime: &dyn Runtime) -> Vec<(String, Value)> {
+        let mut parameters = Vec::with_capacity(self.params.len());
+
+        for (key, value_str) in &self.params {
+            // Try to evaluate the value
+            let quoted = value_str.len() >= 2
+                && (value_str.starts_with('"') && value_str.ends_with('"') || value_str.starts_with('\'') && value_str.ends_with('\''));
+            let value = if quoted {
+                // String literal
+                Value::scalar(value_str[1..value_str.len() - 1].to_string())
+            } else if value_str == "true" {
+                // Boolean true
+                info!("Adding boolean param: {}=true", key);
+                Value::scalar(true)
+            } else if value_str == "false" {
+                // Boolean false
+                info!("Adding boolean param: {}=false", key);
+                Value::scalar(false)
+            } else if let Ok(num) = value_str.parse::<f64>() {
+                // Number
+                info!("Adding numeric param: {}={}", key, num);
+                Value::scalar(num)
+            } else {
+                // Try to parse as a variable reference
+                let path: Vec<_> = value_str.split('.').map(ScalarCow::from).collect();
+                match runtime.get(&path) {
+                    Ok(val) => {
+                        info!("Adding variable param: {}={:?}", key, val.to_value());
+                        val.to_value()
+                    },
+                    Err(_) => {
+                        // Fall back to treating it as a string - create an owned string
+                        info!("Adding string param: {}='{}'", key, value_str);
+                        Value::scalar(value_str.clone())
+                    }
+                }
+            };
+            parameters.push((key.clone(), value));
+        }
+
+        parameters.sort_by(|a, b| a.0.cmp(&b.0));
+        parameters
+    }
+
+    /// The path of the included file
+    pub(super) fn path(&self, filename: &str) -> PathBuf {
+        IncludeTag::new(self.config.clone()).include_path(filename)
+    }
+
+    /// Render an include file with evaluated parameters
+    ///
+    /// Returns the output and the path of the file that was read.
+    pub(super) fn render_file(&self, filename: &str, parameters: Vec<(String, Value)>, runtime: &dyn Runtime) -> Result<(String, PathBuf), Error> {
+        let filename = filename.to_string();
+        let _timer = profiler::start(TemplateKind::Include, filename.as_str());
         
         // Read the include file
         let include_tag = IncludeTag::new(self.config.clone());
-        let content = match include_tag.read_include_file(&filename) {
+        let (content, file_path) = match include_tag.read_include_file(&filename) {
             Ok(content) => content,
             Err(e) => {
                 error!("Failed to read include file '{}': {}", filename, e);
@@ -281,6 +361,8 @@ impl Renderable for IncludeTagRenderer {
             }
         };
         
+        let _frame = errors::enter(TemplateKind::Include, &file_path);
+        
         // Create a new scope for the include with parameters
         let mut include_scope = create_default_include_globals();
         
@@ -330,48 +412,17 @@ impl Renderable for IncludeTagRenderer {
             }
         }
         
-        // Add the parameters directly to the global scope
-        for (key, value_str) in &self.params {
-            // Try to evaluate the value
-            if value_str == "true" {
-                // Boolean true
-                info!("Adding boolean param: {}=true", key);
-                globals.insert(key.clone().into(), Value::scalar(true));
-            } else if value_str == "false" {
-                // Boolean false
-                info!("Adding boolean param: {}=false", key);
-                globals.insert(key.clone().into(), Value::scalar(false));
-            } else if let Ok(num) = value_str.parse::<f64>() {
-                // Number
-                info!("Adding numeric param: {}={}", key, num);
-                globals.insert(key.clone().into(), Value::scalar(num));
-            } else {
-                // Try to parse as a variable reference
-                let path: Vec<_> = value_str.split('.').map(ScalarCow::from).collect();
-                match runtime.get(&path) {
-                    Ok(val) => {
-                        info!("Adding variable param: {}={:?}", key, val.to_value());
-                        globals.insert(key.clone().into(), val.to_value());
-                    },
-                    Err(_) => {
-                        // Fall back to treating it as a string - create an owned string
-                        info!("Adding string param: {}='{}'", key, value_str);
-                        globals.insert(key.clone().into(), Value::scalar(value_str.clone()));
-                    }
-                }
-            }
+        // Add the parameters to the include scope, and directly to the global scope
+        for (key, value) in parameters {
+            include_scope.insert(key.clone().into(), value.clone());
+            globals.insert(key.into(), value);
         }
         
         // Add the include scope to globals
         globals.insert("include".into(), Value::Object(include_scope));
         
-        info!("Full globals context: {:?}", globals);
-        
         // Set up a new liquid parser - with custom filters
-        let mut parser_builder = liquid::ParserBuilder::with_stdlib();
-        
-        // Register custom filters - specifically relative_url
-        parser_builder = filters::register_filters(parser_builder, &self.config);
+        let parser_builder = filters::register_filters(liquid::ParserBuilder::with_stdlib(), &self.config);
         
         // Build the parser
         let options = parser_builder.build()?;
@@ -382,12 +433,17 @@ impl Renderable for IncludeTagRenderer {
         // Parse and render the include content - decode HTML entities first
         let decoded_content = html_escape::decode_html_entities(&preprocessed_content).to_string();
         
+        // Report undefined variables and unknown filters, per the Liquid error mode
+        let checked = strict::check(&content, &file_path, &mut globals, &options, StrictMode::from_config(&self.config))
+            .map_err(Error::with_msg)?;
+        
         // Parse the decoded content
-        let template = match options.parse(&decoded_content) {
+        let parsed = checked.apply(&decoded_content);
+        let template = match options.parse(&parsed) {
             Ok(t) => t,
             Err(e) => {
-                error!("Failed to parse include file '{}': {}", filename, e);
-                return Err(Error::with_msg(format!("Error parsing include file '{}': {}", filename, e)));
+                debug!("Failed to parse include file '{}': {}", filename, e);
+                return Err(Error::with_msg(errors::describe(&e, &content, &parsed, &file_path)));
             }
         };
         
@@ -395,13 +451,25 @@ impl Renderable for IncludeTagRenderer {
         let rendered = match template.render(&globals) {
             Ok(r) => r,
             Err(e) => {
-                error!("Failed to render include file '{}': {}", filename, e);
-                return Err(Error::with_msg(format!("Error rendering include file '{}': {}", filename, e)));
+                debug!("Failed to render include file '{}': {}", filename, e);
+                return Err(Error::with_msg(errors::describe(&e, &content, &parsed, &file_path)));
             }
         };
         
         info!("Successfully rendered include file: {}", filename);
         
+        Ok((rendered, file_path))
+    }
+}
+
+impl Renderable for IncludeTagRenderer {
+    fn render(&self, runtime: &dyn Runtime) -> Result<String, Error> {
+        info!("Rendering include tag with filename: {}", self.filename);
+        info!("Parameters: {:?}", self.params);
+        
+        let filename = self.filename(runtime)?;
+        let parameters = self.parameters(runtime);
+        let (rendered, _) = self.render_file(&filename, parameters, runtime)?;
         Ok(rendered)
     }
 
@@ -0,0 +1,147 @@
+use liquid_core::{Runtime, model::Value, Error, ParseTag, Renderable, TagReflection, TagTokenIter};
+use crate::builder::render_state;
+use crate::config::Config;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use log::debug;
+use super::include::{IncludeTag, IncludeTagRenderer};
+
+/// The output of an include for one set of parameters
+#[derive(Debug, Clone)]
+struct CachedInclude {
+    parameters: Vec<(String, Value)>,
+    output: String,
+}
+
+/// Rendered includes of one site by resolved path, shared by its rendering threads
+#[derive(Debug, Clone, Default)]
+pub struct IncludeCache {
+    entries: Arc<Mutex<HashMap<PathBuf, Vec<CachedInclude>>>>,
+}
+
+impl IncludeCache {
+    /// Look up the output of an include rendered with the same parameters
+    fn get(&self, path: &PathBuf, parameters: &[(String, Value)]) -> Option<String> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(path)?.iter()
+            .find(|entry| entry.parameters == parameters)
+            .map(|entry| entry.output.clone())
+    }
+
+    fn insert(&self, path: PathBuf, parameters: Vec<(String, Value)>, output: String) {
+        self.entries.lock().unwrap()
+            .entry(path)
+            .or_default()
+            .push(CachedInclude { parameters, output });
+    }
+}
+
+/// Include tag that renders each file once per set of parameters, like jekyll-include-cache
+///
+/// The output must not depend on the page it is included in, since later
+/// pages reuse the output of the first one.
+#[derive(Debug, Clone)]
+pub struct IncludeCachedTag {
+    include: IncludeTag,
+}
+
+impl IncludeCachedTag {
+    pub fn new(config: Config) -> Self {
+        Self { include: IncludeTag::new(config) }
+    }
+}
+
+struct IncludeCachedTagReflection;
+
+impl TagReflection for IncludeCachedTagReflection {
+    fn tag(&self) -> &str {
+        "include_cached"
+    }
+
+    fn description(&self) -> &str {
+        "Include content from another file, rendering it once per set of parameters"
+    }
+}
+
+impl ParseTag for IncludeCachedTag {
+    fn reflection(&self) -> &dyn TagReflection {
+        &IncludeCachedTagReflection
+    }
+
+    fn parse(&self, arguments: TagTokenIter, _options: &liquid_core::parser::Language) -> Result<Box<dyn Renderable>, Error> {
+        Ok(Box::new(IncludeCachedTagRenderer {
+            include: self.include.parse_renderer(arguments)?,
+        }))
+    }
+}
+
+/// Renderer for the include_cached tag
+#[derive(Debug)]
+struct IncludeCachedTagRenderer {
+    include: IncludeTagRenderer,
+}
+
+impl Renderable for IncludeCachedTagRenderer {
+    fn render(&self, runtime: &dyn Runtime) -> Result<String, Error> {
+        let filename = self.include.filename(runtime)?;
+        let parameters = self.include.parameters(runtime);
+
+        // Without a site being built there is nothing to share the output with
+        let cache = match render_state::with(|state| state.map(|state| state.includes().clone())) {
+            Some(cache) => cache,
+            None => return Ok(self.include.render_file(&filename, parameters, runtime)?.0),
+        };
+
+        let path = self.include.path(&filename);
+        if let Some(output) = cache.get(&path, &parameters) {
+            debug!("Using cached include: {}", path.display());
+            // The page still depends on the include for incremental builds
+            crate::liquid::dependencies::record(&path);
+            return Ok(output);
+        }
+
+        // Threads that miss at the same time render the include once each
+        let (output, _) = self.include.render_file(&filename, parameters.clone(), runtime)?;
+        cache.insert(path, parameters, output.clone());
+        Ok(output)
+    }
+
+    fn render_to(&self, writer: &mut dyn std::io::Write, runtime: &dyn Runtime) -> Result<(), Error> {
+        let s = self.render(runtime)?;
+        writer.write_all(s.as_bytes()).map_err(|e| Error::with_msg(format!("Failed to write to output: {}", e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::site::Site;
+    use crate::test_support::TestSite;
+
+    #[test]
+    fn test_include_cache_per_site() {
+        let sites: Vec<TestSite> = ["first", "second"].iter().map(|name| {
+            let site = TestSite::new(&format!("include-cached-{}", name));
+            site.write("_includes/note.html", &format!("{} {{{{ include.n }}}}", name))
+                .write("a.html", "---\n---\n{% include_cached note.html n=1 %}")
+                .write("b.html", "---\n---\n{% include_cached note.html n=1 %}|{% include_cached note.html n=2 %}");
+            site
+        }).collect();
+
+        // The second site is read while the first one renders, and does not
+        // reuse its includes of the same name
+        let mut first = Site::new(sites[0].config());
+        let mut second = Site::new(sites[1].config());
+        first.read().unwrap();
+        second.read().unwrap();
+        first.render().unwrap();
+        second.render().unwrap();
+        first.write().unwrap();
+        second.write().unwrap();
+
+        assert_eq!(sites[0].read("b.html"), "first 1|first 2");
+        assert_eq!(sites[1].read("a.html"), "second 1");
+        assert_eq!(sites[1].read("b.html"), "second 1|second 2");
+    }
+}
@@ -39,6 +39,7 @@ impl IncludeRelativeTag {
         match fs::read_to_string(&file_path) {
             Ok(content) => {
                 info!("Successfully read include_relative file: {}", file_path.display());
+                crate::liquid::dependencies::record(&file_path);
                 Ok(content)
             },
             Err(e) => {
@@ -53,6 +54,7 @@ impl IncludeRelativeTag {
                     
                     if let Ok(content) = fs::read_to_string(&alt_path) {
                         info!("Successfully read include_relative file from alternative path: {}", alt_path.display());
+                        crate::liquid::dependencies::record(&alt_path);
                         return Ok(content);
                     }
                 }
@@ -64,6 +66,7 @@ impl IncludeRelativeTag {
                 
                 if let Ok(content) = fs::read_to_string(&includes_path) {
                     info!("Successfully read include_relative file from _includes directory: {}", includes_path.display());
+                    crate::liquid::dependencies::record(&includes_path);
                     return Ok(content);
                 }
                 
@@ -308,10 +311,7 @@ impl Renderable for IncludeRelativeTagRenderer {
         info!("Final include_relative scope after parameters: {:?}", include_scope);
         
         // Set up a new liquid parser with custom filters
-        let mut parser_builder = liquid::ParserBuilder::with_stdlib();
-        
-        // Register custom filters - specifically relative_url
-        parser_builder = filters::register_filters(parser_builder, &self.config);
+        let parser_builder = filters::register_filters(liquid::ParserBuilder::with_stdlib(), &self.config);
         
         // Build the parser
         let options = parser_builder.build()?;
@@ -354,8 +354,6 @@ impl Renderable for IncludeRelativeTagRenderer {
         // Add the include scope to globals
         globals.insert("include".into(), Value::Object(include_scope));
         
-        info!("Full globals context: {:?}", globals);
-        
         // Preprocess the content to fix any include tags inside it
         let preprocessed_content = crate::liquid::preprocess::preprocess_liquid(&content);
         
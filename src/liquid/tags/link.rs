@@ -75,9 +75,8 @@ impl LinkTag {
         self.path_to_url(path)
     }
     
-    fn path_to_url(&self, path: &str) -> Result<String, Error> {
-        // Convert a path like "_docs/history.md" to a URL like "/docs/history.html"
-        
+    /// Convert a path like "_docs/history.md" to a URL like "/docs/history.html"
+    pub fn path_to_url(&self, path: &str) -> Result<String, Error> {
         // Remove leading underscore if present (Jekyll collection convention)
         let path_without_underscore = if path.starts_with('_') && !path.starts_with("_posts/") {
             path.trim_start_matches('_')
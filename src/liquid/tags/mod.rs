@@ -1,24 +1,44 @@
+mod feed_meta;
 mod include;
+mod include_cached;
 mod include_relative;
 mod link;
+mod post_url;
 mod raw;
+mod seo;
 mod highlight;
 pub mod utils;
 
+pub use include_cached::IncludeCache;
+pub use link::LinkTag;
+
 use crate::config::Config;
 use liquid::ParserBuilder;
+use liquid_core::partials::PartialCompiler;
 
 /// Register custom tags for use in Liquid templates
-pub fn register_tags(parser_builder: ParserBuilder, config: &Config) -> ParserBuilder {
+pub fn register_tags<P: PartialCompiler>(parser_builder: ParserBuilder<P>, config: &Config) -> ParserBuilder<P> {
     // Register the include tag
     let parser_builder = parser_builder.tag(include::IncludeTag::new(config.clone()));
     
+    // Register the include_cached tag
+    let parser_builder = parser_builder.tag(include_cached::IncludeCachedTag::new(config.clone()));
+    
     // Register the include_relative tag
     let parser_builder = parser_builder.tag(include_relative::IncludeRelativeTag::new(config.clone()));
     
     // Register the link tag
     let parser_builder = parser_builder.tag(link::LinkTag::new(config.clone()));
     
+    // Register the post_url tag
+    let parser_builder = parser_builder.tag(post_url::PostUrlTag::new(config.clone()));
+    
+    // Register the feed_meta tag
+    let parser_builder = parser_builder.tag(feed_meta::FeedMetaTag::new(config.clone()));
+    
+    // Register the seo tag
+    let parser_builder = parser_builder.tag(seo::SeoTag::new(config.clone()));
+    
     // Register the raw block tag
     let parser_builder = parser_builder.block(raw::RawBlock::new());
     
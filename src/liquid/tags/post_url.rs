@@ -0,0 +1,188 @@
+use liquid_core::{Runtime, ValueView, model::ScalarCow, Error, ParseTag, Renderable, TagReflection, TagTokenIter};
+use crate::config::Config;
+use crate::builder::site::{strip_baseurl, with_baseurl};
+use lazy_static::lazy_static;
+use log::{debug, warn};
+use regex::Regex;
+
+lazy_static! {
+    // A post name as Jekyll expects it: optional directories, the date and the slug
+    static ref POST_NAME_RE: Regex = Regex::new(r"^(?:.+/)*\d+-\d+-\d+-.*$").unwrap();
+}
+
+/// How many similar post names to suggest when a post is not found
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Jekyll-compatible post_url tag
+#[derive(Debug, Clone)]
+pub struct PostUrlTag {
+    config: Config,
+}
+
+impl PostUrlTag {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+/// The name of a post as used by post_url, e.g. `2024/2024-01-01-hello`
+///
+/// This is the post's path inside `_posts`, without the extension.
+fn post_name(relative_path: &str) -> String {
+    let path = relative_path.replace('\\', "/");
+    let path = path.strip_prefix("_posts/").unwrap_or(&path);
+    match path.rsplit_once('.') {
+        Some((name, _)) if !name.ends_with('/') => name.to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// The last path segment of a post name
+fn base_name(name: &str) -> &str {
+    name.rsplit('/').next().unwrap_or(name)
+}
+
+/// Post names closest to one that was not found, most similar first
+fn suggestions<'a>(name: &str, names: &'a [String]) -> Vec<&'a str> {
+    // Names share the shape of a date, so only allow a few edits
+    let limit = (name.len() / 5).max(2);
+    let mut close: Vec<(usize, &str)> = names.iter()
+        .map(|candidate| {
+            let distance = strsim::levenshtein(name, candidate)
+                .min(strsim::levenshtein(base_name(name), base_name(candidate)));
+            (distance, candidate.as_str())
+        })
+        .filter(|(distance, _)| *distance <= limit)
+        .collect();
+    close.sort();
+    close.into_iter().take(MAX_SUGGESTIONS).map(|(_, candidate)| candidate).collect()
+}
+
+struct PostUrlTagReflection;
+
+impl TagReflection for PostUrlTagReflection {
+    fn tag(&self) -> &str {
+        "post_url"
+    }
+
+    fn description(&self) -> &str {
+        "Outputs the URL of a post from its name, e.g. 2024-01-01-hello"
+    }
+}
+
+impl ParseTag for PostUrlTag {
+    fn reflection(&self) -> &dyn TagReflection {
+        &PostUrlTagReflection
+    }
+
+    fn parse(&self, arguments: TagTokenIter, _options: &liquid_core::parser::Language) -> Result<Box<dyn Renderable>, Error> {
+        // Names are quoted by the preprocessor, since they aren't valid Liquid
+        let name: String = arguments.map(|token| token.as_str().to_string()).collect();
+        let name = name.trim_matches('"').trim_matches('\'').trim().trim_start_matches('/').to_string();
+        debug!("post_url tag: parsing name '{}'", name);
+
+        if !POST_NAME_RE.is_match(&name) {
+            return Err(Error::with_msg(format!(
+                "Could not parse name of post \"{}\" in tag 'post_url'. Make sure the post exists and the name is correct.",
+                name
+            )));
+        }
+
+        Ok(Box::new(PostUrlTagRenderer {
+            config: self.config.clone(),
+            name,
+        }))
+    }
+}
+
+/// Renderer for the post_url tag
+#[derive(Debug)]
+struct PostUrlTagRenderer {
+    config: Config,
+    name: String,
+}
+
+impl PostUrlTagRenderer {
+    /// A post's URL with the baseurl once, as post URLs already start with it
+    fn relative_url(&self, url: &str) -> String {
+        with_baseurl(&strip_baseurl(url, &self.config), &self.config)
+    }
+}
+
+impl Renderable for PostUrlTagRenderer {
+    fn render(&self, runtime: &dyn Runtime) -> Result<String, Error> {
+        let posts = runtime.try_get(&[ScalarCow::new("site"), ScalarCow::new("posts")]);
+        let posts: Vec<(String, String)> = posts.as_ref()
+            .and_then(|posts| posts.as_array())
+            .map(|posts| posts.values()
+                .filter_map(|post| {
+                    let post = post.as_object()?;
+                    let name = post_name(&post.get("relative_path")?.to_kstr());
+                    let url = post.get("url")?.to_kstr().to_string();
+                    Some((name, url))
+                })
+                .collect())
+            .unwrap_or_default();
+
+        if let Some((_, url)) = posts.iter().find(|(name, _)| *name == self.name) {
+            return Ok(self.relative_url(url));
+        }
+
+        // Like Jekyll, fall back to matching the date and slug in any directory
+        let base = base_name(&self.name);
+        if let Some((name, url)) = posts.iter().find(|(name, _)| base_name(name) == base) {
+            warn!("post_url: '{}' did not match a post exactly and resolved to '{}'. Use the post's full name, including its directories.",
+                  self.name, name);
+            return Ok(self.relative_url(url));
+        }
+
+        let names: Vec<String> = posts.into_iter().map(|(name, _)| name).collect();
+        let mut message = format!(
+            "Could not find post \"{}\" in tag 'post_url'. Make sure the post exists and the name is correct.",
+            self.name
+        );
+        let similar = suggestions(&self.name, &names);
+        if !similar.is_empty() {
+            message.push_str(&format!(" Did you mean: {}?", similar.join(", ")));
+        }
+        Err(Error::with_msg(message))
+    }
+
+    fn render_to(&self, writer: &mut dyn std::io::Write, runtime: &dyn Runtime) -> Result<(), Error> {
+        let s = self.render(runtime)?;
+        writer.write_all(s.as_bytes()).map_err(|e| Error::with_msg(format!("Failed to write to output: {}", e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestSite;
+
+    #[test]
+    fn test_post_names_and_suggestions() {
+        assert_eq!(post_name("2024-01-01-hello.md"), "2024-01-01-hello");
+        assert_eq!(post_name("rust\\2024-07-01-ownership.markdown"), "rust/2024-07-01-ownership");
+
+        let names = vec![
+            "2024-01-01-hello".to_string(),
+            "2024-01-02-hello-again".to_string(),
+            "2024-03-01-second".to_string(),
+            "rust/2024-07-01-ownership".to_string(),
+        ];
+        assert_eq!(suggestions("2024-01-01-helo", &names), vec!["2024-01-01-hello"]);
+        assert_eq!(suggestions("2024-07-01-ownership", &names), vec!["rust/2024-07-01-ownership"]);
+        assert!(suggestions("2019-05-05-unrelated-title", &names).is_empty());
+    }
+
+    #[test]
+    fn test_post_url_with_baseurl() {
+        let site = TestSite::new("post-url");
+        site.write("_posts/2024-01-01-hello.md", "---\ntitle: Hello\n---\nHello")
+            .write("index.html", "---\n---\n{% post_url 2024-01-01-hello %}");
+
+        site.build(Config { base_url: "/blog".to_string(), ..site.config() });
+        assert_eq!(site.read("index.html"), "/blog/2024/01/01/hello");
+    }
+}
@@ -0,0 +1,444 @@
+use liquid_core::{Runtime, ValueView, model::{Object, ScalarCow, Value}, Error, ParseTag, Renderable, TagReflection, TagTokenIter};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::json;
+use chrono::SecondsFormat;
+use crate::config::Config;
+use crate::builder::site::absolute_document_url;
+use crate::utils::date::{local, parse_date};
+
+lazy_static! {
+    static ref HTML_TAG_RE: Regex = Regex::new(r"<[^>]*>").unwrap();
+    static ref HOMEPAGE_OR_ABOUT_RE: Regex = Regex::new(r"^/(about/)?(index\.html?)?$").unwrap();
+    static ref TITLE_OPTION_RE: Regex = Regex::new(r"\btitle\s*=\s*false\b").unwrap();
+}
+
+/// Separator between the page and site titles
+const TITLE_SEPARATOR: &str = " | ";
+
+/// Meta names of webmaster verifications, by service
+const VERIFICATIONS: &[(&str, &str)] = &[
+    ("google", "google-site-verification"),
+    ("bing", "msvalidate.01"),
+    ("alexa", "alexaVerifyID"),
+    ("yandex", "yandex-verification"),
+    ("baidu", "baidu-site-verification"),
+    ("facebook", "facebook-domain-verification"),
+];
+
+/// jekyll-seo-tag's seo tag, describing the page to search engines and social networks
+///
+/// Values come from the page, then the site, then defaults, and
+/// `{% seo title=false %}` leaves out the `<title>` element.
+#[derive(Debug, Clone)]
+pub struct SeoTag {
+    config: Config,
+}
+
+impl SeoTag {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+struct SeoTagReflection;
+
+impl TagReflection for SeoTagReflection {
+    fn tag(&self) -> &str {
+        "seo"
+    }
+
+    fn description(&self) -> &str {
+        "Outputs the page's title, description, canonical URL, social and JSON-LD metadata"
+    }
+}
+
+impl ParseTag for SeoTag {
+    fn reflection(&self) -> &dyn TagReflection {
+        &SeoTagReflection
+    }
+
+    fn parse(&self, arguments: TagTokenIter, _options: &liquid_core::parser::Language) -> Result<Box<dyn Renderable>, Error> {
+        let arguments: Vec<String> = arguments.map(|token| token.as_str().to_string()).collect();
+        Ok(Box::new(SeoTagRenderer {
+            config: self.config.clone(),
+            title: !TITLE_OPTION_RE.is_match(&arguments.join(" ")),
+        }))
+    }
+}
+
+/// Renderer for the seo tag
+#[derive(Debug)]
+struct SeoTagRenderer {
+    config: Config,
+    title: bool,
+}
+
+/// A non-empty string from an object
+fn text(object: &Object, key: &str) -> Option<String> {
+    match object.get(key) {
+        Some(value @ Value::Scalar(_)) => Some(value.to_kstr().trim().to_string()).filter(|text| !text.is_empty()),
+        _ => None,
+    }
+}
+
+/// An object inside an object
+fn object<'a>(parent: &'a Object, key: &str) -> Option<&'a Object> {
+    match parent.get(key) {
+        Some(Value::Object(object)) => Some(object),
+        _ => None,
+    }
+}
+
+/// A variable of the template, or an empty object
+fn global(runtime: &dyn Runtime, name: &str) -> Object {
+    runtime.try_get(&[ScalarCow::new(name.to_string())])
+        .and_then(|value| value.to_value().into_object())
+        .unwrap_or_default()
+}
+
+/// Plain text for a description, like jekyll-seo-tag's strip_html and normalize_whitespace
+fn plain_text(html: &str) -> String {
+    let text = HTML_TAG_RE.replace_all(html, "");
+    html_escape::decode_html_entities(&text).split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A date in XML Schema format, in the site's timezone
+fn xmlschema(date: &str) -> String {
+    match parse_date(date) {
+        Some(date) => local(date).to_rfc3339_opts(SecondsFormat::Secs, false),
+        None => date.to_string(),
+    }
+}
+
+/// An attribute value
+fn attr(text: &str) -> String {
+    html_escape::encode_double_quoted_attribute(text).to_string()
+}
+
+impl SeoTagRenderer {
+    /// The page's author, from its front matter or the site, with details from `_data/authors`
+    fn author(&self, page: &Object, site: &Object) -> Option<Object> {
+        let author = page.get("author")
+            .filter(|author| !author.is_nil())
+            .or_else(|| match page.get("authors") {
+                Some(Value::Array(authors)) => authors.first(),
+                _ => None,
+            })
+            .or_else(|| site.get("author"))?;
+
+        if let Value::Object(author) = author {
+            return Some(author.clone());
+        }
+        let name = author.to_kstr().trim().to_string();
+        if name.is_empty() {
+            return None;
+        }
+        // Like jekyll-seo-tag, a plain name doubles as a Twitter handle
+        let mut resolved = Object::new();
+        resolved.insert("name".into(), Value::scalar(name.clone()));
+        resolved.insert("twitter".into(), Value::scalar(name.clone()));
+        let data = object(site, "data").and_then(|data| object(data, "authors")).and_then(|authors| object(authors, &name));
+        for (key, value) in data.into_iter().flatten() {
+            resolved.insert(key.clone(), value.clone());
+        }
+        Some(resolved)
+    }
+
+    /// An absolute URL for a path of the site
+    fn absolute_url(&self, path: &str) -> String {
+        self.config.absolute_url(path)
+    }
+
+    fn seo(&self, page: &Object, site: &Object, paginator: &Object) -> String {
+        let seo = object(page, "seo").cloned().unwrap_or_default();
+        let site_title = text(site, "title").or_else(|| text(site, "name"));
+        let site_description = text(site, "description");
+        let site_tagline = text(site, "tagline").or_else(|| site_description.clone());
+        let page_title = text(&seo, "title").or_else(|| text(page, "title")).or_else(|| site_title.clone());
+
+        let url = text(page, "url").unwrap_or_else(|| "/".to_string());
+        let homepage_or_about = HOMEPAGE_OR_ABOUT_RE.is_match(&url);
+        let canonical_url = text(page, "canonical_url").map(|url| self.absolute_url(&url)).unwrap_or_else(|| {
+            let url = url.strip_suffix("index.html").unwrap_or(&url);
+            // Documents have a collection, and URLs which already start with the baseurl
+            if page.contains_key("collection") {
+                absolute_document_url(url, &self.config)
+            } else {
+                self.absolute_url(url)
+            }
+        });
+
+        let mut title = match (&site_title, &page_title) {
+            (Some(site_title), Some(page_title)) if page_title != site_title => format!("{}{}{}", page_title, TITLE_SEPARATOR, site_title),
+            (Some(site_title), _) if site_description.is_some() => format!("{}{}{}", site_title, TITLE_SEPARATOR, site_tagline.clone().unwrap_or_default()),
+            _ => page_title.clone().or_else(|| site_title.clone()).unwrap_or_default(),
+        };
+        let current = paginator.get("page").map(|page| page.to_kstr().to_string()).and_then(|page| page.parse::<u64>().ok());
+        let total = paginator.get("total_pages").map(|total| total.to_kstr().to_string());
+        if let (Some(current), Some(total)) = (current, total) {
+            if current > 1 {
+                title = format!("Page {} of {} for {}", current, total, title);
+            }
+        }
+
+        let description = text(&seo, "description")
+            .or_else(|| text(page, "description"))
+            .or_else(|| text(page, "excerpt"))
+            .or_else(|| site_description.clone())
+            .map(|description| plain_text(&description))
+            .filter(|description| !description.is_empty());
+
+        let image = text(&seo, "image").map(|path| (path, Object::new())).or_else(|| match page.get("image") {
+            Some(Value::Object(image)) => text(image, "path").map(|path| (path, image.clone())),
+            Some(_) => text(page, "image").map(|path| (path, Object::new())),
+            None => None,
+        });
+        let image_url = image.as_ref().map(|(path, _)| self.absolute_url(path));
+
+        let author = self.author(page, site);
+        let author_name = author.as_ref().and_then(|author| text(author, "name").or_else(|| text(author, "twitter")));
+        let date = text(page, "date").map(|date| xmlschema(&date));
+        let modified = text(page, "last_modified_at").map(|date| xmlschema(&date)).or_else(|| date.clone());
+        let locale = text(page, "locale").or_else(|| text(page, "lang"))
+            .or_else(|| text(site, "locale")).or_else(|| text(site, "lang"))
+            .unwrap_or_else(|| "en_US".to_string())
+            .replace('-', "_");
+
+        let mut tags = vec![format!("<!-- Begin Rustyll SEO tag v{} -->", env!("CARGO_PKG_VERSION"))];
+        if self.title && !title.is_empty() {
+            tags.push(format!("<title>{}</title>", html_escape::encode_text(&title)));
+        }
+        tags.push(format!("<meta name=\"generator\" content=\"Rustyll v{}\" />", env!("CARGO_PKG_VERSION")));
+        if let Some(page_title) = &page_title {
+            tags.push(format!("<meta property=\"og:title\" content=\"{}\" />", attr(page_title)));
+        }
+        if let Some(author_name) = &author_name {
+            tags.push(format!("<meta name=\"author\" content=\"{}\" />", attr(author_name)));
+        }
+        tags.push(format!("<meta property=\"og:locale\" content=\"{}\" />", attr(&locale)));
+        if let Some(description) = &description {
+            tags.push(format!("<meta name=\"description\" content=\"{}\" />", attr(description)));
+            tags.push(format!("<meta property=\"og:description\" content=\"{}\" />", attr(description)));
+        }
+        tags.push(format!("<link rel=\"canonical\" href=\"{}\" />", attr(&canonical_url)));
+        tags.push(format!("<meta property=\"og:url\" content=\"{}\" />", attr(&canonical_url)));
+        if let Some(site_title) = &site_title {
+            tags.push(format!("<meta property=\"og:site_name\" content=\"{}\" />", attr(site_title)));
+        }
+        if let (Some(image_url), Some((_, image))) = (&image_url, &image) {
+            tags.push(format!("<meta property=\"og:image\" content=\"{}\" />", attr(image_url)));
+            for key in ["height", "width", "alt"] {
+                if let Some(value) = text(image, key) {
+                    tags.push(format!("<meta property=\"og:image:{}\" content=\"{}\" />", key, attr(&value)));
+                }
+            }
+        }
+        if let Some(date) = &date {
+            tags.push("<meta property=\"og:type\" content=\"article\" />".to_string());
+            tags.push(format!("<meta property=\"article:published_time\" content=\"{}\" />", attr(date)));
+        } else {
+            tags.push("<meta property=\"og:type\" content=\"website\" />".to_string());
+        }
+        for (rel, key) in [("prev", "previous_page_path"), ("next", "next_page_path")] {
+            if let Some(path) = text(paginator, key) {
+                tags.push(format!("<link rel=\"{}\" href=\"{}\" />", rel, attr(&self.absolute_url(&path))));
+            }
+        }
+
+        let twitter = object(site, "twitter").cloned().unwrap_or_default();
+        let card = text(&seo, "twitter_card")
+            .or_else(|| text(&twitter, "card"))
+            .unwrap_or_else(|| if image_url.is_some() { "summary_large_image" } else { "summary" }.to_string());
+        tags.push(format!("<meta name=\"twitter:card\" content=\"{}\" />", attr(&card)));
+        if let Some(image_url) = &image_url {
+            tags.push(format!("<meta property=\"twitter:image\" content=\"{}\" />", attr(image_url)));
+        }
+        if let Some(page_title) = &page_title {
+            tags.push(format!("<meta property=\"twitter:title\" content=\"{}\" />", attr(page_title)));
+        }
+        if let Some(username) = text(&twitter, "username") {
+            tags.push(format!("<meta name=\"twitter:site\" content=\"@{}\" />", attr(username.trim_start_matches('@'))));
+        }
+        if let Some(creator) = author.as_ref().and_then(|author| text(author, "twitter")) {
+            tags.push(format!("<meta name=\"twitter:creator\" content=\"@{}\" />", attr(creator.trim_start_matches('@'))));
+        }
+
+        if let Some(facebook) = object(site, "facebook") {
+            for (key, property) in [("app_id", "fb:app_id"), ("publisher", "article:publisher"), ("admins", "fb:admins")] {
+                if let Some(value) = text(facebook, key) {
+                    tags.push(format!("<meta property=\"{}\" content=\"{}\" />", property, attr(&value)));
+                }
+            }
+        }
+        if let Some(verifications) = object(site, "webmaster_verifications") {
+            for (service, name) in VERIFICATIONS {
+                if let Some(value) = text(verifications, service) {
+                    tags.push(format!("<meta name=\"{}\" content=\"{}\" />", name, attr(&value)));
+                }
+            }
+        }
+
+        // JSON-LD, a WebSite for the home page, a BlogPosting for dated pages
+        let schema_type = if homepage_or_about {
+            "WebSite"
+        } else if date.is_some() {
+            "BlogPosting"
+        } else {
+            "WebPage"
+        };
+        let mut json_ld = json!({
+            "@context": "https://schema.org",
+            "@type": schema_type,
+            "url": canonical_url,
+        });
+        if let Some(page_title) = &page_title {
+            json_ld["headline"] = json!(page_title);
+        }
+        if let Some(description) = &description {
+            json_ld["description"] = json!(description);
+        }
+        if let Some(image_url) = &image_url {
+            json_ld["image"] = json!(image_url);
+        }
+        if let Some(date) = &date {
+            json_ld["datePublished"] = json!(date);
+        }
+        if let Some(modified) = &modified {
+            json_ld["dateModified"] = json!(modified);
+        }
+        if let Some(author_name) = &author_name {
+            let mut person = json!({ "@type": "Person", "name": author_name });
+            if let Some(url) = author.as_ref().and_then(|author| text(author, "url")) {
+                person["url"] = json!(url);
+            }
+            json_ld["author"] = person;
+        }
+        if schema_type == "BlogPosting" {
+            json_ld["mainEntityOfPage"] = json!({ "@type": "WebPage", "@id": canonical_url });
+        }
+        if homepage_or_about {
+            if let Some(site_title) = &site_title {
+                json_ld["name"] = json!(site_title);
+            }
+            let links: Vec<String> = match object(site, "social").and_then(|social| social.get("links")) {
+                Some(Value::Array(links)) => links.iter().map(|link| link.to_kstr().to_string()).collect(),
+                _ => Vec::new(),
+            };
+            if !links.is_empty() {
+                json_ld["sameAs"] = json!(links);
+            }
+        }
+        if let Some(logo) = text(site, "logo") {
+            let mut publisher = json!({
+                "@type": "Organization",
+                "logo": { "@type": "ImageObject", "url": self.absolute_url(&logo) },
+            });
+            if let Some(author_name) = &author_name {
+                publisher["name"] = json!(author_name);
+            }
+            json_ld["publisher"] = publisher;
+        }
+        // The JSON must not close the script element
+        tags.push(format!("<script type=\"application/ld+json\">\n{}</script>", json_ld.to_string().replace("</", "<\\/")));
+        tags.push("<!-- End Rustyll SEO tag -->".to_string());
+
+        tags.join("\n")
+    }
+}
+
+impl Renderable for SeoTagRenderer {
+    fn render(&self, runtime: &dyn Runtime) -> Result<String, Error> {
+        let page = global(runtime, "page");
+        let site = global(runtime, "site");
+        let paginator = global(runtime, "paginator");
+        Ok(self.seo(&page, &site, &paginator))
+    }
+
+    fn render_to(&self, writer: &mut dyn std::io::Write, runtime: &dyn Runtime) -> Result<(), Error> {
+        let s = self.render(runtime)?;
+        writer.write_all(s.as_bytes()).map_err(|e| Error::with_msg(format!("Failed to write to output: {}", e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use crate::liquid::create_jekyll_parser;
+    use crate::test_support::TestSite;
+    use super::*;
+
+    fn render(template: &str, globals: serde_json::Value, config: &Config) -> String {
+        let parser = create_jekyll_parser(config, HashMap::new()).unwrap();
+        let globals: Object = serde_json::from_value(globals).unwrap();
+        parser.parse(template).unwrap().render(&globals).unwrap()
+    }
+
+    #[test]
+    fn test_seo_tag() {
+        let config = Config { url: Some("https://example.com".to_string()), ..Config::default() };
+        let site = json!({
+            "title": "My Site",
+            "description": "A site",
+            "logo": "/logo.png",
+            "twitter": { "username": "mysite" },
+            "data": { "authors": { "jane": { "name": "Jane Doe", "twitter": "@jane" } } },
+        });
+
+        let html = render("{% seo %}", json!({
+            "site": site,
+            "page": {
+                "title": "Hello",
+                "url": "/2024/01/01/hello.html",
+                "collection": "posts",
+                "date": "2024-01-01 00:00:00 +0000",
+                "excerpt": "<p>The  first\npost</p>",
+                "image": "/hello.png",
+                "author": "jane",
+            },
+        }), &config);
+        assert!(html.contains("<title>Hello | My Site</title>"));
+        assert!(html.contains("<meta name=\"description\" content=\"The first post\" />"));
+        assert!(html.contains("<link rel=\"canonical\" href=\"https://example.com/2024/01/01/hello.html\" />"));
+        assert!(html.contains("<meta property=\"og:image\" content=\"https://example.com/hello.png\" />"));
+        assert!(html.contains("<meta property=\"og:type\" content=\"article\" />"));
+        assert!(html.contains("<meta property=\"article:published_time\" content=\"2024-01-01T00:00:00+00:00\" />"));
+        assert!(html.contains("<meta name=\"twitter:card\" content=\"summary_large_image\" />"));
+        assert!(html.contains("<meta name=\"twitter:site\" content=\"@mysite\" />"));
+        assert!(html.contains("<meta name=\"twitter:creator\" content=\"@jane\" />"));
+        assert!(html.contains("\"@type\":\"BlogPosting\""));
+        assert!(html.contains("\"author\":{\"@type\":\"Person\",\"name\":\"Jane Doe\"}"));
+
+        // The home page is the WebSite, and paginated pages link to their neighbours
+        let html = render("{% seo title=false %}", json!({
+            "site": site,
+            "page": { "title": "", "url": "/page2/index.html" },
+            "paginator": { "page": 2, "total_pages": 3, "previous_page_path": "/", "next_page_path": "/page3/" },
+        }), &config);
+        assert!(!html.contains("<title>"));
+        assert!(html.contains("<link rel=\"prev\" href=\"https://example.com/\" />"));
+        assert!(html.contains("<link rel=\"next\" href=\"https://example.com/page3/\" />"));
+        assert!(html.contains("<meta name=\"description\" content=\"A site\" />"));
+
+        let html = render("{% seo %}", json!({ "site": site, "page": { "url": "/index.html" } }), &config);
+        assert!(html.contains("<title>My Site | A site</title>"));
+        assert!(html.contains("\"@type\":\"WebSite\""));
+        assert!(html.contains("<link rel=\"canonical\" href=\"https://example.com/\" />"));
+    }
+
+    #[test]
+    fn test_seo_rendered_excerpt() {
+        let site = TestSite::new("seo");
+        site.write("_layouts/post.html", "<head>{% seo %}</head>{{ content }}")
+            .write("_posts/2024-01-01-hello.md",
+                   "---\nlayout: post\ntitle: Hello\ntags: [rust]\n---\nA *short* post about {{ page.tags | first }} & more\n\nBody");
+
+        site.build(Config { url: Some("https://example.com".to_string()), ..site.config() });
+
+        // The excerpt is converted and rendered before it becomes the description
+        let html = site.read("2024/01/01/hello");
+        assert!(html.contains("<meta name=\"description\" content=\"A short post about rust &amp; more\" />"));
+        assert!(html.contains("\"description\":\"A short post about rust & more\""));
+    }
+}
@@ -1,48 +1,404 @@
-use comrak::Options;
+use std::io::Write;
 
-/// Create default ComrakOptions with GitHub Flavored Markdown settings
-pub fn create_comrak_options<'a>() -> Options<'a> {
+use comrak::html::{format_node_default, ChildRendering, Context};
+use comrak::nodes::{AstNode, ListType, NodeValue};
+use comrak::{create_formatter, parse_document, Arena, Options};
+
+use crate::config::{Config, KramdownConfig};
+use crate::builder::diagnostics::{self, Category};
+use crate::markdown::kramdown::{self, node_key, NodeAttributes};
+use crate::markdown::math::MathRenderer;
+use crate::markdown::renderer::SyntaxHighlighter;
+
+/// The quotes comrak's smart punctuation produces, in kramdown's `smart_quotes` order
+const SMART_QUOTES: [char; 4] = ['\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}'];
+
+/// Starts mermaid once the page has loaded, written after each diagram
+const MERMAID_SCRIPT: &str = "<script>\n\
+document.addEventListener('DOMContentLoaded', function() {\n  \
+if (typeof mermaid !== 'undefined') {\n    mermaid.initialize();\n  }\n\
+});\n\
+</script>\n";
+
+/// How code blocks and math are rendered
+#[derive(Clone, Default)]
+pub struct CodeRendering {
+    /// Highlights code blocks, which are left to comrak without one
+    pub highlighter: Option<SyntaxHighlighter>,
+    /// Whether `mermaid` code blocks are diagrams
+    pub diagrams: bool,
+    /// Renders math and `math` code blocks to MathML, which is otherwise
+    /// left as LaTeX for a script
+    pub math: Option<MathRenderer>,
+}
+
+/// What the formatter needs besides the document
+struct Rendering {
+    attributes: NodeAttributes,
+    code: CodeRendering,
+}
+
+// Nodes with attributes from kramdown's extensions get their own opening
+// tags, code blocks and math are rendered for highlighters and scripts,
+// everything else is rendered the way comrak does
+create_formatter!(KramdownFormatter<Rendering>, {
+    NodeValue::Heading(ref heading) => |context, node, entering| {
+        if entering {
+            context.cr()?;
+            write!(context, "<h{}", heading.level)?;
+            write_attributes(context, node)?;
+            context.write_all(b">")?;
+        } else {
+            writeln!(context, "</h{}>", heading.level)?;
+        }
+    },
+    NodeValue::Paragraph => |context, node, entering| {
+        // Display math on its own is a block, not part of a paragraph
+        if is_display_math(node.first_child()) && node.first_child().unwrap().next_sibling().is_none() {
+            match entering {
+                true => context.cr()?,
+                false => context.write_all(b"\n")?,
+            }
+            return Ok(ChildRendering::HTML);
+        }
+        if !has_attributes(context, node) {
+            return format_node_default(context, node, entering);
+        }
+        if entering {
+            context.cr()?;
+            context.write_all(b"<p")?;
+            write_attributes(context, node)?;
+            context.write_all(b">")?;
+        } else {
+            context.write_all(b"</p>\n")?;
+        }
+    },
+    NodeValue::List(ref list) => |context, node, entering| {
+        if !entering || !has_attributes(context, node) {
+            return format_node_default(context, node, entering);
+        }
+        context.cr()?;
+        match list.list_type {
+            ListType::Bullet => context.write_all(b"<ul")?,
+            ListType::Ordered => context.write_all(b"<ol")?,
+        }
+        write_attributes(context, node)?;
+        if list.list_type == ListType::Ordered && list.start != 1 {
+            write!(context, " start=\"{}\"", list.start)?;
+        }
+        context.write_all(b">\n")?;
+    },
+    NodeValue::Table(_) => |context, node, entering| {
+        return open_tag(context, node, entering, "table", true);
+    },
+    NodeValue::BlockQuote => |context, node, entering| {
+        return open_tag(context, node, entering, "blockquote", true);
+    },
+    NodeValue::Emph => |context, node, entering| {
+        return open_tag(context, node, entering, "em", false);
+    },
+    NodeValue::Strong => |context, node, entering| {
+        return open_tag(context, node, entering, "strong", false);
+    },
+    NodeValue::Strikethrough => |context, node, entering| {
+        return open_tag(context, node, entering, "del", false);
+    },
+    NodeValue::Code(ref code) => |context, node, entering| {
+        if !has_attributes(context, node) {
+            return format_node_default(context, node, entering);
+        }
+        if entering {
+            context.write_all(b"<code")?;
+            write_attributes(context, node)?;
+            context.write_all(b">")?;
+            context.escape(code.literal.as_bytes())?;
+            context.write_all(b"</code>")?;
+        }
+    },
+    NodeValue::CodeBlock(ref code) => |context, node, entering| {
+        let language = code.info.split_whitespace().next().unwrap_or("text");
+        if !entering {
+            return Ok(ChildRendering::HTML);
+        }
+        if context.user.code.diagrams && language == "mermaid" {
+            context.cr()?;
+            context.write_all(b"<div class=\"mermaid\"")?;
+            write_attributes(context, node)?;
+            context.write_all(b">")?;
+            context.escape(code.literal.as_bytes())?;
+            context.write_all(b"</div>\n")?;
+            context.write_all(MERMAID_SCRIPT.as_bytes())?;
+            return Ok(ChildRendering::Skip);
+        }
+        if context.user.code.math.is_some() && language == "math" {
+            context.cr()?;
+            write_math(context, &code.literal, true)?;
+            context.write_all(b"\n")?;
+            return Ok(ChildRendering::Skip);
+        }
+        let Some(highlighted) = context.user.code.highlighter.as_ref().map(|highlighter| highlighter.highlight_code(&code.literal, language)) else {
+            return format_node_default(context, node, entering);
+        };
+
+        context.cr()?;
+        let wrapped = has_attributes(context, node);
+        if wrapped {
+            context.write_all(b"<div")?;
+            write_attributes(context, node)?;
+            context.write_all(b">\n")?;
+        }
+        context.write_all(highlighted.as_bytes())?;
+        context.write_all(if wrapped { b"\n</div>\n" } else { b"\n" })?;
+        return Ok(ChildRendering::Skip);
+    },
+    NodeValue::Math(ref math) => |context, node, entering| {
+        if entering {
+            write_math(context, &math.literal, math.display_math)?;
+        }
+    },
+    NodeValue::Link(ref link) => |context, node, entering| {
+        if !entering || !has_attributes(context, node) {
+            return format_node_default(context, node, entering);
+        }
+        context.write_all(b"<a href=\"")?;
+        context.escape_href(link.url.as_bytes())?;
+        context.write_all(b"\"")?;
+        if !link.title.is_empty() {
+            context.write_all(b" title=\"")?;
+            context.escape(link.title.as_bytes())?;
+            context.write_all(b"\"")?;
+        }
+        write_attributes(context, node)?;
+        context.write_all(b">")?;
+    },
+    NodeValue::Image(ref link) => |context, node, entering| {
+        if !has_attributes(context, node) {
+            return format_node_default(context, node, entering);
+        }
+        if entering {
+            context.write_all(b"<img src=\"")?;
+            context.escape_href(link.url.as_bytes())?;
+            context.write_all(b"\" alt=\"")?;
+            return Ok(ChildRendering::Plain);
+        }
+        context.write_all(b"\"")?;
+        if !link.title.is_empty() {
+            context.write_all(b" title=\"")?;
+            context.escape(link.title.as_bytes())?;
+            context.write_all(b"\"")?;
+        }
+        write_attributes(context, node)?;
+        context.write_all(b" />")?;
+    },
+});
+
+/// Write the start tag of a node with attributes, or leave the node to comrak
+fn open_tag<'a>(context: &mut Context<Rendering>, node: &'a AstNode<'a>, entering: bool, tag: &str, block: bool) -> std::io::Result<ChildRendering> {
+    if !entering || !has_attributes(context, node) {
+        return format_node_default(context, node, entering);
+    }
+    if block {
+        context.cr()?;
+    }
+    write!(context, "<{}", tag)?;
+    write_attributes(context, node)?;
+    context.write_all(if block { b">\n" } else { b">" })?;
+    Ok(ChildRendering::HTML)
+}
+
+fn has_attributes(context: &Context<Rendering>, node: &AstNode) -> bool {
+    context.user.attributes.get(&node_key(node)).is_some_and(|attributes| !attributes.is_empty())
+}
+
+/// Write a node's attributes from kramdown's extensions
+fn write_attributes(context: &mut Context<Rendering>, node: &AstNode) -> std::io::Result<()> {
+    let attributes = context.user.attributes.get(&node_key(node)).cloned().unwrap_or_default();
+    for (name, value) in attributes {
+        write!(context, " {}=\"", name)?;
+        context.escape(value.as_bytes())?;
+        context.write_all(b"\"")?;
+    }
+    Ok(())
+}
+
+/// Write math as MathML, or as LaTeX if there is no renderer or it is invalid
+fn write_math(context: &mut Context<Rendering>, latex: &str, display: bool) -> std::io::Result<()> {
+    let rendered = context.user.code.math.as_ref().map(|math| math.render(latex, display));
+    let mathml = match rendered {
+        Some(Ok(mathml)) => Some(mathml),
+        Some(Err(e)) => {
+            diagnostics::warning(Category::Markdown, &diagnostics::current_file(), format!("Invalid math `{}`: {}", latex.trim(), e));
+            None
+        }
+        None => None,
+    };
+
+    context.write_all(if display { b"<div class=\"math display\">" } else { b"<span class=\"math inline\">" })?;
+    match mathml {
+        Some(mathml) => context.write_all(mathml.as_bytes())?,
+        None => context.escape(latex.as_bytes())?,
+    }
+    context.write_all(if display { b"</div>" } else { b"</span>" })
+}
+
+fn is_display_math(node: Option<&AstNode>) -> bool {
+    node.is_some_and(|node| matches!(node.data.borrow().value, NodeValue::Math(ref math) if math.display_math))
+}
+
+/// Create ComrakOptions from the site's `kramdown` and `markdown_config` settings
+///
+/// Heading IDs are not left to comrak, which prefixes and slugs them
+/// differently; `render_markdown` generates them like kramdown does.
+pub fn create_comrak_options<'a>(config: &Config) -> Options<'a> {
+    let kramdown = config.kramdown.clone().unwrap_or_default();
     let mut options = Options::default();
-    
-    // Extension options - GitHub Flavored Markdown
-    options.extension.strikethrough = true;
+
+    // Extension options - strikethrough, autolinks and task lists are GFM only
+    options.extension.strikethrough = kramdown.is_gfm();
     options.extension.tagfilter = true;
     options.extension.table = true;
-    options.extension.autolink = true;
-    options.extension.tasklist = true;
+    options.extension.autolink = kramdown.is_gfm();
+    options.extension.tasklist = kramdown.is_gfm() && config.markdown_extension("task_lists");
     options.extension.superscript = true;
-    options.extension.header_ids = Some("header-".to_string());
-    options.extension.footnotes = true;
+    options.extension.header_ids = None;
+    options.extension.footnotes = config.markdown_extension("footnotes");
     options.extension.description_lists = true;
-    
+    options.extension.math_dollars = config.markdown_extension("math");
+
     // Render options
-    options.render.hardbreaks = false;
+    options.render.hardbreaks = kramdown.hard_wrap;
     options.render.github_pre_lang = true;
     options.render.unsafe_ = true; // Allow HTML (careful with this!)
-    
+
     // Parse options
-    options.parse.smart = true;
+    options.parse.smart = config.markdown_extension("typographic");
     options.parse.default_info_string = Some("text".to_string());
-    
+
     options
 }
 
 /// Render markdown to HTML using Comrak
-pub fn render_markdown<'a>(content: &str, options: &Options<'a>) -> String {
-    comrak::markdown_to_html(content, options)
+///
+/// kramdown's syntax extensions and heading IDs are applied to the parsed
+/// document, smart quotes follow `smart_quotes` and footnotes are numbered
+/// from `footnote_nr`. Code blocks are highlighted, or rendered as diagrams,
+/// following `code`.
+pub fn render_markdown(content: &str, options: &Options, kramdown: &KramdownConfig, code: &CodeRendering) -> String {
+    let expanded = kramdown::expand_html_blocks(content);
+    let arena = Arena::new();
+    let root = parse_document(&arena, &expanded.source, options);
+    let attributes = kramdown::apply(&arena, root, &expanded, options, kramdown);
+
+    if let Some(quotes) = smart_quotes(&kramdown.smart_quotes).filter(|quotes| *quotes != SMART_QUOTES) {
+        for node in root.descendants() {
+            if let NodeValue::Text(ref mut text) = node.data.borrow_mut().value {
+                if text.contains(SMART_QUOTES) {
+                    *text = text.chars()
+                        .map(|c| SMART_QUOTES.iter().position(|&quote| quote == c).map_or(c, |i| quotes[i]))
+                        .collect();
+                }
+            }
+        }
+    }
+
+    let offset = (kramdown.footnote_nr as u32).saturating_sub(1);
+    if offset > 0 {
+        for node in root.descendants() {
+            if let NodeValue::FootnoteReference(ref mut reference) = node.data.borrow_mut().value {
+                reference.ix += offset;
+            }
+        }
+    }
+
+    let mut html = Vec::new();
+    KramdownFormatter::format_document(root, options, &mut html, Rendering { attributes, code: code.clone() }).unwrap();
+    let html = String::from_utf8(html).unwrap();
+
+    // comrak always numbers the footnote list from one
+    if offset > 0 {
+        let list = "class=\"footnotes\" data-footnotes>\n<ol>";
+        return html.replacen(list, &format!("class=\"footnotes\" data-footnotes>\n<ol start=\"{}\">", offset + 1), 1);
+    }
+    html
+}
+
+/// Parse kramdown's `smart_quotes`, four entity names or code points
+fn smart_quotes(setting: &str) -> Option<[char; 4]> {
+    let quotes: Vec<char> = setting.split(',')
+        .map(|quote| {
+            let quote = quote.trim();
+            if let Ok(code) = quote.parse::<u32>() {
+                return char::from_u32(code);
+            }
+            let entity = match quote {
+                "lsquo" => '\u{2018}',
+                "rsquo" => '\u{2019}',
+                "ldquo" => '\u{201C}',
+                "rdquo" => '\u{201D}',
+                "sbquo" => '\u{201A}',
+                "bdquo" => '\u{201E}',
+                "lsaquo" => '\u{2039}',
+                "rsaquo" => '\u{203A}',
+                "laquo" => '\u{00AB}',
+                "raquo" => '\u{00BB}',
+                "apos" => '\'',
+                "quot" => '"',
+                _ => return None,
+            };
+            Some(entity)
+        })
+        .collect::<Option<_>>()?;
+    quotes.try_into().ok()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[test]
     fn test_comrak_rendering() {
-        let options = create_comrak_options();
+        let config = Config::default();
+        let options = create_comrak_options(&config);
         let markdown = "# Hello, World!\n\nThis is a **bold** statement.";
-        let html = render_markdown(markdown, &options);
-        
-        assert!(html.contains("<h1>"));
+        let html = render_markdown(markdown, &options, &KramdownConfig::default(), &CodeRendering::default());
+
+        assert!(html.contains("<h1 id=\"hello-world\">"));
         assert!(html.contains("<strong>bold</strong>"));
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_kramdown_heading_ids() {
+        let markdown = "# Hello *World*\n\n## 1. Über Café ##\n\nSetext\n---\n\n## Hello World\n\n## ???\n";
+        let options = create_comrak_options(&Config::default());
+
+        let html = render_markdown(markdown, &options, &KramdownConfig::default(), &CodeRendering::default());
+        assert!(html.contains("<h1 id=\"hello-world\">"));
+        assert!(html.contains("<h2 id=\"1-über-café\">"));
+        assert!(html.contains("<h2 id=\"setext\">"));
+        assert!(html.contains("<h2 id=\"hello-world-1\">"));
+        assert!(html.contains("<h2 id=\"\">"));
+
+        let kramdown = KramdownConfig { input: "kramdown".to_string(), ..KramdownConfig::default() };
+        let html = render_markdown(markdown, &options, &kramdown, &CodeRendering::default());
+        assert!(html.contains("<h2 id=\"ber-caf\">"));
+        assert!(html.contains("<h2 id=\"section\">"));
+
+        let kramdown = KramdownConfig { auto_ids: false, ..KramdownConfig::default() };
+        assert!(render_markdown(markdown, &options, &kramdown, &CodeRendering::default()).contains("<h1>"));
+    }
+
+    #[test]
+    fn test_quotes_and_footnotes() {
+        let kramdown = KramdownConfig {
+            smart_quotes: "sbquo,lsquo,bdquo,ldquo".to_string(),
+            footnote_nr: 5,
+            ..KramdownConfig::default()
+        };
+        let options = create_comrak_options(&Config::default());
+        let html = render_markdown("\"Hallo\", 'Welt'.[^a]\n\n[^a]: Note\n", &options, &kramdown, &CodeRendering::default());
+
+        assert!(html.contains("\u{201E}Hallo\u{201C}, \u{201A}Welt\u{2018}"));
+        assert!(html.contains("data-footnote-ref>5</a>"));
+        assert!(html.contains("<ol start=\"5\">"));
+    }
+}
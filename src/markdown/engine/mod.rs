@@ -1,3 +1,3 @@
 mod comrak;
 
-pub use comrak::{create_comrak_options, render_markdown}; 
\ No newline at end of file
+pub use comrak::{create_comrak_options, render_markdown, CodeRendering}; 
\ No newline at end of file
@@ -0,0 +1,77 @@
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::Arena;
+use html_escape::encode_double_quoted_attribute;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::nodes_of;
+
+lazy_static! {
+    // `*[HTML]: Hyper Text Markup Language`
+    static ref DEFINITION_REGEX: Regex = Regex::new(r"^\*\[([^\]]+)\]:(.*)$").unwrap();
+}
+
+/// An abbreviation definition line, giving the abbreviation and its title
+pub(super) fn definition(line: &str) -> Option<(String, String)> {
+    let captures = DEFINITION_REGEX.captures(line)?;
+    Some((captures[1].to_string(), captures[2].trim().to_string()))
+}
+
+/// Wrap abbreviations in text with `<abbr>` like kramdown
+///
+/// Only whole words are matched, longer abbreviations first.
+pub(super) fn apply<'a>(arena: &'a Arena<AstNode<'a>>, root: &'a AstNode<'a>, abbreviations: &[(String, String)]) {
+    if abbreviations.is_empty() {
+        return;
+    }
+
+    let mut sorted: Vec<&(String, String)> = abbreviations.iter().collect();
+    sorted.sort_by_key(|(abbreviation, _)| std::cmp::Reverse(abbreviation.len()));
+    let pattern = sorted.iter().map(|(abbreviation, _)| regex::escape(abbreviation)).collect::<Vec<_>>().join("|");
+    let regex = Regex::new(&pattern).unwrap();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    for node in nodes_of(root, |value| matches!(value, NodeValue::Text(_))) {
+        // Image descriptions become attributes
+        if node.ancestors().any(|ancestor| matches!(ancestor.data.borrow().value, NodeValue::Image(_))) {
+            continue;
+        }
+        let text = match node.data.borrow().value {
+            NodeValue::Text(ref text) => text.clone(),
+            _ => continue,
+        };
+
+        let mut parts = Vec::new();
+        let (mut done, mut start) = (0, 0);
+        while let Some(found) = regex.find_at(&text, start) {
+            let whole = !text[..found.start()].ends_with(is_word) && !text[found.end()..].starts_with(is_word);
+            if !whole {
+                start = found.start() + text[found.start()..].chars().next().map_or(1, char::len_utf8);
+                continue;
+            }
+
+            let title = abbreviations.iter().find(|(abbreviation, _)| abbreviation == found.as_str()).map_or("", |(_, title)| title);
+            let open = match title.is_empty() {
+                true => "<abbr>".to_string(),
+                false => format!("<abbr title=\"{}\">", encode_double_quoted_attribute(title)),
+            };
+            parts.push(NodeValue::Text(text[done..found.start()].to_string()));
+            parts.push(NodeValue::HtmlInline(open));
+            parts.push(NodeValue::Text(found.as_str().to_string()));
+            parts.push(NodeValue::HtmlInline("</abbr>".to_string()));
+            done = found.end();
+            start = found.end();
+        }
+        if parts.is_empty() {
+            continue;
+        }
+
+        parts.push(NodeValue::Text(text[done..].to_string()));
+        for part in parts {
+            if !matches!(part, NodeValue::Text(ref text) if text.is_empty()) {
+                node.insert_before(arena.alloc(AstNode::from(part)));
+            }
+        }
+        node.detach();
+    }
+}
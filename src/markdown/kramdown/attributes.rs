@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use comrak::nodes::{AstNode, NodeValue};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::{is_blank, node_key, nodes_of, paragraph_lines, remove_lines, set_attribute, strip_container, NodeAttributes};
+
+lazy_static! {
+    // `{:name: ...}` attribute list definitions
+    static ref DEFINITION_REGEX: Regex = Regex::new(r"^\{:(\w[\w-]*):((?:\\\}|[^}])*)\}$").unwrap();
+
+    // `{: ...}` on a line of its own
+    static ref BLOCK_REGEX: Regex = Regex::new(r"^\{:((?:\\\}|[^}])*)\}$").unwrap();
+
+    // `{: ...}` right after a span
+    static ref SPAN_REGEX: Regex = Regex::new(r"^\{:((?:\\\}|[^}])*)\}").unwrap();
+
+    // The parts of a list: `key="value"`, `.class#id` and definition names
+    static ref PART_REGEX: Regex = Regex::new(r#"(\w[\w-]*)=(?:"((?:\\.|[^"\\])*)"|'((?:\\.|[^'\\])*)')|\S+"#).unwrap();
+
+    static ref CLASS_OR_ID_REGEX: Regex = Regex::new(r"[.#][^.#]+").unwrap();
+}
+
+/// How deep definitions may reference other definitions
+const MAX_DEPTH: usize = 8;
+
+/// An attribute list definition line, `{:name: ...}`
+pub(super) fn definition(line: &str) -> Option<(String, String)> {
+    let captures = DEFINITION_REGEX.captures(line)?;
+    Some((captures[1].to_string(), captures[2].to_string()))
+}
+
+/// The content of a block attribute list line, but not of kramdown's `{::extensions}`
+fn block_list(line: &str) -> Option<&str> {
+    let list = BLOCK_REGEX.captures(line)?.get(1)?.as_str();
+    (!list.starts_with([':', '/']) && definition(line).is_none()).then_some(list)
+}
+
+/// Apply block and span attribute lists, removing their text
+///
+/// Block lists apply to the block right before them, or else to the one
+/// right after them. Returns the lists marked with `{:toc}`.
+pub(super) fn apply<'a>(
+    root: &'a AstNode<'a>,
+    lines: &[&str],
+    definitions: &HashMap<String, String>,
+    attributes: &mut NodeAttributes,
+) -> Vec<&'a AstNode<'a>> {
+    let mut lists = Lists { definitions, attributes, toc: Vec::new() };
+
+    // GFM tables take the line after them as another row
+    for table in nodes_of(root, |value| matches!(value, NodeValue::Table(_))) {
+        let end = table.data.borrow().sourcepos.end.line;
+        let Some(list) = lines.get(end.wrapping_sub(1)).and_then(|line| block_list(strip_container(line))) else {
+            continue;
+        };
+        if let Some(row) = table.last_child().filter(|row| !row.same_node(table.first_child().unwrap())) {
+            row.detach();
+            lists.assign(table, list);
+        }
+    }
+
+    for paragraph in nodes_of(root, |value| matches!(value, NodeValue::Paragraph)) {
+        let paragraph_lines = paragraph_lines(paragraph, lines);
+        let count = paragraph_lines.len();
+        let leading = paragraph_lines.iter().take_while(|line| block_list(line.text).is_some()).count();
+        let trailing = match leading {
+            _ if leading == count => 0,
+            _ => paragraph_lines.iter().rev().take_while(|line| block_list(line.text).is_some()).count(),
+        };
+        if leading == 0 && trailing == 0 {
+            continue;
+        }
+
+        let position = paragraph.data.borrow().sourcepos;
+        let previous = paragraph.previous_sibling().filter(|_| !is_blank(lines, position.start.line - 1));
+        let following = paragraph.next_sibling().filter(|_| !is_blank(lines, position.end.line + 1));
+        let leading_target = match leading == count {
+            true => previous.or(following),
+            false => Some(previous.unwrap_or(paragraph)),
+        };
+
+        for line in &paragraph_lines[..leading] {
+            if let (Some(target), Some(list)) = (leading_target, block_list(line.text)) {
+                lists.assign(target, list);
+            }
+        }
+        for line in &paragraph_lines[count - trailing..] {
+            // An unindented list after a list or quote applies to all of it
+            let target = match line.unindented {
+                true => top_level(paragraph),
+                false => paragraph,
+            };
+            if let Some(list) = block_list(line.text) {
+                lists.assign(target, list);
+            }
+        }
+
+        let removed: Vec<usize> = (0..leading).chain(count - trailing..count).collect();
+        remove_lines(paragraph, &paragraph_lines, &removed);
+    }
+
+    let spans = nodes_of(root, |value| matches!(value,
+        NodeValue::Image(_) | NodeValue::Link(_) | NodeValue::Emph | NodeValue::Strong | NodeValue::Code(_) | NodeValue::Strikethrough));
+    for span in spans {
+        let Some(next) = span.next_sibling() else { continue };
+        let mut value = next.data.borrow_mut();
+        let NodeValue::Text(ref mut text) = value.value else { continue };
+
+        // Smart punctuation has already curled the quotes around values
+        let plain: String = text.chars().map(|c| match c {
+            '\u{2018}' | '\u{2019}' => '\'',
+            '\u{201C}' | '\u{201D}' => '"',
+            c => c,
+        }).collect();
+        let Some(captures) = SPAN_REGEX.captures(&plain).filter(|captures| !captures[1].starts_with([':', '/'])) else {
+            continue;
+        };
+
+        let length = captures[0].chars().count();
+        *text = text.chars().skip(length).collect();
+        let empty = text.is_empty();
+        drop(value);
+        if empty {
+            next.detach();
+        }
+        lists.assign(span, &captures[1]);
+    }
+
+    lists.toc
+}
+
+/// The block directly in the document containing a node
+fn top_level<'a>(node: &'a AstNode<'a>) -> &'a AstNode<'a> {
+    node.ancestors()
+        .find(|ancestor| ancestor.parent().is_some_and(|parent| matches!(parent.data.borrow().value, NodeValue::Document)))
+        .unwrap_or(node)
+}
+
+/// Attribute lists being applied to a document
+struct Lists<'d, 'a> {
+    definitions: &'d HashMap<String, String>,
+    attributes: &'d mut NodeAttributes,
+    toc: Vec<&'a AstNode<'a>>,
+}
+
+impl<'a> Lists<'_, 'a> {
+    /// Add a list's attributes to a node
+    fn assign(&mut self, node: &'a AstNode<'a>, list: &str) {
+        let mut toc = false;
+        let attributes = self.attributes.entry(node_key(node)).or_default();
+        resolve(list, self.definitions, attributes, &mut toc, 0);
+
+        if toc && matches!(node.data.borrow().value, NodeValue::List(_)) && !self.toc.iter().any(|list| list.same_node(node)) {
+            self.toc.push(node);
+        }
+
+        // `{: .language-ruby}` sets the language of a code block
+        if let NodeValue::CodeBlock(ref mut code) = node.data.borrow_mut().value {
+            let language = attributes.iter()
+                .filter(|(name, _)| name == "class")
+                .flat_map(|(_, classes)| classes.split(' '))
+                .find_map(|class| class.strip_prefix("language-"));
+            if let (Some(language), true) = (language, code.info.is_empty()) {
+                code.info = language.to_string();
+            }
+        }
+    }
+}
+
+/// Add the attributes of a list, and of the definitions it names
+fn resolve(list: &str, definitions: &HashMap<String, String>, attributes: &mut Vec<(String, String)>, toc: &mut bool, depth: usize) {
+    for part in PART_REGEX.captures_iter(list) {
+        if let Some(name) = part.get(1) {
+            let value = part.get(2).or(part.get(3)).map_or("", |value| value.as_str());
+            let value = value.replace("\\}", "}").replace("\\\"", "\"").replace("\\'", "'");
+            set_attribute(attributes, name.as_str(), &value);
+            continue;
+        }
+
+        let part = &part[0];
+        if part.starts_with(['.', '#']) {
+            for class_or_id in CLASS_OR_ID_REGEX.find_iter(part).map(|found| found.as_str()) {
+                match class_or_id.split_at(1) {
+                    (".", class) => set_attribute(attributes, "class", class),
+                    (_, id) => set_attribute(attributes, "id", id),
+                }
+            }
+        } else if part == "toc" {
+            *toc = true;
+        } else if let Some(definition) = definitions.get(part).filter(|_| depth < MAX_DEPTH) {
+            resolve(definition, definitions, attributes, toc, depth + 1);
+        }
+    }
+}
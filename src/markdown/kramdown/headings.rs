@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use comrak::nodes::{AstNode, NodeValue};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::config::KramdownConfig;
+use super::{node_key, nodes_of, set_attribute, NodeAttributes};
+
+lazy_static! {
+    // Characters kramdown's GFM parser drops from heading IDs
+    static ref GFM_NON_WORD_REGEX: Regex = Regex::new(r"[^\p{L}\p{M}\p{Nd}\p{Pc}\- \t]").unwrap();
+
+    // A custom ID at the end of a heading, `## Heading {#id}`
+    static ref CUSTOM_ID_REGEX: Regex = Regex::new(r"[\t ]\{#([A-Za-z][\w:-]*)\}$").unwrap();
+}
+
+/// Give headings their `{#id}`, or an ID generated like kramdown with `auto_ids`
+///
+/// Headings that got an ID from an attribute list keep it.
+pub(super) fn assign_ids<'a>(root: &'a AstNode<'a>, lines: &[&str], kramdown: &KramdownConfig, attributes: &mut NodeAttributes) {
+    let mut ids = HeadingIds::new(kramdown.is_gfm());
+
+    for heading in nodes_of(root, |value| matches!(value, NodeValue::Heading(_))) {
+        let setext = match heading.data.borrow().value {
+            NodeValue::Heading(ref value) => value.setext,
+            _ => continue,
+        };
+        let source = heading_source(lines, heading, setext);
+        let heading_attributes = attributes.entry(node_key(heading)).or_default();
+        let has_id = heading_attributes.iter().any(|(name, _)| name == "id");
+
+        if let Some(captures) = CUSTOM_ID_REGEX.captures(&source) {
+            remove_custom_id(heading, &captures[0]);
+            if !has_id {
+                set_attribute(heading_attributes, "id", &captures[1]);
+            }
+        } else if kramdown.auto_ids && !has_id {
+            set_attribute(heading_attributes, "id", &ids.generate(&source));
+        }
+    }
+}
+
+/// Remove `{#id}` from the end of a heading's text
+fn remove_custom_id<'a>(heading: &'a AstNode<'a>, custom_id: &str) {
+    let Some(last) = heading.last_child() else { return };
+    let mut value = last.data.borrow_mut();
+    let NodeValue::Text(ref mut text) = value.value else { return };
+
+    let Some(stripped) = text.trim_end().strip_suffix(custom_id.trim_start()) else { return };
+    *text = stripped.trim_end().to_string();
+    let empty = text.is_empty();
+    drop(value);
+    if empty {
+        last.detach();
+    }
+}
+
+/// The source text of a heading, which kramdown builds its ID from
+fn heading_source(lines: &[&str], node: &AstNode, setext: bool) -> String {
+    let position = node.data.borrow().sourcepos;
+    let line = |number: usize| -> &str {
+        let line = lines.get(number.saturating_sub(1)).copied().unwrap_or("");
+        line.get(position.start.column.saturating_sub(1)..).unwrap_or(line)
+    };
+
+    if setext {
+        // The last line is the underline
+        return (position.start.line..position.end.line)
+            .map(|number| line(number).trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+
+    let text = line(position.start.line).trim_start().trim_start_matches('#').trim();
+    let unclosed = text.trim_end_matches('#');
+    if unclosed.len() < text.len() && (unclosed.is_empty() || unclosed.ends_with([' ', '\t'])) {
+        unclosed.trim_end().to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Heading IDs generated like kramdown, unique within a document
+struct HeadingIds {
+    gfm: bool,
+    used: HashMap<String, usize>,
+}
+
+impl HeadingIds {
+    fn new(gfm: bool) -> Self {
+        HeadingIds { gfm, used: HashMap::new() }
+    }
+
+    /// The ID for a heading's text, with `-1`, `-2`... for repeats
+    fn generate(&mut self, text: &str) -> String {
+        let id = if self.gfm {
+            GFM_NON_WORD_REGEX.replace_all(&text.to_lowercase(), "").replace([' ', '\t'], "-")
+        } else {
+            let id: String = text.trim_start_matches(|c: char| !c.is_ascii_alphabetic())
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == ' ' || *c == '-')
+                .map(|c| if c == ' ' { '-' } else { c.to_ascii_lowercase() })
+                .collect();
+            if id.is_empty() { "section".to_string() } else { id }
+        };
+
+        match self.used.get_mut(&id) {
+            Some(count) => {
+                *count += 1;
+                format!("{}-{}", id, count)
+            }
+            None => {
+                self.used.insert(id.clone(), 0);
+                id
+            }
+        }
+    }
+}
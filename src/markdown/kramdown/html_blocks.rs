@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+use comrak::nodes::{AstNode, NodeValue};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::nodes_of;
+
+lazy_static! {
+    // A start tag with a `markdown` attribute at the start of a line
+    static ref OPEN_TAG_REGEX: Regex = Regex::new(concat!(
+        r#"^( {0,3})<([A-Za-z][\w-]*)((?:\s+[\w:-]+(?:\s*=\s*(?:"[^"]*"|'[^']*'|[^\s"'>]+))?)*?)"#,
+        r#"\s+markdown\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#,
+        r#"((?:\s+[\w:-]+(?:\s*=\s*(?:"[^"]*"|'[^']*'|[^\s"'>]+))?)*)\s*>(.*)$"#,
+    )).unwrap();
+
+    // A `markdown` attribute inside a tag
+    static ref MARKDOWN_ATTRIBUTE_REGEX: Regex = Regex::new(
+        r#"(<[A-Za-z][^>]*?)\s+markdown\s*=\s*(?:"[^"]*"|'[^']*'|[^\s"'>]+)"#
+    ).unwrap();
+
+    static ref FENCE_REGEX: Regex = Regex::new(r"^ {0,3}(`{3,}|~{3,})").unwrap();
+}
+
+/// Elements whose content kramdown parses at the span level
+const SPAN_ELEMENTS: &[&str] = &[
+    "a", "abbr", "acronym", "b", "bdo", "big", "button", "caption", "cite", "del", "dfn", "dt", "em",
+    "h1", "h2", "h3", "h4", "h5", "h6", "i", "ins", "label", "legend", "p", "q", "small", "span",
+    "strong", "sub", "sup", "th", "tt",
+];
+
+/// Elements whose content kramdown never parses
+const RAW_ELEMENTS: &[&str] = &["code", "kbd", "math", "pre", "samp", "script", "style", "textarea", "var"];
+
+/// Source with `markdown="1"` HTML blocks opened up for comrak
+///
+/// The tags of such blocks are put on lines of their own between blank
+/// lines, so their content is parsed as markdown instead of raw HTML.
+pub struct Expanded {
+    /// The source to parse
+    pub source: String,
+    /// Lines, 1-based, of content to parse at the span level
+    span_lines: Vec<RangeInclusive<usize>>,
+}
+
+/// An HTML block with a `markdown` attribute that has not been closed yet
+struct Open {
+    /// Start and end tags of the element
+    tags: Regex,
+    depth: usize,
+    span: bool,
+    first_line: usize,
+}
+
+/// Open up the HTML blocks of a document that have a `markdown` attribute
+pub fn expand_html_blocks(source: &str) -> Expanded {
+    let mut expanded = Expanded { source: String::with_capacity(source.len()), span_lines: Vec::new() };
+    let mut line_count = 0;
+    let mut push = |expanded: &mut Expanded, line: &str| {
+        expanded.source.push_str(line);
+        expanded.source.push('\n');
+        line_count += 1;
+        line_count
+    };
+
+    let mut open: Vec<Open> = Vec::new();
+    let mut fence: Option<String> = None;
+    let mut pending: VecDeque<String> = source.lines().map(str::to_string).collect();
+
+    while let Some(line) = pending.pop_front() {
+        if let Some(marker) = &fence {
+            if line.trim().starts_with(marker.as_str()) && line.trim().trim_start_matches(marker.chars().next().unwrap()).is_empty() {
+                fence = None;
+            }
+            push(&mut expanded, &line);
+            continue;
+        }
+        if let Some(captures) = FENCE_REGEX.captures(&line) {
+            fence = Some(captures[1].to_string());
+            push(&mut expanded, &line);
+            continue;
+        }
+
+        if let Some(captures) = OPEN_TAG_REGEX.captures(&line) {
+            let tag = captures[2].to_ascii_lowercase();
+            let mode = captures.get(4).or(captures.get(5)).or(captures.get(6)).map_or("", |mode| mode.as_str());
+            if mode != "0" && !RAW_ELEMENTS.contains(&tag.as_str()) {
+                push(&mut expanded, &format!("{}<{}{}{}>", &captures[1], &captures[2], &captures[3], &captures[7]));
+                let blank = push(&mut expanded, "");
+                let rest = captures[8].to_string();
+                open.push(Open {
+                    tags: Regex::new(&format!(r"(?i)<(/?){}(?:\s[^>]*)?>", regex::escape(&tag))).unwrap(),
+                    depth: 0,
+                    span: mode == "span" || (mode != "block" && SPAN_ELEMENTS.contains(&tag.as_str())),
+                    first_line: blank + 1,
+                });
+                if !rest.trim().is_empty() {
+                    pending.push_front(rest);
+                }
+                continue;
+            }
+        }
+
+        if let Some(current) = open.last_mut() {
+            if let Some(end_tag) = find_end_tag(&line, current) {
+                let before = &line[..end_tag.start];
+                if !before.trim().is_empty() {
+                    push(&mut expanded, before);
+                }
+                let current = open.pop().unwrap();
+                let last_line = push(&mut expanded, "") - 1;
+                if current.span {
+                    expanded.span_lines.push(current.first_line..=last_line);
+                }
+                push(&mut expanded, &line[end_tag.clone()]);
+
+                let rest = line[end_tag.end..].to_string();
+                if !rest.trim().is_empty() {
+                    pending.push_front(rest);
+                }
+                continue;
+            }
+        }
+
+        push(&mut expanded, &line);
+    }
+
+    expanded
+}
+
+/// The end tag closing an open block in a line, counting nested elements
+fn find_end_tag(line: &str, open: &mut Open) -> Option<std::ops::Range<usize>> {
+    for tag in open.tags.captures_iter(line) {
+        if tag[1].is_empty() {
+            open.depth += 1;
+        } else if open.depth == 0 {
+            return Some(tag.get(0).unwrap().range());
+        } else {
+            open.depth -= 1;
+        }
+    }
+    None
+}
+
+/// Drop `markdown` attributes from HTML and unwrap span level content
+pub(super) fn apply<'a>(root: &'a AstNode<'a>, expanded: &Expanded) {
+    for node in nodes_of(root, |value| matches!(value, NodeValue::HtmlBlock(_) | NodeValue::HtmlInline(_))) {
+        let mut value = node.data.borrow_mut();
+        let html = match value.value {
+            NodeValue::HtmlBlock(ref mut block) => &mut block.literal,
+            NodeValue::HtmlInline(ref mut html) => html,
+            _ => continue,
+        };
+        if html.contains("markdown") {
+            *html = MARKDOWN_ATTRIBUTE_REGEX.replace_all(html, "$1").into_owned();
+        }
+    }
+
+    if expanded.span_lines.is_empty() {
+        return;
+    }
+    for paragraph in nodes_of(root, |value| matches!(value, NodeValue::Paragraph)) {
+        let line = paragraph.data.borrow().sourcepos.start.line;
+        if expanded.span_lines.iter().any(|lines| lines.contains(&line)) {
+            while let Some(child) = paragraph.first_child() {
+                paragraph.insert_before(child);
+            }
+            paragraph.detach();
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! kramdown's syntax extensions on top of comrak's AST
+//!
+//! Attribute lists, custom heading IDs, `{:toc}`, abbreviations and
+//! `markdown="1"` HTML blocks are found in the parsed document and turned
+//! into node attributes or replacement nodes before it is rendered.
+
+mod abbreviations;
+mod attributes;
+mod headings;
+mod html_blocks;
+mod toc;
+
+use std::collections::HashMap;
+
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::{Arena, Options};
+
+use crate::config::KramdownConfig;
+
+pub use html_blocks::{expand_html_blocks, Expanded};
+
+/// HTML attributes for AST nodes, keyed by node address
+pub type NodeAttributes = HashMap<usize, Vec<(String, String)>>;
+
+/// The key of a node in `NodeAttributes`
+pub fn node_key(node: &AstNode) -> usize {
+    node as *const AstNode as usize
+}
+
+/// Apply kramdown's extensions to a document parsed from `expanded`
+///
+/// Returns the attributes the HTML formatter has to add to nodes.
+pub fn apply<'a>(
+    arena: &'a Arena<AstNode<'a>>,
+    root: &'a AstNode<'a>,
+    expanded: &Expanded,
+    options: &Options,
+    kramdown: &KramdownConfig,
+) -> NodeAttributes {
+    let lines: Vec<&str> = expanded.source.lines().collect();
+    html_blocks::apply(root, expanded);
+
+    let definitions = Definitions::collect(root, &lines);
+    let mut attributes = NodeAttributes::new();
+    let toc_lists = attributes::apply(root, &lines, &definitions.attribute_lists, &mut attributes);
+    headings::assign_ids(root, &lines, kramdown, &mut attributes);
+    toc::insert(arena, root, &toc_lists, &attributes, options, &kramdown.toc_levels);
+    abbreviations::apply(arena, root, &definitions.abbreviations);
+
+    attributes
+}
+
+/// Attribute list and abbreviation definitions, which render nothing
+struct Definitions {
+    attribute_lists: HashMap<String, String>,
+    abbreviations: Vec<(String, String)>,
+}
+
+impl Definitions {
+    /// Collect definitions from paragraph lines and remove those lines
+    fn collect<'a>(root: &'a AstNode<'a>, lines: &[&str]) -> Self {
+        let mut definitions = Definitions { attribute_lists: HashMap::new(), abbreviations: Vec::new() };
+
+        for paragraph in nodes_of(root, |value| matches!(value, NodeValue::Paragraph)) {
+            let paragraph_lines = paragraph_lines(paragraph, lines);
+            let mut removed = Vec::new();
+            for (index, line) in paragraph_lines.iter().enumerate() {
+                if let Some((name, list)) = attributes::definition(line.text) {
+                    definitions.attribute_lists.insert(name, list);
+                } else if let Some((abbreviation, title)) = abbreviations::definition(line.text) {
+                    definitions.abbreviations.retain(|(defined, _)| *defined != abbreviation);
+                    definitions.abbreviations.push((abbreviation, title));
+                } else {
+                    continue;
+                }
+                removed.push(index);
+            }
+            remove_lines(paragraph, &paragraph_lines, &removed);
+        }
+
+        definitions
+    }
+}
+
+/// One line of a paragraph, with the inline nodes parsed from it
+struct Line<'a, 's> {
+    /// The source line without indentation and blockquote markers, if the
+    /// line is plain text
+    text: &'s str,
+    /// Whether the line starts at the left margin, outside any container
+    unindented: bool,
+    nodes: Vec<&'a AstNode<'a>>,
+    /// The soft or hard break ending the line
+    line_break: Option<&'a AstNode<'a>>,
+}
+
+/// Split a paragraph into lines at its line breaks
+///
+/// Lines continuing inlines from the line before, e.g. emphasis across a
+/// line break, are part of that line.
+fn paragraph_lines<'a, 's>(paragraph: &'a AstNode<'a>, lines: &[&'s str]) -> Vec<Line<'a, 's>> {
+    let mut groups = Vec::new();
+    let mut current = Vec::new();
+    for child in paragraph.children() {
+        if matches!(child.data.borrow().value, NodeValue::SoftBreak | NodeValue::LineBreak) {
+            groups.push((std::mem::take(&mut current), Some(child)));
+        } else {
+            current.push(child);
+        }
+    }
+    groups.push((current, None));
+
+    groups.into_iter().map(|(nodes, line_break)| {
+        // Extensions only look at lines that are a single piece of text
+        let plain = match nodes.as_slice() {
+            [node] => matches!(node.data.borrow().value, NodeValue::Text(_)),
+            _ => false,
+        };
+        let number = nodes.first().map_or(0, |node| node.data.borrow().sourcepos.start.line);
+        let source = lines.get(number.wrapping_sub(1)).copied().unwrap_or("");
+        Line {
+            text: if plain { strip_container(source) } else { "" },
+            unindented: !source.starts_with([' ', '\t', '>']),
+            nodes,
+            line_break,
+        }
+    }).collect()
+}
+
+/// Remove some of a paragraph's lines, and the paragraph once it is empty
+fn remove_lines<'a>(paragraph: &'a AstNode<'a>, lines: &[Line<'a, '_>], removed: &[usize]) {
+    if removed.is_empty() {
+        return;
+    }
+    if removed.len() == lines.len() {
+        paragraph.detach();
+        return;
+    }
+
+    for &index in removed {
+        lines[index].nodes.iter().for_each(|node| node.detach());
+        match lines[index].line_break {
+            Some(line_break) => line_break.detach(),
+            // The last line takes the break before it along
+            None => if let Some(line_break) = index.checked_sub(1).and_then(|previous| lines[previous].line_break) {
+                line_break.detach();
+            },
+        }
+    }
+}
+
+/// A source line without indentation and blockquote markers
+fn strip_container(line: &str) -> &str {
+    line.trim_start_matches(|c: char| c == '>' || c.is_whitespace()).trim_end()
+}
+
+/// Whether a source line, by 1-based number, is blank or outside the document
+fn is_blank(lines: &[&str], number: usize) -> bool {
+    number == 0 || lines.get(number - 1).is_none_or(|line| strip_container(line).is_empty())
+}
+
+/// The nodes of a document matching a predicate, collected so the tree can be changed
+fn nodes_of<'a>(root: &'a AstNode<'a>, predicate: impl Fn(&NodeValue) -> bool) -> Vec<&'a AstNode<'a>> {
+    root.descendants().filter(|node| predicate(&node.data.borrow().value)).collect()
+}
+
+/// Set an attribute, adding to existing classes
+fn set_attribute(attributes: &mut Vec<(String, String)>, name: &str, value: &str) {
+    match attributes.iter_mut().find(|(existing, _)| existing == name) {
+        Some((_, existing)) if name == "class" && !existing.is_empty() => {
+            if !existing.split(' ').any(|class| class == value) {
+                existing.push(' ');
+                existing.push_str(value);
+            }
+        }
+        Some((_, existing)) => *existing = value.to_string(),
+        None => attributes.push((name.to_string(), value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use comrak::parse_document;
+
+    use super::*;
+    use crate::config::Config;
+    use crate::markdown::engine::{create_comrak_options, render_markdown, CodeRendering};
+
+    fn render(markdown: &str) -> String {
+        render_markdown(markdown, &create_comrak_options(&Config::default()), &KramdownConfig::default(), &CodeRendering::default())
+    }
+
+    #[test]
+    fn test_attribute_lists() {
+        let html = render("{:note: .note title=\"A note\"}\n\nA paragraph\n{: note #first}\n\n\
+                           ![Logo](/logo.png){: .right width=\"50\"} and [a link](/){:target=\"_blank\"}\n\n\
+                           | a | b |\n|---|---|\n| 1 | 2 |\n{: .table}\n\n\
+                           ## Heading\n{: .title}\n\n\
+                           {: .intro}\nIntroduction\n");
+
+        assert!(html.contains("<p class=\"note\" title=\"A note\" id=\"first\">A paragraph</p>"));
+        assert!(html.contains("<img src=\"/logo.png\" alt=\"Logo\" class=\"right\" width=\"50\" />"));
+        assert!(html.contains("<a href=\"/\" target=\"_blank\">a link</a>"));
+        assert!(html.contains("<table class=\"table\">"));
+        assert!(!html.contains("{:"));
+        assert!(html.contains("<h2 class=\"title\" id=\"heading\">Heading</h2>"));
+        assert!(html.contains("<p class=\"intro\">Introduction</p>"));
+    }
+
+    #[test]
+    fn test_toc_and_heading_ids() {
+        let html = render("## Contents\n{:.no_toc}\n\n* TOC\n{:toc}\n\n## Intro {#start}\n\n### Details\n\n## Usage\n");
+
+        assert!(html.contains("<h2 id=\"start\">Intro</h2>"));
+        assert!(html.contains("<ul id=\"markdown-toc\">"));
+        assert!(html.contains("<a href=\"#start\" id=\"markdown-toc-start\">Intro</a>"));
+        assert!(html.contains("<li><a href=\"#details\" id=\"markdown-toc-details\">Details</a></li>"));
+        assert!(!html.contains("markdown-toc-contents"));
+        assert!(!html.contains("TOC"));
+    }
+
+    #[test]
+    fn test_abbreviations_and_markdown_blocks() {
+        let html = render("HTML and XHTML, not HTMLX.\n\n*[HTML]: Hyper Text Markup Language\n*[XHTML]:\n\n\
+                           <div class=\"box\" markdown=\"1\">\n**Bold** `HTML`\n</div>\n\n<p markdown=\"span\">*em*</p>\n");
+
+        assert!(html.contains("<abbr title=\"Hyper Text Markup Language\">HTML</abbr> and <abbr>XHTML</abbr>, not HTMLX."));
+        assert!(html.contains("<div class=\"box\">\n<p><strong>Bold</strong> <code>HTML</code></p>\n</div>"));
+        assert!(html.contains("<p>\n<em>em</em>\n</p>"));
+        assert!(!html.contains("Hyper Text Markup Language\n"));
+    }
+
+    #[test]
+    fn test_paragraph_lines() {
+        let arena = Arena::new();
+        let source = "one *two*\nthree\\\nfour";
+        let root = parse_document(&arena, source, &Options::default());
+        let lines: Vec<&str> = source.lines().collect();
+        let paragraph = root.first_child().unwrap();
+
+        let split = paragraph_lines(paragraph, &lines);
+        assert_eq!(split.iter().map(|line| line.text).collect::<Vec<_>>(), ["", "three\\", "four"]);
+        assert_eq!(split[0].nodes.len(), 2);
+    }
+}
@@ -0,0 +1,125 @@
+use comrak::nodes::{AstNode, ListType, NodeHtmlBlock, NodeValue};
+use comrak::{format_html, Arena, Options};
+use html_escape::encode_double_quoted_attribute;
+
+use super::{node_key, nodes_of, NodeAttributes};
+
+/// A heading listed in a table of contents
+struct Entry {
+    level: u8,
+    id: String,
+    html: String,
+    children: Vec<Entry>,
+}
+
+/// Replace lists marked with `{:toc}` by a table of contents like kramdown's
+///
+/// It lists the headings with an ID in `toc_levels`, except for those with
+/// the `no_toc` class. The list keeps its attributes, with an `id` of
+/// `markdown-toc` unless it has one.
+pub(super) fn insert<'a>(
+    arena: &'a Arena<AstNode<'a>>,
+    root: &'a AstNode<'a>,
+    lists: &[&'a AstNode<'a>],
+    attributes: &NodeAttributes,
+    options: &Options,
+    levels: &[u8],
+) {
+    if lists.is_empty() {
+        return;
+    }
+
+    let mut entries = Vec::new();
+    for heading in nodes_of(root, |value| matches!(value, NodeValue::Heading(_))) {
+        let NodeValue::Heading(ref value) = heading.data.borrow().value else { continue };
+        let heading_attributes = attributes.get(&node_key(heading)).map_or(&[][..], |attributes| attributes);
+        let id = heading_attributes.iter().find(|(name, _)| name == "id").map(|(_, id)| id.clone());
+        let excluded = heading_attributes.iter()
+            .any(|(name, classes)| name == "class" && classes.split(' ').any(|class| class == "no_toc"));
+
+        if let (Some(id), false, true) = (id, excluded, levels.contains(&value.level)) {
+            entries.push(Entry { level: value.level, id, html: inline_html(heading, options), children: Vec::new() });
+        }
+    }
+    let entries = nest(entries);
+
+    for list in lists {
+        let tag = match list.data.borrow().value {
+            NodeValue::List(ref value) if value.list_type == ListType::Ordered => "ol",
+            _ => "ul",
+        };
+        let mut list_attributes = attributes.get(&node_key(list)).cloned().unwrap_or_default();
+        if !list_attributes.iter().any(|(name, _)| name == "id") {
+            list_attributes.insert(0, ("id".to_string(), "markdown-toc".to_string()));
+        }
+
+        let mut html = format!("<{}", tag);
+        for (name, value) in &list_attributes {
+            html.push_str(&format!(" {}=\"{}\"", name, encode_double_quoted_attribute(value)));
+        }
+        html.push_str(">\n");
+        write_entries(&entries, tag, 1, &mut html);
+        html.push_str(&format!("</{}>\n", tag));
+
+        let block = arena.alloc(AstNode::from(NodeValue::HtmlBlock(NodeHtmlBlock { block_type: 6, literal: html })));
+        list.insert_before(block);
+        list.detach();
+    }
+}
+
+/// Nest entries under the closest heading before them with a lower level
+fn nest(entries: Vec<Entry>) -> Vec<Entry> {
+    let mut nested: Vec<Entry> = Vec::new();
+    let mut stack: Vec<Entry> = Vec::new();
+
+    let mut close = |stack: &mut Vec<Entry>| {
+        let entry = stack.pop().unwrap();
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(entry),
+            None => nested.push(entry),
+        }
+    };
+    for entry in entries {
+        while stack.last().is_some_and(|last| last.level >= entry.level) {
+            close(&mut stack);
+        }
+        stack.push(entry);
+    }
+    while !stack.is_empty() {
+        close(&mut stack);
+    }
+
+    nested
+}
+
+fn write_entries(entries: &[Entry], tag: &str, depth: usize, html: &mut String) {
+    let indent = "  ".repeat(depth);
+    for entry in entries {
+        let id = encode_double_quoted_attribute(&entry.id);
+        html.push_str(&format!("{}<li><a href=\"#{}\" id=\"markdown-toc-{}\">{}</a>", indent, id, id, entry.html));
+        if !entry.children.is_empty() {
+            html.push_str(&format!("\n{}  <{}>\n", indent, tag));
+            write_entries(&entry.children, tag, depth + 2, html);
+            html.push_str(&format!("{}  </{}>\n{}", indent, tag, indent));
+        }
+        html.push_str("</li>\n");
+    }
+}
+
+/// The HTML of a heading's content, without links and footnotes
+fn inline_html<'a>(node: &'a AstNode<'a>, options: &Options) -> String {
+    let mut html = String::new();
+    for child in node.children() {
+        match child.data.borrow().value {
+            NodeValue::FootnoteReference(_) => {}
+            NodeValue::Link(_) => html.push_str(&inline_html(child, options)),
+            _ => {
+                let mut output = Vec::new();
+                if format_html(child, options, &mut output).is_ok() {
+                    html.push_str(&String::from_utf8_lossy(&output));
+                }
+            }
+        }
+    }
+    html
+}
@@ -0,0 +1,162 @@
+//! Relative links between sources, rewritten to their output URLs
+//!
+//! Like jekyll-relative-links, `[setup](../guides/setup.md#install)` links
+//! to the page built from that file, so docs read the same on GitHub and
+//! on the site.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use lazy_static::lazy_static;
+use log::debug;
+use regex::{Captures, Regex};
+
+use crate::config::Config;
+use crate::builder::diagnostics::{self, Category};
+use crate::builder::site::with_baseurl;
+use crate::liquid::LinkTag;
+
+lazy_static! {
+    static ref HREF_RE: Regex = Regex::new(r#"(<a\s[^>]*?\bhref=")([^"]*)(")"#).unwrap();
+    static ref SCHEME_RE: Regex = Regex::new(r"^[a-zA-Z][a-zA-Z0-9+.-]*:").unwrap();
+}
+
+/// The URLs of pages and documents by source path, for rewriting links
+#[derive(Debug, Clone)]
+pub struct RelativeLinks {
+    source: PathBuf,
+    urls: HashMap<PathBuf, String>,
+    markdown_ext: Vec<String>,
+    link_tag: LinkTag,
+}
+
+impl RelativeLinks {
+    /// Links to sources in `urls`, keyed by path relative to the source directory
+    ///
+    /// The URLs are without the baseurl, which is added here.
+    pub fn new(urls: HashMap<PathBuf, String>, config: &Config) -> Self {
+        RelativeLinks {
+            source: config.source.clone(),
+            urls: urls.into_iter().map(|(path, url)| (normalize(&path).unwrap_or(path), with_baseurl(&url, config))).collect(),
+            markdown_ext: config.markdown_ext.iter().map(|ext| ext.to_lowercase()).collect(),
+            link_tag: LinkTag::new(config.clone()),
+        }
+    }
+
+    /// Rewrite the relative links of HTML converted from `file`
+    ///
+    /// Links to Markdown files which match no page or document are reported
+    /// and left alone.
+    pub fn rewrite(&self, html: &str, file: &Path) -> String {
+        let directory = match file.strip_prefix(&self.source) {
+            Ok(relative) => relative.parent().unwrap_or(Path::new("")).to_path_buf(),
+            Err(_) => return html.to_string(),
+        };
+
+        HREF_RE.replace_all(html, |caps: &Captures| {
+            let href = html_escape::decode_html_entities(&caps[2]);
+            match self.resolve(&href, &directory, file) {
+                Some(url) => format!("{}{}{}", &caps[1], html_escape::encode_double_quoted_attribute(&url), &caps[3]),
+                None => caps[0].to_string(),
+            }
+        }).into_owned()
+    }
+
+    /// The URL a relative link points to, with its query and fragment
+    fn resolve(&self, href: &str, directory: &Path, file: &Path) -> Option<String> {
+        if href.is_empty() || href.starts_with(['/', '#', '?']) || SCHEME_RE.is_match(href) || href.contains("{{") || href.contains("{%") {
+            return None;
+        }
+
+        let split = href.find(['?', '#']).unwrap_or(href.len());
+        let (path, suffix) = href.split_at(split);
+        let path = urlencoding::decode(path).map(|path| path.into_owned()).unwrap_or_else(|_| path.to_string());
+        let target = normalize(&directory.join(&path));
+        if let Some(url) = target.as_ref().and_then(|target| self.urls.get(target)) {
+            return Some(format!("{}{}", url, suffix));
+        }
+
+        // Only links to Markdown sources need a page to go to
+        let is_markdown = Path::new(&path).extension()
+            .is_some_and(|ext| self.markdown_ext.contains(&ext.to_string_lossy().to_lowercase()));
+        if !is_markdown {
+            return None;
+        }
+        match target.filter(|target| self.source.join(target).is_file()) {
+            Some(target) => {
+                debug!("Relative link to {} matches no page, using its path", target.display());
+                let url = self.link_tag.path_to_url(&target.to_string_lossy().replace('\\', "/")).ok()?;
+                Some(format!("{}{}", url, suffix))
+            },
+            None => {
+                diagnostics::warning(Category::Markdown, file, format!("Relative link to '{}' does not match a page or document", href));
+                None
+            }
+        }
+    }
+}
+
+/// Resolve `.` and `..` in a relative path, which must stay inside the source
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {},
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            },
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestSite;
+
+    #[test]
+    fn test_relative_links() {
+        let site = TestSite::new("links");
+        site.write("notes/draft.md", "Draft");
+
+        let urls = HashMap::from([
+            (PathBuf::from("guides/setup.md"), "/guides/setup/".to_string()),
+            (PathBuf::from("_posts/2024-01-01-hello.md"), "/2024/01/01/hello.html".to_string()),
+        ]);
+        let links = RelativeLinks::new(urls, &site.config());
+        let file = site.path("docs/intro.md");
+
+        let html = links.rewrite("<p><a href=\"../guides/setup.md#install\">setup</a> and \
+                                  <a title=\"x\" href=\"../_posts/2024-01-01-hello.md\">hello</a></p>", &file);
+        assert_eq!(html, "<p><a href=\"/guides/setup/#install\">setup</a> and \
+                          <a title=\"x\" href=\"/2024/01/01/hello.html\">hello</a></p>");
+
+        // Markdown files which are not pages keep the link tag's mapping
+        assert_eq!(links.rewrite("<a href=\"../notes/draft.md\">", &file), "<a href=\"/notes/draft.html\">");
+
+        // Other links are left alone, and missing targets too
+        for html in ["<a href=\"https://example.com/a.md\">", "<a href=\"/guides/setup.md\">", "<a href=\"#top\">",
+                     "<a href=\"image.png\">", "<a href=\"missing.md\">", "<code>&lt;a href=\"setup.md\"&gt;</code>"] {
+            assert_eq!(links.rewrite(html, &file), html);
+        }
+    }
+
+    #[test]
+    fn test_relative_links_with_baseurl() {
+        let site = TestSite::new("links-baseurl");
+        site.write("_posts/2024-01-01-hello.md", "---\ntitle: Hello\n---\nHello")
+            .write("guide.md", "---\ntitle: Guide\n---\nSee [hello](_posts/2024-01-01-hello.md#end).")
+            .write("about.md", "---\ntitle: About\n---\nRead [the guide](guide.md).");
+
+        site.build(Config { base_url: "/b".to_string(), ..site.config() });
+
+        // Pages and documents both get the baseurl once
+        assert!(site.read("guide.html").contains("<a href=\"/b/2024/01/01/hello#end\">hello</a>"));
+        assert!(site.read("about.html").contains("<a href=\"/b/guide.html\">the guide</a>"));
+    }
+}
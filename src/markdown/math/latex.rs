@@ -0,0 +1,556 @@
+use super::symbols;
+
+/// A piece of LaTeX math
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Command(String),
+    Char(char),
+    Number(String),
+    Open,
+    Close,
+    Superscript,
+    Subscript,
+    Align,
+    NewRow,
+    End,
+}
+
+/// A parsed element and whether scripts go under and over it
+struct Atom {
+    mathml: String,
+    limits: bool,
+}
+
+impl Atom {
+    fn new(mathml: String) -> Self {
+        Atom { mathml, limits: false }
+    }
+}
+
+/// Convert LaTeX math to a single MathML element
+///
+/// Rows separated by `\\` or columns separated by `&` outside of an
+/// environment are laid out as a table.
+pub(super) fn to_mathml(latex: &str) -> Result<String, String> {
+    let mut parser = Parser { chars: latex.chars().collect(), position: 0, variant: None };
+    let table = parser.parse_table(None)?;
+    match parser.next_token() {
+        Token::End => Ok(table),
+        Token::Close => Err("Unexpected `}`".to_string()),
+        token => Err(format!("Unexpected {}", describe(&token))),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    /// The `mathvariant` of a font command being applied
+    variant: Option<&'static str>,
+}
+
+impl Parser {
+    fn peek_char(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek_char() {
+            if c == '%' {
+                while self.peek_char().is_some_and(|c| c != '\n') {
+                    self.position += 1;
+                }
+            } else if c.is_whitespace() {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Token {
+        self.skip_whitespace();
+        let Some(c) = self.peek_char() else { return Token::End };
+        self.position += 1;
+
+        match c {
+            '\\' => {
+                let start = self.position;
+                while self.peek_char().is_some_and(|c| c.is_ascii_alphabetic()) {
+                    self.position += 1;
+                }
+                if self.position == start {
+                    match self.peek_char() {
+                        Some('\\') => {
+                            self.position += 1;
+                            return Token::NewRow;
+                        }
+                        Some(c) => {
+                            self.position += 1;
+                            return Token::Command(c.to_string());
+                        }
+                        None => return Token::Command(String::new()),
+                    }
+                }
+                // `\operatorname*` and friends
+                if self.peek_char() == Some('*') {
+                    self.position += 1;
+                }
+                Token::Command(self.chars[start..self.position].iter().filter(|&&c| c != '*').collect())
+            }
+            '{' => Token::Open,
+            '}' => Token::Close,
+            '^' => Token::Superscript,
+            '_' => Token::Subscript,
+            '&' => Token::Align,
+            '~' => Token::Command(" ".to_string()),
+            c if c.is_ascii_digit() => {
+                let start = self.position - 1;
+                while let Some(c) = self.peek_char() {
+                    let decimal = c == '.' && self.chars.get(self.position + 1).is_some_and(|c| c.is_ascii_digit());
+                    if !c.is_ascii_digit() && !decimal {
+                        break;
+                    }
+                    self.position += 1;
+                }
+                Token::Number(self.chars[start..self.position].iter().collect())
+            }
+            c => Token::Char(c),
+        }
+    }
+
+    fn peek_token(&mut self) -> Token {
+        let position = self.position;
+        let token = self.next_token();
+        self.position = position;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next_token() {
+            token if token == expected => Ok(()),
+            token => Err(format!("Expected {} but found {}", describe(&expected), describe(&token))),
+        }
+    }
+
+    /// Parse rows and columns, as a table if there is more than one cell
+    fn parse_table(&mut self, environment: Option<&str>) -> Result<String, String> {
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        loop {
+            let mut cells = Vec::new();
+            loop {
+                cells.push(row(self.parse_row(None)?));
+                if self.peek_token() != Token::Align {
+                    break;
+                }
+                self.next_token();
+            }
+            rows.push(cells);
+            if self.peek_token() != Token::NewRow {
+                break;
+            }
+            self.next_token();
+        }
+
+        // A `\\` ending the last row does not start another
+        if rows.len() > 1 && rows.last().is_some_and(|cells| cells.len() == 1 && cells[0] == "<mrow></mrow>") {
+            rows.pop();
+        }
+        if environment.is_none() && rows.len() == 1 && rows[0].len() == 1 {
+            return Ok(rows.remove(0).remove(0));
+        }
+
+        let attributes = match environment {
+            Some("aligned" | "align" | "align*" | "split") => " columnalign=\"right left\" columnspacing=\"0\"",
+            Some("cases") => " columnalign=\"left left\"",
+            None => " columnalign=\"left\"",
+            _ => "",
+        };
+        let mut table = format!("<mtable{}>", attributes);
+        for cells in rows {
+            table.push_str("<mtr>");
+            for cell in cells {
+                table.push_str(&format!("<mtd>{}</mtd>", cell));
+            }
+            table.push_str("</mtr>");
+        }
+        table.push_str("</mtable>");
+        Ok(table)
+    }
+
+    /// Parse elements up to the end of a group, cell or row
+    ///
+    /// With `closing`, the row also ends before that character, for
+    /// optional arguments.
+    fn parse_row(&mut self, closing: Option<char>) -> Result<Vec<String>, String> {
+        let mut elements = Vec::new();
+        loop {
+            let token = self.peek_token();
+            let ends = match token {
+                Token::End | Token::Close | Token::Align | Token::NewRow => true,
+                Token::Command(ref name) => name == "right" || name == "end",
+                Token::Char(c) => Some(c) == closing,
+                _ => false,
+            };
+            if ends {
+                return Ok(elements);
+            }
+
+            // Scripts may start a row, on nothing
+            let atom = match token {
+                Token::Superscript | Token::Subscript => Some(Atom::new("<mrow></mrow>".to_string())),
+                _ => self.parse_atom()?,
+            };
+            if let Some(atom) = atom {
+                elements.push(self.parse_scripts(atom)?);
+            }
+        }
+    }
+
+    /// Add the superscript and subscript following an element
+    fn parse_scripts(&mut self, mut base: Atom) -> Result<String, String> {
+        let mut superscript: Option<String> = None;
+        let mut subscript: Option<String> = None;
+        loop {
+            self.skip_whitespace();
+            if self.peek_char() == Some('\'') {
+                let mut primes = String::new();
+                while self.peek_char() == Some('\'') {
+                    self.position += 1;
+                    primes.push('′');
+                }
+                if superscript.is_some() {
+                    return Err("Double superscript".to_string());
+                }
+                superscript = Some(format!("<mo>{}</mo>", primes));
+                continue;
+            }
+
+            match self.peek_token() {
+                Token::Superscript => {
+                    self.next_token();
+                    if superscript.is_some() {
+                        return Err("Double superscript".to_string());
+                    }
+                    superscript = Some(self.parse_argument()?);
+                }
+                Token::Subscript => {
+                    self.next_token();
+                    if subscript.is_some() {
+                        return Err("Double subscript".to_string());
+                    }
+                    subscript = Some(self.parse_argument()?);
+                }
+                Token::Command(ref name) if name == "limits" || name == "nolimits" => {
+                    base.limits = name == "limits";
+                    self.next_token();
+                }
+                _ => break,
+            }
+        }
+
+        let base = base_with_limits(base);
+        let mathml = match (subscript, superscript) {
+            (None, None) => base.mathml,
+            (Some(sub), None) if base.limits => format!("<munder>{}{}</munder>", base.mathml, sub),
+            (None, Some(sup)) if base.limits => format!("<mover>{}{}</mover>", base.mathml, sup),
+            (Some(sub), Some(sup)) if base.limits => format!("<munderover>{}{}{}</munderover>", base.mathml, sub, sup),
+            (Some(sub), None) => format!("<msub>{}{}</msub>", base.mathml, sub),
+            (None, Some(sup)) => format!("<msup>{}{}</msup>", base.mathml, sup),
+            (Some(sub), Some(sup)) => format!("<msubsup>{}{}{}</msubsup>", base.mathml, sub, sup),
+        };
+        Ok(mathml)
+    }
+
+    /// Parse the argument of a command or script, a group or a single element
+    fn parse_argument(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        // `\frac12` takes one digit at a time
+        if let Some(digit) = self.peek_char().filter(|c| c.is_ascii_digit()) {
+            self.position += 1;
+            return Ok(self.number(&digit.to_string()));
+        }
+
+        match self.peek_token() {
+            Token::Open => {
+                self.next_token();
+                let elements = self.parse_row(None)?;
+                self.expect(Token::Close)?;
+                Ok(row(elements))
+            }
+            Token::End => Err("Missing argument".to_string()),
+            Token::Close | Token::Align | Token::NewRow | Token::Superscript | Token::Subscript => {
+                Err(format!("Missing argument before {}", describe(&self.peek_token())))
+            }
+            _ => Ok(self.parse_atom()?.map_or("<mrow></mrow>".to_string(), |atom| atom.mathml)),
+        }
+    }
+
+    /// Parse an optional `[...]` argument
+    fn parse_optional_argument(&mut self) -> Result<Option<String>, String> {
+        self.skip_whitespace();
+        if self.peek_char() != Some('[') {
+            return Ok(None);
+        }
+        self.position += 1;
+        let elements = self.parse_row(Some(']'))?;
+        self.expect(Token::Char(']'))?;
+        Ok(Some(row(elements)))
+    }
+
+    /// The raw text of a `{...}` group, for text and environment names
+    fn parse_text_argument(&mut self) -> Result<String, String> {
+        self.expect(Token::Open)?;
+        let start = self.position;
+        let mut depth = 0;
+        while let Some(c) = self.peek_char() {
+            match c {
+                '\\' => self.position += 1,
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    let text = self.chars[start..self.position].iter().collect();
+                    self.position += 1;
+                    return Ok(text);
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+            self.position += 1;
+        }
+        Err("Missing `}`".to_string())
+    }
+
+    /// Parse one element, or nothing for commands that only change style
+    fn parse_atom(&mut self) -> Result<Option<Atom>, String> {
+        let atom = match self.next_token() {
+            Token::Number(number) => Atom::new(self.number(&number)),
+            Token::Char(c) if c.is_alphabetic() => Atom::new(self.identifier(&c.to_string(), None)),
+            Token::Char('\'') => Atom::new("<mo>′</mo>".to_string()),
+            Token::Char(c) => Atom::new(format!("<mo>{}</mo>", escape(&operator_char(c).to_string()))),
+            Token::Open => {
+                let elements = self.parse_row(None)?;
+                self.expect(Token::Close)?;
+                Atom::new(row(elements))
+            }
+            Token::Command(name) => return self.parse_command(&name),
+            token => return Err(format!("Unexpected {}", describe(&token))),
+        };
+        Ok(Some(atom))
+    }
+
+    fn parse_command(&mut self, name: &str) -> Result<Option<Atom>, String> {
+        if let Some(symbol) = symbols::identifier(name) {
+            return Ok(Some(Atom::new(self.identifier(symbol, None))));
+        }
+        if let Some(symbol) = symbols::upright_identifier(name) {
+            return Ok(Some(Atom::new(self.identifier(symbol, Some("normal")))));
+        }
+        if let Some(symbol) = symbols::operator(name) {
+            return Ok(Some(Atom::new(format!("<mo>{}</mo>", escape(symbol)))));
+        }
+        if let Some(symbol) = symbols::large_operator(name) {
+            return Ok(Some(Atom { mathml: format!("<mo>{}</mo>", symbol), limits: true }));
+        }
+        if let Some(symbol) = symbols::integral(name) {
+            return Ok(Some(Atom::new(format!("<mo>{}</mo>", symbol))));
+        }
+        if symbols::is_function(name) {
+            return Ok(Some(Atom::new(format!("<mi>{}</mi>", name))));
+        }
+        if let Some(word) = symbols::limit_function(name) {
+            return Ok(Some(Atom { mathml: format!("<mo movablelimits=\"true\" form=\"prefix\">{}</mo>", word), limits: true }));
+        }
+        if let Some(width) = symbols::space(name) {
+            return Ok(Some(Atom::new(format!("<mspace width=\"{}\"/>", width))));
+        }
+        if let Some(variant) = symbols::font(name) {
+            let outer = self.variant.replace(variant);
+            let argument = self.parse_argument();
+            self.variant = outer;
+            return argument.map(|mathml| Some(Atom::new(mathml)));
+        }
+        if let Some((mark, over, stretchy)) = symbols::accent(name) {
+            let argument = self.parse_argument()?;
+            let mark = format!("<mo stretchy=\"{}\">{}</mo>", stretchy, escape(mark));
+            let mathml = match over {
+                true => format!("<mover accent=\"true\">{}{}</mover>", argument, mark),
+                false => format!("<munder accentunder=\"true\">{}{}</munder>", argument, mark),
+            };
+            return Ok(Some(Atom::new(mathml)));
+        }
+        if let Some(size) = symbols::delimiter_size(name) {
+            let delimiter = self.parse_delimiter()?;
+            return Ok(Some(Atom::new(format!(
+                "<mo minsize=\"{}\" maxsize=\"{}\">{}</mo>", size, size, escape(&delimiter)))));
+        }
+
+        let mathml = match name {
+            "frac" | "cfrac" => format!("<mfrac>{}{}</mfrac>", self.parse_argument()?, self.parse_argument()?),
+            "dfrac" | "tfrac" => format!("<mstyle displaystyle=\"{}\"><mfrac>{}{}</mfrac></mstyle>",
+                name == "dfrac", self.parse_argument()?, self.parse_argument()?),
+            "binom" | "dbinom" | "tbinom" => format!("<mrow><mo>(</mo><mfrac linethickness=\"0\">{}{}</mfrac><mo>)</mo></mrow>",
+                self.parse_argument()?, self.parse_argument()?),
+            "sqrt" => match self.parse_optional_argument()? {
+                Some(index) => format!("<mroot>{}{}</mroot>", self.parse_argument()?, index),
+                None => format!("<msqrt>{}</msqrt>", self.parse_argument()?),
+            },
+            "text" | "textrm" | "textit" | "textbf" | "textsf" | "texttt" | "textup" | "mbox" => {
+                let text = self.parse_text_argument()?;
+                let variant = match name {
+                    "textit" => " mathvariant=\"italic\"",
+                    "textbf" => " mathvariant=\"bold\"",
+                    "textsf" => " mathvariant=\"sans-serif\"",
+                    "texttt" => " mathvariant=\"monospace\"",
+                    _ => "",
+                };
+                format!("<mtext{}>{}</mtext>", variant, escape(&text_content(&text)))
+            }
+            "operatorname" => format!("<mi>{}</mi>", escape(&self.parse_text_argument()?)),
+            "not" => {
+                let negated = self.parse_argument()?;
+                match negated.strip_suffix("</mo>") {
+                    Some(operator) => format!("{}\u{338}</mo>", operator),
+                    None => negated,
+                }
+            }
+            "left" => {
+                let open = self.parse_delimiter()?;
+                let elements = self.parse_row(None)?;
+                match self.next_token() {
+                    Token::Command(name) if name == "right" => {}
+                    token => return Err(format!("Expected \\right but found {}", describe(&token))),
+                }
+                let close = self.parse_delimiter()?;
+                format!("<mrow>{}{}{}</mrow>", fence(&open, "prefix"), elements.concat(), fence(&close, "postfix"))
+            }
+            "middle" => fence(&self.parse_delimiter()?, "infix"),
+            "begin" => self.parse_environment()?,
+            "displaystyle" | "textstyle" | "scriptstyle" | "limits" | "nolimits" | "nonumber" | "notag" => return Ok(None),
+            "" => return Err("Missing command name after `\\`".to_string()),
+            "right" | "end" => return Err(format!("Unexpected \\{}", name)),
+            _ => return Err(format!("Unknown command \\{}", name)),
+        };
+        Ok(Some(Atom::new(mathml)))
+    }
+
+    /// Parse a `\begin{...}` environment up to its `\end`
+    fn parse_environment(&mut self) -> Result<String, String> {
+        let environment = self.parse_text_argument()?;
+        let Some((open, close)) = symbols::matrix_delimiters(&environment) else {
+            return Err(format!("Unknown environment {}", environment));
+        };
+        // The column specification of an array
+        if environment == "array" {
+            self.parse_text_argument()?;
+        }
+
+        let table = self.parse_table(Some(&environment))?;
+        match self.next_token() {
+            Token::Command(name) if name == "end" => {}
+            token => return Err(format!("Expected \\end{{{}}} but found {}", environment, describe(&token))),
+        }
+        let end = self.parse_text_argument()?;
+        if end != environment {
+            return Err(format!("\\begin{{{}}} ended by \\end{{{}}}", environment, end));
+        }
+
+        if open.is_empty() && close.is_empty() {
+            return Ok(table);
+        }
+        Ok(format!("<mrow>{}{}{}</mrow>", fence(open, "prefix"), table, fence(close, "postfix")))
+    }
+
+    /// Parse the delimiter after `\left`, `\right` or a size command
+    fn parse_delimiter(&mut self) -> Result<String, String> {
+        match self.next_token() {
+            Token::Char('.') => Ok(String::new()),
+            Token::Char(c) if "()[]|/<>".contains(c) => Ok(match c {
+                '<' => "⟨".to_string(),
+                '>' => "⟩".to_string(),
+                c => c.to_string(),
+            }),
+            Token::Command(name) => match symbols::operator(&name) {
+                Some(symbol) => Ok(symbol.to_string()),
+                None => Err(format!("Invalid delimiter \\{}", name)),
+            },
+            token => Err(format!("Invalid delimiter {}", describe(&token))),
+        }
+    }
+
+    fn identifier(&self, text: &str, variant: Option<&str>) -> String {
+        match self.variant.or(variant) {
+            Some(variant) => format!("<mi mathvariant=\"{}\">{}</mi>", variant, escape(text)),
+            None => format!("<mi>{}</mi>", escape(text)),
+        }
+    }
+
+    fn number(&self, number: &str) -> String {
+        match self.variant {
+            Some(variant) if variant != "normal" => format!("<mn mathvariant=\"{}\">{}</mn>", variant, number),
+            _ => format!("<mn>{}</mn>", number),
+        }
+    }
+}
+
+/// Elements as a single element
+fn row(elements: Vec<String>) -> String {
+    match elements.len() {
+        1 => elements.into_iter().next().unwrap(),
+        _ => format!("<mrow>{}</mrow>", elements.concat()),
+    }
+}
+
+/// A stretchy delimiter, or nothing for `.`
+fn fence(delimiter: &str, form: &str) -> String {
+    match delimiter {
+        "" => String::new(),
+        _ => format!("<mo fence=\"true\" stretchy=\"true\" form=\"{}\">{}</mo>", form, escape(delimiter)),
+    }
+}
+
+/// The operator typed as a character
+fn operator_char(c: char) -> char {
+    match c {
+        '-' => '−',
+        '*' => '∗',
+        c => c,
+    }
+}
+
+/// Text with the spaces at its ends kept, which MathML would trim
+fn text_content(text: &str) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return "\u{a0}".repeat(text.len());
+    }
+    let start = text.len() - text.trim_start().len();
+    let end = text.len() - text.trim_end().len();
+    format!("{}{}{}", "\u{a0}".repeat(start), trimmed, "\u{a0}".repeat(end))
+}
+
+/// Large operators keep scripts under and over them only in display style,
+/// which `movablelimits` leaves to the renderer
+fn base_with_limits(base: Atom) -> Atom {
+    if base.limits && base.mathml.starts_with("<mo>") {
+        return Atom { mathml: base.mathml.replacen("<mo>", "<mo movablelimits=\"true\">", 1), limits: true };
+    }
+    base
+}
+
+fn escape(text: &str) -> String {
+    html_escape::encode_text(text).to_string()
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Command(name) => format!("\\{}", name),
+        Token::Char(c) => format!("`{}`", c),
+        Token::Number(number) => format!("`{}`", number),
+        Token::Open => "`{`".to_string(),
+        Token::Close => "`}`".to_string(),
+        Token::Superscript => "`^`".to_string(),
+        Token::Subscript => "`_`".to_string(),
+        Token::Align => "`&`".to_string(),
+        Token::NewRow => "`\\\\`".to_string(),
+        Token::End => "the end of the formula".to_string(),
+    }
+}
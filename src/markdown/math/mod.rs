@@ -0,0 +1,109 @@
+//! LaTeX math rendered to MathML at build time
+//!
+//! Pages with math need no JavaScript to display it. Rendered formulas are
+//! cached in the site's `cache_dir` by a hash of their source and the
+//! version of Rustyll.
+
+mod latex;
+mod symbols;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use html_escape::encode_text;
+use log::warn;
+use sha2::{Digest, Sha256};
+
+/// Name of the math cache directory inside the cache directory
+const CACHE_DIR: &str = "math";
+
+/// Renders LaTeX to MathML, caching the results on disk
+#[derive(Debug, Clone, Default)]
+pub struct MathRenderer {
+    cache_dir: Option<PathBuf>,
+}
+
+impl MathRenderer {
+    /// Create a renderer caching formulas under `cache_dir`, or not at all
+    pub fn new(cache_dir: Option<&Path>) -> Self {
+        MathRenderer { cache_dir: cache_dir.map(|dir| dir.join(CACHE_DIR)) }
+    }
+
+    /// Render a formula to a `<math>` element
+    pub fn render(&self, latex: &str, display: bool) -> Result<String, String> {
+        // Formulas are rendered again by a new version
+        let key = format!("{}\n{}\n{}", env!("CARGO_PKG_VERSION"), display, latex);
+        let key = hex::encode(Sha256::digest(key.as_bytes()));
+        let cache_path = self.cache_dir.as_ref().map(|dir| dir.join(format!("{}.html", key)));
+        if let Some(cached) = cache_path.as_ref().and_then(|path| fs::read_to_string(path).ok()) {
+            return Ok(cached);
+        }
+
+        let mathml = to_mathml(latex, display)?;
+        if let Some(path) = cache_path {
+            let written = fs::create_dir_all(path.parent().unwrap()).and_then(|_| fs::write(&path, &mathml));
+            if let Err(e) = written {
+                warn!("Failed to cache rendered math: {}", e);
+            }
+        }
+        Ok(mathml)
+    }
+}
+
+/// Convert a LaTeX formula to MathML, keeping the source as an annotation
+pub fn to_mathml(latex: &str, display: bool) -> Result<String, String> {
+    let content = latex::to_mathml(latex.trim())?;
+    let display = if display { " display=\"block\"" } else { "" };
+    Ok(format!(
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"{}><semantics>{}\
+         <annotation encoding=\"application/x-tex\">{}</annotation></semantics></math>",
+        display, content, encode_text(latex.trim())
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestSite;
+
+    #[test]
+    fn test_latex_to_mathml() {
+        let mathml = to_mathml("x^2 + \\frac{1}{2}", false).unwrap();
+        assert!(mathml.contains("<msup><mi>x</mi><mn>2</mn></msup><mo>+</mo><mfrac><mn>1</mn><mn>2</mn></mfrac>"));
+        assert!(mathml.contains("<annotation encoding=\"application/x-tex\">x^2 + \\frac{1}{2}</annotation>"));
+
+        let mathml = to_mathml("\\sum_{i=1}^n \\alpha_i \\leq \\sqrt[3]{\\mathbb{R}}", true).unwrap();
+        assert!(mathml.starts_with("<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\">"));
+        assert!(mathml.contains("<munderover><mo movablelimits=\"true\">∑</mo><mrow><mi>i</mi><mo>=</mo><mn>1</mn></mrow><mi>n</mi></munderover>"));
+        assert!(mathml.contains("<msub><mi>α</mi><mi>i</mi></msub><mo>≤</mo>"));
+        assert!(mathml.contains("<mroot><mi mathvariant=\"double-struck\">R</mi><mn>3</mn></mroot>"));
+
+        let mathml = to_mathml("\\left( \\begin{matrix} a & b \\\\ c & d \\end{matrix} \\right) \\text{ if } x < 0", false).unwrap();
+        assert!(mathml.contains("<mtable><mtr><mtd><mi>a</mi></mtd><mtd><mi>b</mi></mtd></mtr>"));
+        assert!(mathml.contains("<mtext>\u{a0}if\u{a0}</mtext><mi>x</mi><mo>&lt;</mo><mn>0</mn>"));
+    }
+
+    #[test]
+    fn test_latex_errors() {
+        assert_eq!(to_mathml("\\foo{x}", false).unwrap_err(), "Unknown command \\foo");
+        assert_eq!(to_mathml("\\frac{1}{2", false).unwrap_err(), "Expected `}` but found the end of the formula");
+        assert_eq!(to_mathml("x}", false).unwrap_err(), "Unexpected `}`");
+        assert_eq!(to_mathml("x^1^2", false).unwrap_err(), "Double superscript");
+    }
+
+    #[test]
+    fn test_math_cache() {
+        let site = TestSite::new("math");
+        let dir = site.source();
+        let renderer = MathRenderer::new(Some(dir));
+
+        let mathml = renderer.render("a+b", false).unwrap();
+        let cached: Vec<_> = fs::read_dir(dir.join(CACHE_DIR)).unwrap().collect();
+        assert_eq!(cached.len(), 1);
+
+        let path = cached[0].as_ref().unwrap().path();
+        fs::write(&path, "<math>cached</math>").unwrap();
+        assert_eq!(renderer.render("a+b", false).unwrap(), "<math>cached</math>");
+        assert_ne!(renderer.render("a+b", true).unwrap(), mathml);
+    }
+}
@@ -0,0 +1,176 @@
+/// Greek letters and other letter-like symbols, rendered as `<mi>`
+pub(super) fn identifier(name: &str) -> Option<&'static str> {
+    let symbol = match name {
+        "alpha" => "α", "beta" => "β", "gamma" => "γ", "delta" => "δ",
+        "epsilon" => "ϵ", "varepsilon" => "ε", "zeta" => "ζ", "eta" => "η",
+        "theta" => "θ", "vartheta" => "ϑ", "iota" => "ι", "kappa" => "κ",
+        "lambda" => "λ", "mu" => "μ", "nu" => "ν", "xi" => "ξ",
+        "omicron" => "ο", "pi" => "π", "varpi" => "ϖ", "rho" => "ρ",
+        "varrho" => "ϱ", "sigma" => "σ", "varsigma" => "ς", "tau" => "τ",
+        "upsilon" => "υ", "phi" => "ϕ", "varphi" => "φ", "chi" => "χ",
+        "psi" => "ψ", "omega" => "ω",
+        "infty" => "∞", "partial" => "∂", "nabla" => "∇", "emptyset" => "∅",
+        "varnothing" => "∅", "ell" => "ℓ", "hbar" => "ℏ", "imath" => "ı",
+        "jmath" => "ȷ", "Re" => "ℜ", "Im" => "ℑ", "aleph" => "ℵ", "wp" => "℘",
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+/// Capital Greek letters, which are upright
+pub(super) fn upright_identifier(name: &str) -> Option<&'static str> {
+    let symbol = match name {
+        "Gamma" => "Γ", "Delta" => "Δ", "Theta" => "Θ", "Lambda" => "Λ",
+        "Xi" => "Ξ", "Pi" => "Π", "Sigma" => "Σ", "Upsilon" => "Υ",
+        "Phi" => "Φ", "Psi" => "Ψ", "Omega" => "Ω",
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+/// Operators, relations, arrows and punctuation, rendered as `<mo>`
+pub(super) fn operator(name: &str) -> Option<&'static str> {
+    let symbol = match name {
+        "times" => "×", "cdot" => "⋅", "pm" => "±", "mp" => "∓", "div" => "÷",
+        "ast" => "∗", "star" => "⋆", "circ" => "∘", "bullet" => "∙",
+        "oplus" => "⊕", "ominus" => "⊖", "otimes" => "⊗", "odot" => "⊙",
+        "cup" => "∪", "cap" => "∩", "setminus" => "∖", "wedge" => "∧", "land" => "∧",
+        "vee" => "∨", "lor" => "∨", "neg" => "¬", "lnot" => "¬",
+        "leq" => "≤", "le" => "≤", "geq" => "≥", "ge" => "≥", "neq" => "≠", "ne" => "≠",
+        "ll" => "≪", "gg" => "≫", "approx" => "≈", "equiv" => "≡", "sim" => "∼",
+        "simeq" => "≃", "cong" => "≅", "propto" => "∝", "prec" => "≺", "succ" => "≻",
+        "in" => "∈", "notin" => "∉", "ni" => "∋", "subset" => "⊂", "supset" => "⊃",
+        "subseteq" => "⊆", "supseteq" => "⊇", "perp" => "⊥", "parallel" => "∥",
+        "mid" => "∣", "models" => "⊨", "vdash" => "⊢",
+        "forall" => "∀", "exists" => "∃", "nexists" => "∄",
+        "to" => "→", "rightarrow" => "→", "leftarrow" => "←", "gets" => "←",
+        "leftrightarrow" => "↔", "Rightarrow" => "⇒", "Leftarrow" => "⇐",
+        "Leftrightarrow" => "⇔", "implies" => "⟹", "impliedby" => "⟸", "iff" => "⟺",
+        "mapsto" => "↦", "longrightarrow" => "⟶", "longleftarrow" => "⟵",
+        "uparrow" => "↑", "downarrow" => "↓",
+        "ldots" => "…", "dots" => "…", "cdots" => "⋯", "vdots" => "⋮", "ddots" => "⋱",
+        "prime" => "′", "angle" => "∠", "triangle" => "△", "colon" => ":",
+        "langle" => "⟨", "rangle" => "⟩", "lfloor" => "⌊", "rfloor" => "⌋",
+        "lceil" => "⌈", "rceil" => "⌉", "vert" => "|", "Vert" => "‖", "|" => "‖",
+        "{" => "{", "}" => "}", "lbrace" => "{", "rbrace" => "}",
+        "#" => "#", "%" => "%", "&" => "&", "$" => "$", "_" => "_",
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+/// Large operators, with limits under and over them in display math
+pub(super) fn large_operator(name: &str) -> Option<&'static str> {
+    let symbol = match name {
+        "sum" => "∑", "prod" => "∏", "coprod" => "∐",
+        "bigcup" => "⋃", "bigcap" => "⋂", "bigvee" => "⋁", "bigwedge" => "⋀",
+        "bigoplus" => "⨁", "bigotimes" => "⨂", "bigodot" => "⨀",
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+/// Integrals, with limits as scripts
+pub(super) fn integral(name: &str) -> Option<&'static str> {
+    let symbol = match name {
+        "int" => "∫", "iint" => "∬", "iiint" => "∭", "oint" => "∮",
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+/// Functions written as upright words, like `\sin`
+pub(super) fn is_function(name: &str) -> bool {
+    matches!(name,
+        "sin" | "cos" | "tan" | "cot" | "sec" | "csc" | "arcsin" | "arccos" | "arctan"
+        | "sinh" | "cosh" | "tanh" | "coth" | "log" | "ln" | "lg" | "exp" | "det" | "dim"
+        | "ker" | "deg" | "gcd" | "hom" | "arg")
+}
+
+/// Functions with limits under them, like `\lim`
+pub(super) fn limit_function(name: &str) -> Option<&'static str> {
+    let word = match name {
+        "lim" => "lim", "max" => "max", "min" => "min", "sup" => "sup", "inf" => "inf",
+        "limsup" => "lim sup", "liminf" => "lim inf", "Pr" => "Pr",
+        _ => return None,
+    };
+    Some(word)
+}
+
+/// Accents above or below their argument, and whether they stretch
+pub(super) fn accent(name: &str) -> Option<(&'static str, bool, bool)> {
+    // (mark, over, stretchy)
+    let accent = match name {
+        "hat" => ("^", true, false), "widehat" => ("^", true, true),
+        "tilde" => ("~", true, false), "widetilde" => ("~", true, true),
+        "bar" => ("¯", true, false), "overline" => ("¯", true, true),
+        "vec" => ("→", true, false), "overrightarrow" => ("→", true, true),
+        "dot" => ("˙", true, false), "ddot" => ("¨", true, false),
+        "check" => ("ˇ", true, false), "breve" => ("˘", true, false),
+        "acute" => ("´", true, false), "grave" => ("`", true, false),
+        "underline" => ("_", false, true),
+        "overbrace" => ("⏞", true, true), "underbrace" => ("⏟", false, true),
+        _ => return None,
+    };
+    Some(accent)
+}
+
+/// Font commands and the `mathvariant` they select
+pub(super) fn font(name: &str) -> Option<&'static str> {
+    let variant = match name {
+        "mathrm" | "rm" => "normal",
+        "mathit" => "italic",
+        "mathbf" | "bf" => "bold",
+        "boldsymbol" | "bm" => "bold-italic",
+        "mathsf" => "sans-serif",
+        "mathtt" => "monospace",
+        "mathcal" => "script",
+        "mathbb" => "double-struck",
+        "mathfrak" => "fraktur",
+        _ => return None,
+    };
+    Some(variant)
+}
+
+/// Spacing commands and their width
+pub(super) fn space(name: &str) -> Option<&'static str> {
+    let width = match name {
+        "," | "thinspace" => "0.1667em",
+        ":" | ">" | "medspace" => "0.2222em",
+        ";" | "thickspace" => "0.2778em",
+        " " | "space" => "0.25em",
+        "quad" => "1em",
+        "qquad" => "2em",
+        "!" | "negthinspace" => "-0.1667em",
+        _ => return None,
+    };
+    Some(width)
+}
+
+/// Sizing commands for delimiters, like `\big`
+pub(super) fn delimiter_size(name: &str) -> Option<&'static str> {
+    let size = match name {
+        "big" | "bigl" | "bigr" | "bigm" => "1.2em",
+        "Big" | "Bigl" | "Bigr" | "Bigm" => "1.623em",
+        "bigg" | "biggl" | "biggr" | "biggm" => "2.047em",
+        "Bigg" | "Biggl" | "Biggr" | "Biggm" => "2.470em",
+        _ => return None,
+    };
+    Some(size)
+}
+
+/// Matrix environments and the delimiters around them
+pub(super) fn matrix_delimiters(environment: &str) -> Option<(&'static str, &'static str)> {
+    let delimiters = match environment {
+        "matrix" | "array" | "aligned" | "align" | "align*" | "gathered" | "gather" | "gather*"
+        | "split" | "smallmatrix" => ("", ""),
+        "pmatrix" => ("(", ")"),
+        "bmatrix" => ("[", "]"),
+        "Bmatrix" => ("{", "}"),
+        "vmatrix" => ("|", "|"),
+        "Vmatrix" => ("‖", "‖"),
+        "cases" => ("{", ""),
+        _ => return None,
+    };
+    Some(delimiters)
+}
@@ -2,7 +2,9 @@ pub mod renderer;
 pub mod toc;
 pub mod utils;
 pub mod engine;
-pub mod types;
+pub mod kramdown;
+pub mod links;
+pub mod math;
 
 pub use renderer::MarkdownRenderer;
  
\ No newline at end of file
@@ -1,73 +1,57 @@
-use crate::config::Config;
-use crate::markdown::engine::{create_comrak_options, render_markdown};
+use crate::config::{Config, KramdownConfig};
+use crate::markdown::engine::{create_comrak_options, render_markdown, CodeRendering};
+use crate::markdown::links::RelativeLinks;
+use crate::markdown::math::MathRenderer;
 use crate::markdown::renderer::syntax::SyntaxHighlighter;
 use crate::markdown::toc::{generate_toc, extract_headings, TocOptions};
-use regex::Regex;
-use lazy_static::lazy_static;
-
-lazy_static! {
-    // Regex for math equations
-    static ref MATH_INLINE_REGEX: Regex = Regex::new(r"\$(.+?)\$").unwrap();
-    static ref MATH_BLOCK_REGEX: Regex = Regex::new(r"\$\$([\s\S]+?)\$\$").unwrap();
-    
-    // Regex for typographical improvements
-    static ref SMART_QUOTES_REGEX: Regex = Regex::new(r#"(^|[-—/\(\[\{"""\s])[''](.+?)['']([-.,:;!?\)\]\}""\s]|$)"#).unwrap();
-    static ref EM_DASH_REGEX: Regex = Regex::new(r"---").unwrap();
-    static ref EN_DASH_REGEX: Regex = Regex::new(r"--").unwrap();
-    
-    // Regex for diagrams
-    static ref MERMAID_REGEX: Regex = Regex::new(r"```mermaid\s([\s\S]+?)```").unwrap();
-}
+use crate::builder::diagnostics;
+use crate::builder::profiler::{self, TemplateKind};
 
 /// Markdown renderer with syntax highlighting and extended features
 pub struct MarkdownRenderer<'a> {
     engine: String,
     options: comrak::Options<'a>,
+    kramdown: KramdownConfig,
     syntax_highlighter: SyntaxHighlighter,
     toc_options: TocOptions,
-    enable_math: bool,
     enable_diagrams: bool,
-    enable_typographic: bool,
+    math_renderer: MathRenderer,
+    relative_links: Option<RelativeLinks>,
 }
 
 impl<'a> MarkdownRenderer<'a> {
     /// Create a new markdown renderer from config
     pub fn new(config: &Config) -> Self {
         // Get comrak options
-        let options = create_comrak_options();
+        let options = create_comrak_options(config);
+        let kramdown = config.kramdown.clone().unwrap_or_default();
         
         // Create syntax highlighter
         let syntax_highlighter = SyntaxHighlighter::new();
         
-        // Set default TOC options
+        // TOC levels come from kramdown's toc_levels
         let toc_options = TocOptions {
-            min_level: 1,
-            max_level: 6,
+            min_level: kramdown.toc_levels.iter().min().map_or(1, |&level| level as usize),
+            max_level: kramdown.toc_levels.iter().max().map_or(6, |&level| level as usize),
             include_title: false,
             list_class: "toc".to_string(),
         };
         
-        // Check for markdown extensions in config
-        let enable_math = config.markdown_extensions.as_ref()
-            .map(|exts| exts.contains(&"math".to_string()))
-            .unwrap_or(false);
-            
-        let enable_diagrams = config.markdown_extensions.as_ref()
-            .map(|exts| exts.contains(&"diagrams".to_string()))
-            .unwrap_or(false);
-            
-        let enable_typographic = config.markdown_extensions.as_ref()
-            .map(|exts| exts.contains(&"typographic".to_string()))
-            .unwrap_or(true); // Enable by default
+        // Math and typography are comrak options, diagrams are code blocks
+        let enable_diagrams = config.markdown_extension("diagrams");
+        
+        // Math is rendered to MathML, cached in the cache directory
+        let math_renderer = MathRenderer::new(Some(&config.source.join(&config.cache_dir)));
         
         MarkdownRenderer {
             engine: "comrak".to_string(),
             options,
+            kramdown,
             syntax_highlighter,
             toc_options,
-            enable_math,
             enable_diagrams,
-            enable_typographic,
+            math_renderer,
+            relative_links: None,
         }
     }
     
@@ -88,7 +72,7 @@ impl<'a> MarkdownRenderer<'a> {
     
     /// Enable or disable math equation support
     pub fn set_math_enabled(&mut self, enabled: bool) {
-        self.enable_math = enabled;
+        self.options.extension.math_dollars = enabled;
     }
     
     /// Enable or disable diagram support
@@ -98,70 +82,12 @@ impl<'a> MarkdownRenderer<'a> {
     
     /// Enable or disable typographical improvements
     pub fn set_typographic_enabled(&mut self, enabled: bool) {
-        self.enable_typographic = enabled;
-    }
-    
-    /// Process math equations in the HTML content
-    fn process_math(&self, html: &str) -> String {
-        if !self.enable_math {
-            return html.to_string();
-        }
-        
-        // Process inline math
-        let html = MATH_INLINE_REGEX.replace_all(html, |caps: &regex::Captures| {
-            format!("<span class=\"math inline\">{}</span>", &caps[1])
-        }).to_string();
-        
-        // Process block math
-        let html = MATH_BLOCK_REGEX.replace_all(&html, |caps: &regex::Captures| {
-            format!("<div class=\"math display\">{}</div>", &caps[1])
-        }).to_string();
-        
-        html
+        self.options.parse.smart = enabled;
     }
     
-    /// Process diagrams in the HTML content
-    fn process_diagrams(&self, html: &str) -> String {
-        if !self.enable_diagrams {
-            return html.to_string();
-        }
-        
-        // Process mermaid diagrams
-        let html = MERMAID_REGEX.replace_all(html, |caps: &regex::Captures| {
-            format!(
-                "<div class=\"mermaid\">{}</div>
-                <script>
-                document.addEventListener('DOMContentLoaded', function() {{
-                  if (typeof mermaid !== 'undefined') {{
-                    mermaid.initialize();
-                  }}
-                }});
-                </script>",
-                &caps[1]
-            )
-        }).to_string();
-        
-        html
-    }
-    
-    /// Apply typographical improvements to the HTML content
-    fn apply_typography(&self, html: &str) -> String {
-        if !self.enable_typographic {
-            return html.to_string();
-        }
-        
-        // Replace smart quotes
-        let html = SMART_QUOTES_REGEX.replace_all(html, |caps: &regex::Captures| {
-            format!("{}\"{}\"{}",&caps[1].to_string(), &caps[2].to_string(), &caps[3].to_string())
-        }).to_string();
-        
-        // Replace em dashes
-        let html = EM_DASH_REGEX.replace_all(&html, "—").to_string();
-        
-        // Replace en dashes
-        let html = EN_DASH_REGEX.replace_all(&html, "–").to_string();
-        
-        html
+    /// Rewrite relative links between sources to their URLs
+    pub fn set_relative_links(&mut self, relative_links: RelativeLinks) {
+        self.relative_links = Some(relative_links);
     }
     
     /// Generate a table of contents from the HTML content
@@ -181,32 +107,19 @@ impl<'a> MarkdownRenderer<'a> {
     
     /// Render Markdown content to HTML with all enabled features
     pub fn render(&self, content: &str) -> String {
-        // First do basic markdown rendering
-        let mut html = render_markdown(content, &self.options);
-        
-        // Process math equations if enabled
-        if self.enable_math {
-            html = self.process_math(&html);
-        }
-        
-        // Process diagrams if enabled
-        if self.enable_diagrams {
-            html = self.process_diagrams(&html);
-        }
-        
-        // Apply typographical improvements if enabled
-        if self.enable_typographic {
-            html = self.apply_typography(&html);
-        }
-        
-        // Then process code blocks for syntax highlighting if needed
-        match self.syntax_highlighter.highlight_html(&html) {
-            Ok(highlighted) => highlighted,
-            Err(e) => {
-                // If highlighting fails, just return the original HTML
-                log::warn!("Syntax highlighting failed: {}", e);
-                html
-            }
+        let file = diagnostics::current_file();
+        let _timer = profiler::start(TemplateKind::Markdown, file.to_string_lossy());
+        
+        // Math, typography, diagrams and highlighting are all done on the AST
+        let code = CodeRendering {
+            highlighter: Some(self.syntax_highlighter.clone()),
+            diagrams: self.enable_diagrams,
+            math: self.options.extension.math_dollars.then(|| self.math_renderer.clone()),
+        };
+        let html = render_markdown(content, &self.options, &self.kramdown, &code);
+        match &self.relative_links {
+            Some(relative_links) => relative_links.rewrite(&html, &file),
+            None => html,
         }
     }
     
@@ -226,6 +139,7 @@ pub fn markdownify<'a>(content: &str, renderer: &MarkdownRenderer<'a>) -> String
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::TestSite;
     
     #[test]
     fn test_markdown_rendering() {
@@ -235,7 +149,7 @@ mod tests {
         let markdown = "# Hello, World!\n\nThis is a **bold** statement.";
         let html = renderer.render(markdown);
         
-        assert!(html.contains("<h1>"));
+        assert!(html.contains("<h1 id=\"hello-world\">"));
         assert!(html.contains("<strong>bold</strong>"));
     }
     
@@ -266,7 +180,8 @@ mod tests {
     
     #[test]
     fn test_math_rendering() {
-        let mut config = Config::default();
+        let site = TestSite::new("math-rendering");
+        let mut config = site.config();
         if let Some(ref mut exts) = config.markdown_extensions {
             exts.push("math".to_string());
         } else {
@@ -280,5 +195,31 @@ mod tests {
         
         assert!(html.contains("<span class=\"math inline\">"));
         assert!(html.contains("<div class=\"math display\">"));
+        assert!(html.contains("<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\">"));
+        assert!(!html.contains("<p><div"));
+        
+        // Fenced math blocks are display math, invalid math is left as LaTeX
+        let html = renderer.render("```math\na^2 + b^2 = c^2\n```\n\n$\\frac{1}{$\n");
+        assert!(html.contains("<div class=\"math display\"><math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\">"));
+        assert!(html.contains("<span class=\"math inline\">\\frac{1}{</span>"));
     }
-} 
\ No newline at end of file
+    
+    #[test]
+    fn test_code_is_left_alone() {
+        let site = TestSite::new("math-code");
+        let config = Config {
+            markdown_extensions: Some(vec!["math".to_string(), "diagrams".to_string()]),
+            ..site.config()
+        };
+        let renderer = MarkdownRenderer::new(&config);
+        
+        let markdown = "It's $x$ -- or `$y$ -- 'z'`.\n\n```\n$a$ -- \"b\"\n```\n\n```mermaid\ngraph TD; A-->B\n```";
+        let html = renderer.render(markdown);
+        
+        assert!(html.contains("It\u{2019}s <span class=\"math inline\"><math xmlns=\"http://www.w3.org/1998/Math/MathML\"><semantics><mi>x</mi>"));
+        assert!(html.contains("</math></span> \u{2013} or <code>$y$ -- 'z'</code>."));
+        assert!(html.contains("$a$ -- "));
+        assert!(!html.contains("math inline\">a"));
+        assert!(html.contains("<div class=\"mermaid\">graph TD; A--&gt;B\n</div>"));
+    }
+}
@@ -1,4 +1,5 @@
 mod syntax;
 mod markdown_renderer;
 
-pub use markdown_renderer::MarkdownRenderer; 
\ No newline at end of file
+pub use markdown_renderer::MarkdownRenderer;
+pub use syntax::SyntaxHighlighter; 
\ No newline at end of file
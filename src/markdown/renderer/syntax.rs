@@ -3,11 +3,9 @@ use syntect::highlighting::ThemeSet;
 use syntect::parsing::SyntaxSet;
 use syntect::html::{ClassedHTMLGenerator, ClassStyle};
 use syntect::util::LinesWithEndings;
-use regex::Regex;
 
-use crate::markdown::types::BoxResult;
-
-/// Component for syntax highlighting code blocks in HTML
+/// Component for syntax highlighting code blocks
+#[derive(Clone)]
 pub struct SyntaxHighlighter {
     syntax_set: Arc<SyntaxSet>,
     theme_set: Arc<ThemeSet>,
@@ -45,20 +43,6 @@ impl SyntaxHighlighter {
             .collect()
     }
     
-    /// Process HTML content to add syntax highlighting to code blocks
-    pub fn highlight_html(&self, html: &str) -> BoxResult<String> {
-        let pre_regex = Regex::new(r#"<pre><code( class="language-([^"]+)")?>([^<]+)</code></pre>"#)?;
-        
-        let html_highlighted = pre_regex.replace_all(html, |caps: &regex::Captures| {
-            let code = html_escape::decode_html_entities(&caps[3]).to_string();
-            let lang = caps.get(2).map(|m| m.as_str()).unwrap_or("text");
-            
-            self.highlight_code(&code, lang)
-        });
-        
-        Ok(html_highlighted.to_string())
-    }
-    
     /// Highlight a specific code block with specified language
     pub fn highlight_code(&self, code: &str, lang: &str) -> String {
         // Get the theme
@@ -105,14 +89,4 @@ mod tests {
         assert!(html.contains("<div class=\"highlight\">"));
         assert!(html.contains("<pre class=\"highlight rust\">"));
     }
-    
-    #[test]
-    fn test_html_processing() {
-        let highlighter = SyntaxHighlighter::new();
-        let html = "<pre><code class=\"language-rust\">fn main() {\n    println!(\"Hello\");\n}</code></pre>";
-        let processed = highlighter.highlight_html(html).unwrap();
-        
-        assert!(processed.contains("<div class=\"highlight\">"));
-        assert!(processed.contains("<pre class=\"highlight rust\">"));
-    }
-} 
\ No newline at end of file
+}
\ No newline at end of file
@@ -1,5 +1,5 @@
 use regex::Regex;
-use crate::markdown::types::BoxResult;
+use crate::utils::error::BoxResult;
 
 /// Generate a table of contents from Markdown content
 pub fn generate_toc(markdown: &str) -> BoxResult<String> {
@@ -1,4 +1,4 @@
-use crate::markdown::types::BoxResult;
+use crate::utils::error::BoxResult;
 use regex::Regex;
 use lazy_static::lazy_static;
 use serde::{Serialize, Deserialize};
@@ -1,4 +0,0 @@
-use std::error::Error;
-
-/// Common result type for markdown operations
-pub type BoxResult<T> = Result<T, Box<dyn Error>>; 
\ No newline at end of file
@@ -12,7 +12,7 @@ use std::sync::Arc;
 use std::fs;
 use log::info;
 
-use crate::server::handlers::{handle_not_found, create_directory_listing};
+use crate::server::handlers::{handle_not_found, handle_redirect, create_directory_listing};
 use crate::server::config::ServerConfig;
 
 // App state that will be shared with handlers
@@ -20,6 +20,7 @@ use crate::server::config::ServerConfig;
 struct AppState {
     show_dir_listing: bool,
     base_url: String,
+    site_url: String,
     destination: PathBuf,
 }
 
@@ -38,6 +39,11 @@ async fn serve_file_handler(
 
     info!("🔗 [SERVER] Decoded path: {}", decoded_path);
 
+    // Old URLs of the site redirect like they would once deployed
+    if let Some(response) = handle_redirect(&state.destination, &decoded_path, &state.site_url) {
+        return response.into_response();
+    }
+
     let clean_path = decoded_path.trim_start_matches('/').trim_end_matches('/');
 
     // Build the requested file path
@@ -196,6 +202,7 @@ pub fn create_app(
     let state = AppState {
         show_dir_listing: config.show_dir_listing,
         base_url: config.baseurl.clone(),
+        site_url: config.site_url.clone(),
         destination,
     };
 
@@ -24,6 +24,9 @@ pub struct ServerConfig {
     /// Base URL to use for serving the site
     pub baseurl: String,
     
+    /// URL of the deployed site, whose redirects stay on the server
+    pub site_url: String,
+    
     /// SSL certificate path
     pub ssl_cert: Option<PathBuf>,
     
@@ -56,6 +59,7 @@ impl Default for ServerConfig {
             open_url: false,
             detach: false,
             baseurl: "".to_string(),
+            site_url: "".to_string(),
             ssl_cert: None,
             ssl_key: None,
             show_dir_listing: false,
@@ -97,6 +101,12 @@ impl ServerConfig {
         self
     }
     
+    /// Set the URL of the deployed site
+    pub fn with_site_url(mut self, site_url: &str) -> Self {
+        self.site_url = site_url.to_string();
+        self
+    }
+    
     /// Set the LiveReload port
     pub fn with_livereload_port(mut self, port: u16) -> Self {
         self.livereload_port = Some(port);
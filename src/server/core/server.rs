@@ -54,7 +54,7 @@ pub async fn serve_with_watch(
     // First perform initial build unless skipped
     if !server_config.skip_initial_build {
         info!("Building site before serving...");
-        match build_site(config, include_drafts, include_unpublished) {
+        match build_site(config, include_drafts, include_unpublished).result {
             Ok(_) => info!("Site built successfully"),
             Err(e) => {
                 error!("Failed to build site: {}", e);
@@ -251,7 +251,7 @@ pub async fn serve(
     // Build the site first unless skipped
     if !server_config.skip_initial_build {
         info!("Building site before serving...");
-        match build_site(config, include_drafts, include_unpublished) {
+        match build_site(config, include_drafts, include_unpublished).result {
             Ok(_) => info!("Site built successfully"),
             Err(e) => {
                 error!("Failed to build site: {}", e);
@@ -31,12 +31,12 @@ pub fn handle_file_changes(
                     std::thread::sleep(min_delay - elapsed);
                     
                     // Drain any additional events that came in during sleep
-                    while let Ok(_) = rx.try_recv() {}
+                    while rx.try_recv().is_ok() {}
                 }
                 
                 // Rebuild the site
                 info!("File change detected, rebuilding site...");
-                if let Err(e) = build_site(config, include_drafts, include_unpublished) {
+                if let Err(e) = build_site(config, include_drafts, include_unpublished).result {
                     error!("Error rebuilding site: {}", e);
                 }
                 
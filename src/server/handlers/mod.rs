@@ -1,3 +1,3 @@
 mod static_files;
 
-pub use static_files::{create_directory_listing, handle_not_found}; 
\ No newline at end of file
+pub use static_files::{create_directory_listing, handle_not_found, handle_redirect}; 
\ No newline at end of file
@@ -7,6 +7,7 @@ use axum::{
 use tower_http::services::ServeDir;
 use std::path::{Path as FilePath, PathBuf};
 use log::{debug, error, info};
+use std::collections::HashMap;
 use std::fs;
 use std::io::ErrorKind;
 
@@ -95,6 +96,42 @@ pub fn handle_not_found(root_dir: &FilePath) -> Response {
     (StatusCode::NOT_FOUND, "Page not found").into_response()
 }
 
+/// Redirect a request listed in the site's redirects.json with a 301
+///
+/// Targets on the site itself lose `site_url`, so that they stay on the
+/// development server.
+pub fn handle_redirect(root_dir: &FilePath, request_path: &str, site_url: &str) -> Option<Response> {
+    let content = fs::read_to_string(root_dir.join("redirects.json")).ok()?;
+    let redirects: HashMap<String, String> = match serde_json::from_str(&content) {
+        Ok(redirects) => redirects,
+        Err(e) => {
+            error!("Error reading redirects.json: {}", e);
+            return None;
+        }
+    };
+
+    let requested = redirect_key(request_path);
+    let target = redirects.iter().find(|(from, _)| redirect_key(from) == requested)?.1;
+    let site_url = site_url.trim_end_matches('/');
+    let location = match target.strip_prefix(site_url) {
+        Some(path) if !site_url.is_empty() && path.starts_with('/') => path,
+        _ => target.as_str(),
+    };
+
+    info!("↪️ [VERBOSE] Redirecting {} to {}", request_path, location);
+    Some(Response::builder()
+        .status(StatusCode::MOVED_PERMANENTLY)
+        .header(header::LOCATION, location)
+        .body(Body::empty())
+        .unwrap())
+}
+
+/// A path compared for redirects, with or without its index or trailing slash
+fn redirect_key(path: &str) -> &str {
+    let path = path.strip_suffix("index.html").unwrap_or(path);
+    path.trim_matches('/')
+}
+
 /// Handle directory listing requests
 pub fn create_directory_listing(dir: &FilePath, site_root: &FilePath) -> Result<Response, std::io::Error> {
     if !dir.is_dir() {
@@ -0,0 +1,78 @@
+//! A site in a temporary directory, shared by the tests of every module
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::builder::site::{BuildOutcome, Site};
+use crate::config::Config;
+
+/// Tests of one process share a counter, so each site gets its own directory
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A site whose source is a fresh temporary directory, removed when dropped
+///
+/// Output goes to `_site` inside the source directory, which is excluded
+/// from reading like in Jekyll.
+pub struct TestSite {
+    dir: PathBuf,
+}
+
+impl TestSite {
+    /// An empty site, with `name` in the directory name for debugging
+    pub fn new(name: &str) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("rustyll-{}-test-{}-{}", name, std::process::id(), id));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        TestSite { dir }
+    }
+
+    /// The source directory
+    pub fn source(&self) -> &Path {
+        &self.dir
+    }
+
+    /// A path in the source directory
+    pub fn path(&self, path: &str) -> PathBuf {
+        self.dir.join(path)
+    }
+
+    /// A path in the destination directory
+    pub fn output(&self, path: &str) -> PathBuf {
+        self.dir.join("_site").join(path)
+    }
+
+    /// Write a source file, creating its directories
+    pub fn write(&self, path: &str, content: &str) -> &Self {
+        let path = self.path(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+        self
+    }
+
+    /// Read an output file
+    pub fn read(&self, path: &str) -> String {
+        fs::read_to_string(self.output(path)).unwrap_or_else(|e| panic!("Cannot read output {}: {}", path, e))
+    }
+
+    /// The default configuration with this site's source and destination
+    pub fn config(&self) -> Config {
+        Config {
+            source: self.dir.clone(),
+            destination: self.dir.join("_site"),
+            ..Config::default()
+        }
+    }
+
+    /// Build the site with `config`, which must succeed
+    pub fn build(&self, config: Config) -> BuildOutcome {
+        Site::new(config).process().unwrap()
+    }
+}
+
+impl Drop for TestSite {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt::Write;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::builder::render_state::{self, RenderState};
+
+/// Date formats with an explicit offset, tried in order
+const OFFSET_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S %z", "%Y-%m-%d %H:%M:%S %:z", "%Y-%m-%d %H:%M:%S%.f %z", "%Y-%m-%dT%H:%M:%S%z"];
+
+/// Date formats without an offset, interpreted in the site's timezone
+const LOCAL_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M"];
+
+/// Month and day names for formatting dates, e.g. from a locale data file
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateNames {
+    /// Month names, January first
+    pub months: Vec<String>,
+    /// Abbreviated month names, January first
+    pub abbr_months: Vec<String>,
+    /// Day names, Sunday first
+    pub days: Vec<String>,
+    /// Abbreviated day names, Sunday first
+    pub abbr_days: Vec<String>,
+}
+
+/// Parse the timezone of a site's `timezone` setting, like Jekyll's TZ
+///
+/// Takes an IANA name such as `Europe/Berlin`, or `None` for UTC.
+pub fn parse_timezone(name: Option<&str>) -> Result<Tz, String> {
+    match name {
+        Some(name) => name.parse::<Tz>().map_err(|_| format!("Unknown timezone '{}'", name)),
+        None => Ok(Tz::UTC),
+    }
+}
+
+/// The timezone dates are interpreted and displayed in
+///
+/// This is the timezone of the site being built on the current thread, or
+/// UTC outside of a build.
+pub fn timezone() -> Tz {
+    render_state::with(|state| state.map_or(Tz::UTC, RenderState::timezone))
+}
+
+/// Convert a date to the site's timezone
+pub fn local(date: DateTime<Utc>) -> DateTime<Tz> {
+    date.with_timezone(&timezone())
+}
+
+/// Interpret a date and time without an offset in the site's timezone
+pub fn from_local(naive: NaiveDateTime) -> DateTime<Utc> {
+    from_local_in(naive, timezone())
+}
+
+fn from_local_in(naive: NaiveDateTime, timezone: Tz) -> DateTime<Utc> {
+    // Times skipped by a DST change are moved forward like Ruby does
+    timezone.from_local_datetime(&naive).earliest()
+        .or_else(|| timezone.from_local_datetime(&(naive + chrono::Duration::hours(1))).earliest())
+        .map(|date| date.with_timezone(&Utc))
+        .unwrap_or_else(|| naive.and_utc())
+}
+
+/// Midnight of a day in the site's timezone
+pub fn local_midnight(date: NaiveDate) -> DateTime<Utc> {
+    from_local(date.and_hms_opt(0, 0, 0).unwrap())
+}
+
+/// Parse a date like Jekyll does for front matter and filters
+///
+/// Dates without an offset are in the site's timezone, and a date without
+/// a time is midnight.
+pub fn parse_date(text: &str) -> Option<DateTime<Utc>> {
+    parse_date_in(text, timezone())
+}
+
+fn parse_date_in(text: &str, timezone: Tz) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in OFFSET_FORMATS {
+        if let Ok(date) = DateTime::parse_from_str(text, format) {
+            return Some(date.with_timezone(&Utc));
+        }
+    }
+    if let Ok(date) = DateTime::parse_from_rfc2822(text) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in LOCAL_FORMATS {
+        if let Ok(date) = NaiveDateTime::parse_from_str(text, format) {
+            return Some(from_local_in(date, timezone));
+        }
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()
+        .map(|date| from_local_in(date.and_hms_opt(0, 0, 0).unwrap(), timezone))
+}
+
+/// The English ordinal suffix of a day, e.g. "st" for 1
+pub fn ordinal_suffix(day: u32) -> &'static str {
+    match (day % 100, day % 10) {
+        (11..=13, _) => "th",
+        (_, 1) => "st",
+        (_, 2) => "nd",
+        (_, 3) => "rd",
+        _ => "th",
+    }
+}
+
+/// Format a date with Ruby's strftime directives
+///
+/// Month and day names come from `names` when given. Ruby's `%L`, `%N`
+/// and `%^` (uppercase) are supported on top of chrono's directives, and
+/// unknown directives are kept as they are.
+pub fn strftime<T: TimeZone>(date: &DateTime<T>, format: &str, names: Option<&DateNames>) -> String
+where
+    T::Offset: std::fmt::Display,
+{
+    let mut formatted = String::with_capacity(format.len() + 16);
+    let mut rest = format;
+
+    while let Some(start) = rest.find('%') {
+        formatted.push_str(&rest[..start]);
+        let directive = &rest[start..];
+
+        // A directive is `%`, optional flags, an optional width and a conversion
+        let flags_end = directive[1..].find(|c: char| !"-_0^#:".contains(c) && !c.is_ascii_digit())
+            .map_or(directive.len(), |i| i + 1);
+        let length = directive[flags_end..].chars().next().map_or(flags_end, |c| flags_end + c.len_utf8());
+        let (spec, flags, conversion) = (&directive[..length], &directive[1..flags_end], directive[flags_end..length].chars().next());
+
+        let upper = flags.contains('^');
+        let name = |list: Option<&Vec<String>>, index: usize| list.and_then(|list| list.get(index)).cloned();
+        let text = match conversion {
+            Some('B') => name(names.map(|n| &n.months), date.month0() as usize),
+            Some('b') | Some('h') => name(names.map(|n| &n.abbr_months), date.month0() as usize),
+            Some('A') => name(names.map(|n| &n.days), date.weekday().num_days_from_sunday() as usize),
+            Some('a') => name(names.map(|n| &n.abbr_days), date.weekday().num_days_from_sunday() as usize),
+            Some('L') => Some(format!("{:03}", date.timestamp_subsec_millis())),
+            Some('N') => Some(format!("{:09}", date.timestamp_subsec_nanos())),
+            _ => None,
+        };
+        let text = text.unwrap_or_else(|| {
+            let chrono_spec = spec.replace('^', "");
+            let valid = !StrftimeItems::new(&chrono_spec).any(|item| matches!(item, Item::Error));
+            let mut text = String::new();
+            if !valid || write!(text, "{}", date.format(&chrono_spec)).is_err() {
+                return spec.to_string();
+            }
+            text
+        });
+
+        if upper {
+            formatted.push_str(&text.to_uppercase());
+        } else {
+            formatted.push_str(&text);
+        }
+        rest = &directive[length..];
+    }
+
+    formatted.push_str(rest);
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dates_in_site_timezone() {
+        let timezone: Tz = "America/New_York".parse().unwrap();
+
+        // A post published late in the evening stays on its day
+        let date = parse_date_in("2024-01-01 23:30:00", timezone).unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-01-02T04:30:00+00:00");
+        assert_eq!(strftime(&date.with_timezone(&timezone), "%Y-%m-%d %H:%M %z", None), "2024-01-01 23:30 -0500");
+
+        let date = parse_date_in("2024-07-04", timezone).unwrap();
+        assert_eq!(strftime(&date.with_timezone(&timezone), "%^b %-d %A %L", None), "JUL 4 Thursday 000");
+
+        let date = parse_date_in("2024-01-01 23:30:00 +0100", timezone).unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-01-01T22:30:00+00:00");
+        assert_eq!(parse_date_in("2024-01-01 23:30:00", Tz::UTC).unwrap().to_rfc3339(), "2024-01-01T23:30:00+00:00");
+        assert_eq!(parse_timezone(Some("America/New_York")), Ok(timezone));
+        assert!(parse_timezone(Some("Mars/Olympus_Mons")).is_err());
+    }
+
+    #[test]
+    fn test_strftime_names_and_ordinals() {
+        let names = DateNames {
+            months: ["janvier", "février", "mars"].iter().map(|s| s.to_string()).collect(),
+            abbr_months: Vec::new(),
+            days: Vec::new(),
+            abbr_days: Vec::new(),
+        };
+        let date = Utc.with_ymd_and_hms(2024, 2, 3, 0, 0, 0).unwrap();
+        assert_eq!(strftime(&date, "%d %B %Y, %b %Q", Some(&names)), "03 février 2024, Feb %Q");
+        assert_eq!([1, 2, 3, 4, 11, 12, 13, 21, 22, 23].map(ordinal_suffix),
+                   ["st", "nd", "rd", "th", "th", "th", "th", "st", "nd", "rd"]);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod fs;
 pub mod path;
-pub mod error; 
\ No newline at end of file
+pub mod error;
+pub mod date;
\ No newline at end of file